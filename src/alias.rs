@@ -0,0 +1,71 @@
+//! User-defined command aliases from `[aliases]` in `.sleek.toml`, e.g.
+//! `t = "test --workspace --all-features"`.
+
+use anyhow::{bail, Result};
+use colored::*;
+use std::collections::BTreeMap;
+
+use crate::output;
+
+/// Expands `name` into the cargo command and arguments it stands for, `None` if it is
+/// not an alias. An alias may start with another alias; cycles are rejected.
+pub fn resolve_alias(
+    name: &str,
+    aliases: &BTreeMap<String, String>,
+) -> Result<Option<Vec<String>>> {
+    let Some(expansion) = aliases.get(name) else {
+        return Ok(None);
+    };
+    let mut chain = vec![name.to_string()];
+    let mut words: Vec<String> = expansion.split_whitespace().map(String::from).collect();
+    while let Some(first) = words.first().cloned() {
+        let Some(next) = aliases.get(&first) else {
+            break;
+        };
+        if chain.contains(&first) {
+            chain.push(first);
+            bail!("Alias `{}` refers to itself: {}", name, chain.join(" → "));
+        }
+        chain.push(first);
+        let rest = words.split_off(1);
+        words = next.split_whitespace().map(String::from).collect();
+        words.extend(rest);
+    }
+    if words.is_empty() {
+        bail!("Alias `{}` is empty", name);
+    }
+    Ok(Some(words))
+}
+
+/// The aliases section appended to `cargo sleek --help`, `None` when there are none.
+pub fn aliases_help(aliases: &BTreeMap<String, String>) -> Option<String> {
+    if aliases.is_empty() {
+        return None;
+    }
+    let width = aliases.keys().map(String::len).max().unwrap_or(0);
+    let mut help = String::from("Aliases (from .sleek.toml):");
+    for (name, expansion) in aliases {
+        help.push_str(&format!("\n  {:<width$}  cargo {}", name, expansion));
+    }
+    Some(help)
+}
+
+/// Prints the configured aliases with their expansions.
+pub fn show_aliases(aliases: &BTreeMap<String, String>) {
+    if aliases.is_empty() {
+        output::line(
+            "🔗 No aliases configured. Add e.g. `[aliases] t = \"test --workspace\"` to .sleek.toml"
+                .yellow(),
+        );
+        return;
+    }
+    output::line("🔗 Aliases:".bold().cyan());
+    let width = aliases.keys().map(String::len).max().unwrap_or(0);
+    for (name, expansion) in aliases {
+        output::line(format!(
+            "   {:<width$}  →  cargo {}",
+            name.green().bold(),
+            expansion
+        ));
+    }
+}
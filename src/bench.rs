@@ -0,0 +1,314 @@
+//! Benchmark results from `cargo bench`, tracked across commits (`bench`).
+
+use anyhow::{bail, Context, Result};
+use chrono::DateTime;
+use colored::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::executor;
+use crate::history::{self, BenchRecord};
+use crate::metadata;
+use crate::output;
+use crate::stats;
+
+/// Slowdown, in percent, above which a benchmark is reported as regressed.
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 5.0;
+
+/// Width of the bars in `bench --history`.
+const TREND_WIDTH: usize = 40;
+
+/// Parses libtest bench lines: `test parse ... bench:       1,234 ns/iter (+/- 56)`.
+/// Returns nanoseconds per iteration by benchmark name.
+pub fn parse_libtest_benches(output: &str) -> BTreeMap<String, f64> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("test ")?;
+            let (name, result) = rest.split_once(" ... bench:")?;
+            let value = result.trim().split(" ns/iter").next()?.replace(',', "");
+            Some((name.trim().to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct Estimate {
+    point_estimate: f64,
+}
+
+#[derive(Deserialize)]
+struct Estimates {
+    median: Estimate,
+}
+
+#[derive(Deserialize)]
+struct CriterionBenchmark {
+    full_id: String,
+}
+
+/// Median, in nanoseconds, from a criterion `estimates.json`.
+pub fn parse_criterion_estimates(json: &str) -> Option<f64> {
+    serde_json::from_str::<Estimates>(json)
+        .ok()
+        .map(|estimates| estimates.median.point_estimate)
+}
+
+fn find_new_dirs(dir: &Path, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if entry.file_name() == "new" {
+            found.push(path);
+        } else {
+            find_new_dirs(&path, found);
+        }
+    }
+}
+
+/// Medians criterion wrote under `criterion_dir` (usually `target/criterion`) at or after
+/// `since`, keyed by the benchmark's full id.
+pub fn load_criterion_results(criterion_dir: &Path, since: SystemTime) -> BTreeMap<String, f64> {
+    let mut dirs = Vec::new();
+    find_new_dirs(criterion_dir, &mut dirs);
+    let mut results = BTreeMap::new();
+    for dir in dirs {
+        let estimates = dir.join("estimates.json");
+        let fresh = fs::metadata(&estimates)
+            .and_then(|meta| meta.modified())
+            .is_ok_and(|modified| modified >= since);
+        if !fresh {
+            continue;
+        }
+        let Some(median) = fs::read_to_string(&estimates)
+            .ok()
+            .and_then(|json| parse_criterion_estimates(&json))
+        else {
+            continue;
+        };
+        let name = fs::read_to_string(dir.join("benchmark.json"))
+            .ok()
+            .and_then(|json| serde_json::from_str::<CriterionBenchmark>(&json).ok())
+            .map(|bench| bench.full_id)
+            .or_else(|| {
+                let relative = dir.parent()?.strip_prefix(criterion_dir).ok()?;
+                Some(relative.to_string_lossy().replace('\\', "/"))
+            });
+        if let Some(name) = name {
+            results.insert(name, median);
+        }
+    }
+    results
+}
+
+/// One benchmark compared with the previous recorded run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchDelta {
+    pub name: String,
+    pub previous_ns: Option<f64>,
+    pub current_ns: f64,
+}
+
+impl BenchDelta {
+    /// Change against the previous run, in percent (positive is slower).
+    pub fn percent(&self) -> Option<f64> {
+        self.previous_ns
+            .filter(|previous| *previous > 0.0)
+            .map(|previous| (self.current_ns - previous) / previous * 100.0)
+    }
+
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.percent().is_some_and(|percent| percent > threshold)
+    }
+}
+
+/// Pairs each benchmark of `current` with its median in `previous`.
+pub fn compare_benches(previous: Option<&BenchRecord>, current: &BenchRecord) -> Vec<BenchDelta> {
+    current
+        .results
+        .iter()
+        .map(|(name, current_ns)| BenchDelta {
+            name: name.clone(),
+            previous_ns: previous.and_then(|p| p.results.get(name).copied()),
+            current_ns: *current_ns,
+        })
+        .collect()
+}
+
+/// Formats nanoseconds with a unit that keeps the number readable.
+pub fn format_ns(ns: f64) -> String {
+    if ns >= 1e9 {
+        format!("{:.2} s", ns / 1e9)
+    } else if ns >= 1e6 {
+        format!("{:.2} ms", ns / 1e6)
+    } else if ns >= 1e3 {
+        format!("{:.2} µs", ns / 1e3)
+    } else {
+        format!("{:.1} ns", ns)
+    }
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+/// Runs `cargo bench`, records the medians and compares them with the previous run.
+/// Returns `false` when a benchmark regressed by more than `threshold` percent.
+pub fn run_bench(
+    history_path: &Path,
+    stats_path: &Path,
+    args: &[String],
+    threshold: f64,
+) -> Result<bool> {
+    output::status("🏎️  Running cargo bench...");
+    let metadata = metadata::cargo_metadata(Path::new("."))?;
+    let started = SystemTime::now();
+    let mut child = Command::new(executor::cargo_bin())
+        .arg("bench")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo bench"))?;
+    // Echo benchmark progress as it happens while keeping it for parsing.
+    let mut stdout = String::new();
+    if let Some(pipe) = child.stdout.take() {
+        for line in BufReader::new(pipe).lines() {
+            let line = line.context("Failed to read cargo bench output")?;
+            println!("{}", line);
+            stdout.push_str(&line);
+            stdout.push('\n');
+        }
+    }
+    let status = child.wait().context("Failed to wait for cargo bench")?;
+    stats::track_command_result(stats_path, "bench", status.success())?;
+    if !status.success() {
+        bail!("cargo bench failed");
+    }
+
+    let mut results = load_criterion_results(&metadata.target_directory.join("criterion"), started);
+    if results.is_empty() {
+        results = parse_libtest_benches(&stdout);
+    }
+    if results.is_empty() {
+        output::line("🏎️  No benchmark results found (criterion or libtest output)".yellow());
+        return Ok(true);
+    }
+
+    let record = BenchRecord {
+        timestamp: started.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+        commit: history::git_commit(),
+        results,
+    };
+    let mut history = history::load_history(history_path);
+    let previous = history.benches.last();
+    let deltas = compare_benches(previous, &record);
+
+    output::line(format!("\n{}", "📊 Benchmarks:".bold().cyan()));
+    if let Some(previous) = previous {
+        output::line(format!(
+            "   compared with {}{}",
+            format_timestamp(previous.timestamp),
+            previous
+                .commit
+                .as_ref()
+                .map(|c| format!(" ({})", c))
+                .unwrap_or_default()
+        ));
+    }
+    output::line(format!(
+        "{:<40} {:>12} {:>12} {:>9}",
+        "Benchmark", "Previous", "Current", "Change"
+    ));
+    for delta in &deltas {
+        let row = format!(
+            "{:<40} {:>12} {:>12} {:>9}",
+            delta.name,
+            delta
+                .previous_ns
+                .map(format_ns)
+                .unwrap_or_else(|| "-".into()),
+            format_ns(delta.current_ns),
+            delta
+                .percent()
+                .map(|p| format!("{:+.1}%", p))
+                .unwrap_or_else(|| "new".into())
+        );
+        if delta.regressed(threshold) {
+            output::line(format!("{} {}", row.red(), "◀ regression".red()));
+        } else if delta.percent().is_some_and(|p| p < -threshold) {
+            output::line(row.green());
+        } else {
+            output::line(row);
+        }
+    }
+
+    let regressions = deltas.iter().filter(|d| d.regressed(threshold)).count();
+    if regressions > 0 {
+        output::line(
+            format!(
+                "\n⚠️  {} benchmark(s) slowed down by more than {}%",
+                regressions, threshold
+            )
+            .yellow(),
+        );
+    }
+    history.benches.push(record);
+    history::save_history(history_path, &history)?;
+    Ok(regressions == 0)
+}
+
+/// Prints a text trend of one benchmark's median across recorded runs.
+pub fn show_bench_history(history_path: &Path, name: &str) -> Result<()> {
+    let history = history::load_history(history_path);
+    let points: Vec<(&BenchRecord, f64)> = history
+        .benches
+        .iter()
+        .filter_map(|run| run.results.get(name).map(|ns| (run, *ns)))
+        .collect();
+    if points.is_empty() {
+        let known: Vec<&str> = history
+            .benches
+            .last()
+            .map(|run| run.results.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        bail!(
+            "No recorded results for benchmark `{}`{}",
+            name,
+            if known.is_empty() {
+                String::new()
+            } else {
+                format!("; known benchmarks: {}", known.join(", "))
+            }
+        );
+    }
+    let max = points.iter().map(|(_, ns)| *ns).fold(0.0, f64::max);
+    output::line(
+        format!("📈 {} over {} run(s):", name, points.len())
+            .bold()
+            .cyan(),
+    );
+    for (run, ns) in &points {
+        let len = ((ns / max.max(f64::EPSILON)) * TREND_WIDTH as f64).round() as usize;
+        output::line(format!(
+            "   {:<16} {:<9} {:>12} {}",
+            format_timestamp(run.timestamp),
+            run.commit.as_deref().unwrap_or("-"),
+            format_ns(*ns),
+            "█".repeat(len.max(1)).green()
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,161 @@
+//! Binary size contributors, by wrapping `cargo bloat`.
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Serialize;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::executor;
+use crate::output;
+use crate::performance::format_size;
+use crate::stats;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BloatEntry {
+    pub crate_name: String,
+    /// Function name; `None` in `--crates` mode.
+    pub name: Option<String>,
+    pub bytes: u64,
+    /// Share of the whole file, in percent.
+    pub file_percent: f64,
+    /// Share of the `.text` section, in percent.
+    pub text_percent: f64,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct BloatSummary {
+    pub entries: Vec<BloatEntry>,
+    pub text_bytes: Option<u64>,
+    pub file_bytes: Option<u64>,
+}
+
+/// Parses sizes as printed by cargo-bloat: `512B`, `13.4KiB`, `2.7MiB`.
+pub fn parse_bloat_size(text: &str) -> Option<u64> {
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: u64 = match unit {
+        "B" => 1,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => return None,
+    };
+    Some((number * multiplier as f64) as u64)
+}
+
+fn parse_percent(text: &str) -> Option<f64> {
+    text.strip_suffix('%')?.parse().ok()
+}
+
+/// Parses the table printed by `cargo bloat`, in function or `--crates` mode.
+pub fn parse_bloat_output(output: &str) -> BloatSummary {
+    let mut summary = BloatSummary::default();
+    for line in output.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(file), Some(text), Some(size)) = (
+            fields.next().and_then(parse_percent),
+            fields.next().and_then(parse_percent),
+            fields.next().and_then(parse_bloat_size),
+        ) else {
+            continue;
+        };
+        let rest: Vec<&str> = fields.collect();
+        let rest_text = rest.join(" ");
+        if rest_text.starts_with(".text section size") {
+            summary.text_bytes = Some(size);
+            summary.file_bytes = rest_text.rsplit(' ').next().and_then(parse_bloat_size);
+            continue;
+        }
+        if rest_text.starts_with("And ") {
+            // "And 1234 smaller methods. Use -n N to show more."
+            continue;
+        }
+        let (crate_name, name) = match rest.as_slice() {
+            [] => continue,
+            [krate] => (krate.to_string(), None),
+            [krate, name @ ..] => (krate.to_string(), Some(name.join(" "))),
+        };
+        summary.entries.push(BloatEntry {
+            crate_name,
+            name,
+            bytes: size,
+            file_percent: file,
+            text_percent: text,
+        });
+    }
+    summary
+}
+
+fn bloat_installed() -> bool {
+    Command::new(executor::cargo_bin())
+        .args(["bloat", "--version"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+pub fn run_bloat(stats_path: &Path, crates: bool, top: usize, json: bool) -> Result<()> {
+    if !bloat_installed() {
+        output::warning(
+            "⚠️  cargo-bloat is not installed. Install it with `cargo install cargo-bloat`."
+                .yellow(),
+        );
+        return Ok(());
+    }
+    if !json {
+        output::status("📦 Running cargo bloat --release...");
+    }
+    let mut cmd = Command::new(executor::cargo_bin());
+    cmd.args(["bloat", "--release", "-n", &top.to_string()]);
+    if crates {
+        cmd.arg("--crates");
+    }
+    let output = cmd
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo bloat"))?;
+    stats::track_command_result(stats_path, "bloat", output.status.success())?;
+    if !output.status.success() {
+        output::warning("❌ cargo bloat failed. Check logs for details.");
+        return Ok(());
+    }
+
+    let summary = parse_bloat_output(&String::from_utf8_lossy(&output.stdout));
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&summary).context("Failed to serialize summary")?
+        );
+        return Ok(());
+    }
+    if let (Some(text), Some(file)) = (summary.text_bytes, summary.file_bytes) {
+        output::line(format!(
+            "📏 File size {}, of which .text {}",
+            format_size(file),
+            format_size(text)
+        ));
+    }
+    let heading = if crates {
+        "🐘 Biggest crates:"
+    } else {
+        "🐘 Biggest functions:"
+    };
+    output::line(heading.bold().cyan());
+    for (i, entry) in summary.entries.iter().take(top).enumerate() {
+        let label = match &entry.name {
+            Some(name) => format!("{} ({})", name, entry.crate_name),
+            None => entry.crate_name.clone(),
+        };
+        output::line(format!(
+            "{:<4} {:>10} {:>6.1}%  {}",
+            i + 1,
+            format_size(entry.bytes),
+            entry.text_percent,
+            label
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,292 @@
+//! Measures what build caches save: sccache when it wraps rustc, otherwise cargo's
+//! incremental compilation cache.
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::executor;
+use crate::linker;
+use crate::memory;
+use crate::metadata;
+use crate::output;
+use crate::performance::{self, format_size};
+use crate::prompt;
+
+/// The `rustc-wrapper` in effect: `RUSTC_WRAPPER`, then `CARGO_BUILD_RUSTC_WRAPPER`, then
+/// `[build] rustc-wrapper` from cargo config files (closest first).
+pub fn rustc_wrapper(configs: &[String], env: Option<&str>) -> Option<String> {
+    if let Some(wrapper) = env {
+        // An empty RUSTC_WRAPPER disables a configured wrapper.
+        return (!wrapper.is_empty()).then(|| wrapper.to_string());
+    }
+    configs.iter().find_map(|text| {
+        let config = text.parse::<toml::Table>().ok()?;
+        config
+            .get("build")?
+            .get("rustc-wrapper")?
+            .as_str()
+            .filter(|wrapper| !wrapper.is_empty())
+            .map(String::from)
+    })
+}
+
+/// Whether `wrapper` runs sccache, e.g. `sccache` or `/usr/local/bin/sccache.exe`.
+pub fn is_sccache(wrapper: &str) -> bool {
+    Path::new(wrapper)
+        .file_stem()
+        .is_some_and(|stem| stem == "sccache")
+}
+
+/// Counters from `sccache --show-stats`.
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SccacheStats {
+    pub compile_requests: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub cache_size_bytes: Option<u64>,
+}
+
+impl SccacheStats {
+    /// Counters accumulated since `before`; the cache size is the current one.
+    pub fn since(&self, before: &SccacheStats) -> SccacheStats {
+        SccacheStats {
+            compile_requests: self
+                .compile_requests
+                .saturating_sub(before.compile_requests),
+            cache_hits: self.cache_hits.saturating_sub(before.cache_hits),
+            cache_misses: self.cache_misses.saturating_sub(before.cache_misses),
+            cache_size_bytes: self.cache_size_bytes,
+        }
+    }
+
+    /// Share of cacheable compilations served from the cache, in percent.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.cache_hits + self.cache_misses;
+        (total > 0).then(|| self.cache_hits as f64 * 100.0 / total as f64)
+    }
+}
+
+/// Parses the human-readable `sccache --show-stats` table, whose label and value are
+/// separated by a run of spaces.
+pub fn parse_sccache_stats(output: &str) -> SccacheStats {
+    let mut stats = SccacheStats::default();
+    for line in output.lines() {
+        let Some((label, value)) = line.trim().split_once("  ") else {
+            continue;
+        };
+        let value = value.trim();
+        let count = || value.parse::<u64>().unwrap_or(0);
+        match label.trim() {
+            "Compile requests" => stats.compile_requests = count(),
+            "Cache hits" => stats.cache_hits = count(),
+            "Cache misses" => stats.cache_misses = count(),
+            "Cache size" => {
+                let compact: String = value.split_whitespace().collect();
+                stats.cache_size_bytes = memory::parse_size(compact.trim_end_matches("bytes")).ok();
+            }
+            _ => {}
+        }
+    }
+    stats
+}
+
+/// Rough time saved by cache hits: each hit is assumed to cost as much as an average
+/// miss of the same build. `None` when nothing was compiled to base the average on.
+pub fn estimate_time_saved(build: Duration, stats: &SccacheStats) -> Option<Duration> {
+    (stats.cache_misses > 0)
+        .then(|| build.mul_f64(stats.cache_hits as f64 / stats.cache_misses as f64))
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "mode", rename_all = "kebab-case")]
+pub enum CacheReport {
+    Sccache {
+        wrapper: String,
+        build_secs: f64,
+        stats: SccacheStats,
+        hit_rate: Option<f64>,
+        estimated_saved_secs: Option<f64>,
+    },
+    Incremental {
+        cold_secs: f64,
+        without_incremental_secs: f64,
+        with_incremental_secs: f64,
+    },
+}
+
+fn sccache_stats(wrapper: &str) -> Result<SccacheStats> {
+    let output = Command::new(wrapper)
+        .arg("--show-stats")
+        .output()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute sccache --show-stats"))?;
+    Ok(parse_sccache_stats(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Runs a plain `cargo build` and returns its duration, `None` if it failed.
+fn timed_build() -> Result<Option<Duration>> {
+    let start = Instant::now();
+    let status = Command::new(executor::cargo_bin())
+        .arg("build")
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo build"))?;
+    Ok(status.success().then(|| start.elapsed()))
+}
+
+fn measure_sccache(wrapper: &str, quiet: bool) -> Result<Option<CacheReport>> {
+    if !quiet {
+        output::line(format!(
+            "🗃️  sccache is configured as the rustc wrapper ({})",
+            wrapper
+        ));
+    }
+    let before = sccache_stats(wrapper)?;
+    performance::cargo_clean()?;
+    let Some(build) = timed_build()? else {
+        return Ok(None);
+    };
+    let stats = sccache_stats(wrapper)?.since(&before);
+    Ok(Some(CacheReport::Sccache {
+        wrapper: wrapper.to_string(),
+        build_secs: build.as_secs_f64(),
+        stats,
+        hit_rate: stats.hit_rate(),
+        estimated_saved_secs: estimate_time_saved(build, &stats).map(|d| d.as_secs_f64()),
+    }))
+}
+
+fn measure_incremental(quiet: bool) -> Result<Option<CacheReport>> {
+    let step = |message: &str| {
+        if !quiet {
+            output::status(message);
+        }
+    };
+    step("🗃️  No sccache wrapper configured; measuring cargo's incremental cache instead");
+    let metadata = metadata::cargo_metadata(Path::new("."))?;
+    performance::cargo_clean()?;
+    step("\n▶️  Cold build");
+    let Some(cold) = timed_build()? else {
+        return Ok(None);
+    };
+
+    step("▶️  Rebuild with the incremental cache cleared");
+    let incremental =
+        performance::profile_target_dir(&metadata.target_directory, "dev").join("incremental");
+    if incremental.is_dir() {
+        fs::remove_dir_all(&incremental)
+            .with_context(|| format!("Failed to remove {}", incremental.display()))?;
+    }
+    let touched = performance::touch_entry_point(&metadata)?;
+    let Some(without) = timed_build()? else {
+        return Ok(None);
+    };
+    drop(touched);
+
+    step("▶️  Rebuild with the incremental cache");
+    let touched = performance::touch_entry_point(&metadata)?;
+    let Some(with) = timed_build()? else {
+        return Ok(None);
+    };
+    drop(touched);
+
+    Ok(Some(CacheReport::Incremental {
+        cold_secs: cold.as_secs_f64(),
+        without_incremental_secs: without.as_secs_f64(),
+        with_incremental_secs: with.as_secs_f64(),
+    }))
+}
+
+fn print_report(report: &CacheReport) {
+    output::line(format!("\n{}", "📊 Build cache report:".bold().cyan()));
+    let rows: Vec<(&str, String)> = match report {
+        CacheReport::Sccache {
+            build_secs,
+            stats,
+            hit_rate,
+            estimated_saved_secs,
+            ..
+        } => vec![
+            ("Clean build", format!("{:.2}s", build_secs)),
+            ("Compile requests", stats.compile_requests.to_string()),
+            ("Cache hits", stats.cache_hits.to_string()),
+            ("Cache misses", stats.cache_misses.to_string()),
+            (
+                "Hit rate",
+                hit_rate.map_or_else(|| "-".to_string(), |rate| format!("{:.1}%", rate)),
+            ),
+            (
+                "Cache size",
+                stats
+                    .cache_size_bytes
+                    .map_or_else(|| "-".to_string(), format_size),
+            ),
+            (
+                "Est. time saved",
+                estimated_saved_secs.map_or_else(|| "-".to_string(), |s| format!("~{:.2}s", s)),
+            ),
+        ],
+        CacheReport::Incremental {
+            cold_secs,
+            without_incremental_secs,
+            with_incremental_secs,
+        } => vec![
+            ("Cold build", format!("{:.2}s", cold_secs)),
+            (
+                "Rebuild, no incremental",
+                format!("{:.2}s", without_incremental_secs),
+            ),
+            (
+                "Rebuild, incremental",
+                format!("{:.2}s", with_incremental_secs),
+            ),
+            (
+                "Incremental saves",
+                format!(
+                    "{:.2}s",
+                    (without_incremental_secs - with_incremental_secs).max(0.0)
+                ),
+            ),
+        ],
+    };
+    for (label, value) in rows {
+        output::line(format!("{:<26} {:>12}", label, value));
+    }
+}
+
+pub fn cache_report(json: bool, yes: bool) -> Result<()> {
+    if !prompt::confirm(
+        "🧹 The report starts from `cargo clean`, deleting the target directory. Continue?",
+        yes,
+    )? {
+        output::line("❌ Cache report cancelled.".yellow());
+        return Ok(());
+    }
+    let configs: Vec<String> = linker::config_files(Path::new("."))
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect();
+    let env = std::env::var("RUSTC_WRAPPER")
+        .or_else(|_| std::env::var("CARGO_BUILD_RUSTC_WRAPPER"))
+        .ok();
+    let report = match rustc_wrapper(&configs, env.as_deref()) {
+        Some(wrapper) if is_sccache(&wrapper) => measure_sccache(&wrapper, json)?,
+        _ => measure_incremental(json)?,
+    };
+    let Some(report) = report else {
+        output::warning("❌ Build failed. Check logs for details.");
+        return Ok(());
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
@@ -0,0 +1,244 @@
+//! Shared on-disk HTTP cache for crates.io queries.
+//!
+//! Responses are stored under the cargo-sleek data directory together with their
+//! `ETag`/`Last-Modified` validators. Fresh entries are served directly, stale ones
+//! are revalidated with a conditional request, and offline mode never touches the
+//! network. All requests share a global concurrency limit and timeouts.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::output;
+
+/// How long a cached response is served without revalidation.
+pub const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Maximum number of HTTP requests in flight at once, process-wide.
+pub const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const READ_TIMEOUT: Duration = Duration::from_secs(15);
+const USER_AGENT: &str = concat!(
+    "cargo-sleek/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/Arunmadhavan28/sleek)"
+);
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct EntryMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64,
+}
+
+/// Summary printed by `cargo sleek cache stats`.
+#[derive(Debug, Default, PartialEq)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+/// Directory holding cached responses.
+pub fn cache_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cargo-sleek")
+        .join("http-cache")
+}
+
+/// File name used for a URL's cache entry.
+pub fn cache_key(url: &str) -> String {
+    url.trim_start_matches("https://")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Counting semaphore limiting concurrent requests.
+struct Limiter {
+    in_flight: Mutex<usize>,
+    released: Condvar,
+}
+
+struct Permit(&'static Limiter);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        *self.0.in_flight.lock().unwrap() -= 1;
+        self.0.released.notify_one();
+    }
+}
+
+fn acquire() -> Permit {
+    static LIMITER: OnceLock<Limiter> = OnceLock::new();
+    let limiter = LIMITER.get_or_init(|| Limiter {
+        in_flight: Mutex::new(0),
+        released: Condvar::new(),
+    });
+    let mut in_flight = limiter.in_flight.lock().unwrap();
+    while *in_flight >= MAX_CONCURRENT_REQUESTS {
+        in_flight = limiter.released.wait(in_flight).unwrap();
+    }
+    *in_flight += 1;
+    Permit(limiter)
+}
+
+fn agent() -> &'static ureq::Agent {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT.get_or_init(|| {
+        ureq::AgentBuilder::new()
+            .timeout_connect(CONNECT_TIMEOUT)
+            .timeout_read(READ_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+    })
+}
+
+/// Fetches `url`, using the cache as described in the module docs.
+pub fn fetch(url: &str, offline: bool) -> Result<String> {
+    let dir = cache_dir();
+    let key = cache_key(url);
+    let body_path = dir.join(&key);
+    let meta_path = dir.join(format!("{key}.meta.json"));
+
+    let cached_body = fs::read_to_string(&body_path).ok();
+    let meta: Option<EntryMeta> = fs::read_to_string(&meta_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+
+    if let (Some(body), Some(meta)) = (&cached_body, &meta) {
+        if offline || now().saturating_sub(meta.fetched_at) < CACHE_TTL.as_secs() {
+            return Ok(body.clone());
+        }
+    }
+    if offline {
+        bail!("{url} is not cached (offline mode)");
+    }
+
+    let _permit = acquire();
+    let mut request = agent().get(url);
+    if cached_body.is_some() {
+        if let Some(meta) = &meta {
+            if let Some(etag) = &meta.etag {
+                request = request.set("If-None-Match", etag);
+            }
+            if let Some(modified) = &meta.last_modified {
+                request = request.set("If-Modified-Since", modified);
+            }
+        }
+    }
+    let response = request
+        .call()
+        .with_context(|| format!("Request to {url} failed"))?;
+
+    let mut new_meta = EntryMeta {
+        url: url.to_string(),
+        etag: response.header("ETag").map(str::to_string),
+        last_modified: response.header("Last-Modified").map(str::to_string),
+        fetched_at: now(),
+    };
+    let body = if response.status() == 304 {
+        let old = meta.unwrap_or_default();
+        new_meta.etag = new_meta.etag.or(old.etag);
+        new_meta.last_modified = new_meta.last_modified.or(old.last_modified);
+        cached_body.context("Server revalidated a response that is no longer cached")?
+    } else {
+        response
+            .into_string()
+            .context("Failed to read response body")?
+    };
+
+    let _ = fs::create_dir_all(&dir);
+    let _ = fs::write(&body_path, &body);
+    if let Ok(json) = serde_json::to_string(&new_meta) {
+        let _ = fs::write(&meta_path, json);
+    }
+    Ok(body)
+}
+
+/// Applies `f` to every item using at most [`MAX_CONCURRENT_REQUESTS`] worker threads,
+/// returning results in input order.
+pub fn map_concurrent<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..items.len()).map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..MAX_CONCURRENT_REQUESTS.min(items.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                let result = f(item);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.expect("every item is processed"))
+        .collect()
+}
+
+pub fn cache_stats() -> CacheStats {
+    let mut stats = CacheStats::default();
+    if let Ok(entries) = fs::read_dir(cache_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_meta = path.to_string_lossy().ends_with(".meta.json");
+            let size = entry.metadata().map(|m| m.len()).unwrap_or_default();
+            stats.bytes += size;
+            if !is_meta {
+                stats.entries += 1;
+            }
+        }
+    }
+    stats
+}
+
+pub fn show_cache_stats() -> Result<()> {
+    let stats = cache_stats();
+    output::line("🗄️  crates.io cache:".bold().cyan());
+    output::line(format!("   Location: {}", cache_dir().display()));
+    output::line(format!("   Entries:  {}", stats.entries));
+    output::line(format!(
+        "   Size:     {}",
+        crate::performance::format_size(stats.bytes)
+    ));
+    Ok(())
+}
+
+pub fn clear_cache() -> Result<()> {
+    let dir = cache_dir();
+    if dir.exists() {
+        fs::remove_dir_all(&dir).context("Failed to clear cache")?;
+    }
+    output::line(format!("✅ Cache cleared ({})", dir.display()));
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! Project configuration loaded from `.sleek.toml`.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lint_versions::VersionStyle;
+
+pub const CONFIG_FILE: &str = ".sleek.toml";
+
+#[derive(Deserialize, Debug, Default)]
+pub struct SleekConfig {
+    /// Track commands together with their arguments (see `--track-args`).
+    #[serde(default)]
+    pub track_args: bool,
+    /// `tracking = false` turns command tracking off, like `SLEEK_NO_STATS=1`.
+    pub tracking: Option<bool>,
+    /// Stats file location (overridden by `SLEEK_STATS_FILE` and `--stats-file`).
+    pub stats_file: Option<PathBuf>,
+    /// Default for `--log-dir`: keep a timestamped log of every cargo run here.
+    pub log_dir: Option<PathBuf>,
+    /// Record per-test times of every `cargo test` (see `--record-test-times`).
+    #[serde(default)]
+    pub record_test_times: bool,
+    #[serde(rename = "check-deps", default)]
+    pub check_deps: CheckDepsConfig,
+    #[serde(rename = "deps-age", default)]
+    pub deps_age: DepsAgeConfig,
+    #[serde(default)]
+    pub pipeline: PipelineConfig,
+    /// Shorthands resolved before passing unknown subcommands to cargo, e.g.
+    /// `br = "build --release"`.
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// Named step lists for `cargo sleek chain <name>`, e.g. `ci = ["fmt", "clippy", "test"]`.
+    #[serde(default)]
+    pub chains: HashMap<String, Vec<String>>,
+    #[serde(rename = "lint-versions", default)]
+    pub lint_versions: LintVersionsConfig,
+    #[serde(rename = "build-time", default)]
+    pub build_time: BuildTimeConfig,
+    #[serde(default)]
+    pub confirm: ConfirmConfig,
+    #[serde(default)]
+    pub bench: BenchConfig,
+    #[serde(default)]
+    pub watch: WatchConfig,
+    /// Shell commands run around cargo commands, by command name.
+    #[serde(default)]
+    pub hooks: HashMap<String, HookConfig>,
+}
+
+/// Hooks of one command, e.g. `[hooks.build] pre = ["cargo fmt --check"]`.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct HookConfig {
+    /// Run before the command; a failure aborts it unless `allow_failure` is set.
+    #[serde(default)]
+    pub pre: Vec<String>,
+    /// Run after the command, whether or not it succeeded.
+    #[serde(default)]
+    pub post: Vec<String>,
+    #[serde(default)]
+    pub allow_failure: bool,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct CheckDepsConfig {
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct DepsAgeConfig {
+    /// Pinned versions older than this many months are highlighted.
+    pub threshold_months: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct PipelineConfig {
+    /// Cargo commands run in order by `cargo sleek pipeline`, e.g. `"clippy -- -D warnings"`.
+    #[serde(default)]
+    pub steps: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct LintVersionsConfig {
+    /// Requirement style written by `lint-versions --fix`: `full` or `minor`.
+    #[serde(default)]
+    pub style: VersionStyle,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct BenchConfig {
+    /// Slowdown, in percent, above which `bench` flags a benchmark as regressed.
+    pub max_regression: Option<f64>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct WatchConfig {
+    /// Watched by `watch` besides `src` and `Cargo.toml`, e.g. `["assets", "build.rs"]`.
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    /// Globs of changed paths that never re-run the command.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct BuildTimeConfig {
+    /// Feature sets compared by `build-time --matrix` when none are given on the command line.
+    #[serde(default)]
+    pub matrix: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ConfirmConfig {
+    /// Cargo commands that ask before running; `clean` unless configured otherwise.
+    #[serde(default = "default_confirm_commands")]
+    pub commands: Vec<String>,
+}
+
+fn default_confirm_commands() -> Vec<String> {
+    vec!["clean".to_string()]
+}
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        ConfirmConfig {
+            commands: default_confirm_commands(),
+        }
+    }
+}
+
+/// Loads the config at `path`, falling back to defaults when it doesn't exist.
+pub fn load_config(path: &Path) -> Result<SleekConfig> {
+    if !path.exists() {
+        return Ok(SleekConfig::default());
+    }
+    let content = fs::read_to_string(path).context("Failed to read .sleek.toml")?;
+    toml::from_str(&content).context("Failed to parse .sleek.toml")
+}
@@ -0,0 +1,518 @@
+//! Unused dependency detection.
+
+use anyhow::{Context, Result};
+use colored::*;
+use rayon::prelude::*;
+use semver::{Version, VersionReq};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::index;
+use crate::metadata;
+use crate::output;
+use crate::usage::{self, RefKind};
+
+/// Options controlling a `check-deps` run.
+#[derive(Debug, Default, Clone)]
+pub struct CheckOptions {
+    /// Dependencies skipped by the analysis but still listed in the report.
+    pub ignore: Vec<String>,
+    /// Apply suggested manifest changes instead of only reporting them.
+    pub fix: bool,
+    /// List the source locations referencing each dependency.
+    pub explain: bool,
+    /// Audit the features each dependency requests.
+    pub features: bool,
+    /// Skip crates.io lookups when auditing features.
+    pub offline: bool,
+}
+
+const DEPENDENCY_SECTIONS: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Features a dependency entry requests in `Cargo.toml`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyFeatures {
+    pub name: String,
+    pub section: String,
+    /// Version requirement, absent for path/git/workspace-inherited entries.
+    pub version: Option<String>,
+    pub features: Vec<String>,
+    pub default_features: bool,
+}
+
+impl DependencyFeatures {
+    /// `default-features = false` without any explicit features usually leaves the
+    /// crate without functionality it needs.
+    pub fn possibly_broken(&self) -> bool {
+        !self.default_features && self.features.is_empty()
+    }
+}
+
+impl std::fmt::Display for DependencyFeatures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.default_features {
+            write!(f, " (no default features)")?;
+        }
+        if !self.features.is_empty() {
+            write!(f, " [{}]", self.features.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the requested features of every dependency in `manifest`.
+pub fn parse_dependency_features(manifest: &str) -> Result<Vec<DependencyFeatures>> {
+    let manifest: DocumentMut = manifest.parse().context("Failed to parse Cargo.toml")?;
+    let mut deps = Vec::new();
+    for section in DEPENDENCY_SECTIONS {
+        let Some(table) = manifest.get(section).and_then(Item::as_table_like) else {
+            continue;
+        };
+        for (name, item) in table.iter() {
+            let entry = item.as_table_like();
+            let features = entry
+                .and_then(|t| t.get("features"))
+                .and_then(Item::as_array)
+                .map(|array| {
+                    array
+                        .iter()
+                        .filter_map(|v| v.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let default_features = entry
+                .and_then(|t| {
+                    t.get("default-features")
+                        .or_else(|| t.get("default_features"))
+                })
+                .and_then(Item::as_bool)
+                .unwrap_or(true);
+            let version = match entry {
+                Some(t) => t.get("version").and_then(Item::as_str),
+                None => item.as_str(),
+            };
+            deps.push(DependencyFeatures {
+                name: name.to_string(),
+                section: section.to_string(),
+                version: version.map(String::from),
+                features,
+                default_features,
+            });
+        }
+    }
+    Ok(deps)
+}
+
+/// Features of `dep` that the newest published version matching its requirement does
+/// not declare.
+fn undeclared_features(dep: &DependencyFeatures, offline: bool) -> Result<Vec<String>> {
+    let Some(req) = dep
+        .version
+        .as_deref()
+        .and_then(|v| VersionReq::parse(v).ok())
+    else {
+        return Ok(Vec::new());
+    };
+    let entries = index::fetch_versions(&dep.name, offline)?;
+    let Some(entry) = entries
+        .iter()
+        .filter(|e| !e.yanked)
+        .filter_map(|e| Some((Version::parse(&e.vers).ok()?, e)))
+        .filter(|(v, _)| req.matches(v))
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, e)| e)
+    else {
+        return Ok(Vec::new());
+    };
+    Ok(dep
+        .features
+        .iter()
+        .filter(|f| !f.contains('/') && !entry.has_feature(f))
+        .cloned()
+        .collect())
+}
+
+/// Lists each dependency with its features, flagging suspicious combinations.
+fn audit_features(crate_dir: &Path, offline: bool) -> Result<()> {
+    let manifest =
+        fs::read_to_string(crate_dir.join("Cargo.toml")).context("Failed to read Cargo.toml")?;
+    let deps = parse_dependency_features(&manifest)?;
+
+    output::line("🎛️  Requested features:".bold());
+    let mut lookup_error = None;
+    for dep in &deps {
+        output::line(format!("   • [{}] {}", dep.section, dep));
+        if dep.possibly_broken() {
+            output::line(format!(
+                "       {}",
+                "⚠️  default features disabled and none enabled — possibly broken".yellow()
+            ));
+        }
+        if dep.features.is_empty() || lookup_error.is_some() {
+            continue;
+        }
+        match undeclared_features(dep, offline) {
+            Ok(missing) if !missing.is_empty() => output::line(format!(
+                "       {}",
+                format!("🚨 not declared by the crate: {}", missing.join(", ")).red()
+            )),
+            Ok(_) => {}
+            Err(err) => lookup_error = Some(err),
+        }
+    }
+    if let Some(err) = lookup_error {
+        output::line(format!("⚠️  Skipped checking features against crates.io: {err:#}").yellow());
+    }
+    Ok(())
+}
+
+/// Maximum number of references shown per dependency by `--explain`.
+pub const EXPLAIN_LIMIT: usize = 3;
+
+/// Result of scanning a manifest for unused dependencies.
+#[derive(Debug, Default, PartialEq)]
+pub struct UnusedReport {
+    pub unused: Vec<String>,
+    pub ignored: Vec<String>,
+    /// Path and git dependencies, which the lockfile check can't judge.
+    pub local: Vec<String>,
+}
+
+/// Scans `Cargo.toml` and `Cargo.lock` in `project_dir`, skipping names in `ignore`.
+pub fn find_unused_deps(project_dir: &Path, ignore: &[String]) -> Result<UnusedReport> {
+    find_unused_deps_with_lock(project_dir, &project_dir.join("Cargo.lock"), ignore)
+}
+
+/// Like [`find_unused_deps`], but reads the lockfile from `lock_path` (the workspace root
+/// for member crates).
+pub fn find_unused_deps_with_lock(
+    project_dir: &Path,
+    lock_path: &Path,
+    ignore: &[String],
+) -> Result<UnusedReport> {
+    let manifest = read_manifest(project_dir)?;
+    let cargo_lock = fs::read_to_string(lock_path).unwrap_or_default();
+
+    let mut report = UnusedReport::default();
+    let Some(deps) = manifest.get("dependencies").and_then(Item::as_table_like) else {
+        return Ok(report);
+    };
+    for (dep, item) in deps.iter() {
+        if ignore.iter().any(|name| name == dep) {
+            report.ignored.push(dep.to_string());
+            continue;
+        }
+        let local = item
+            .as_table_like()
+            .is_some_and(|entry| entry.contains_key("path") || entry.contains_key("git"));
+        if local {
+            report.local.push(dep.to_string());
+        } else if !cargo_lock.contains(dep) {
+            report.unused.push(dep.to_string());
+        }
+    }
+
+    Ok(report)
+}
+
+/// A crate belonging to a workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceMember {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+/// Returns whether the manifest in `project_dir` declares a `[workspace]`.
+pub fn is_workspace(project_dir: &Path) -> Result<bool> {
+    Ok(read_manifest(project_dir)?.contains_key("workspace"))
+}
+
+/// Enumerates workspace members via `cargo metadata`, sorted by name.
+pub fn workspace_members(project_dir: &Path) -> Result<Vec<WorkspaceMember>> {
+    let metadata = metadata::cargo_metadata(project_dir)?;
+    let mut members: Vec<WorkspaceMember> = metadata
+        .members()
+        .map(|pkg| WorkspaceMember {
+            name: pkg.name.clone(),
+            dir: pkg
+                .manifest_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+        })
+        .collect();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(members)
+}
+
+fn read_manifest(project_dir: &Path) -> Result<DocumentMut> {
+    let content =
+        fs::read_to_string(project_dir.join("Cargo.toml")).context("Failed to read Cargo.toml")?;
+    content.parse().context("Failed to parse Cargo.toml")
+}
+
+/// Lists the dependency names declared in `section` (e.g. `dependencies`).
+pub fn manifest_dependencies(project_dir: &Path, section: &str) -> Result<Vec<String>> {
+    let manifest = read_manifest(project_dir)?;
+    Ok(manifest
+        .get(section)
+        .and_then(Item::as_table_like)
+        .map(|table| table.iter().map(|(name, _)| name.to_string()).collect())
+        .unwrap_or_default())
+}
+
+/// Finds `[dependencies]` that are only referenced from tests, benches, examples
+/// or `#[cfg(test)]` code.
+pub fn find_dev_only_deps(project_dir: &Path, ignore: &[String]) -> Result<Vec<String>> {
+    let deps: Vec<String> = manifest_dependencies(project_dir, "dependencies")?
+        .into_iter()
+        .filter(|dep| !ignore.contains(dep))
+        .collect();
+    let usage = usage::scan_usage(project_dir, &deps)?;
+
+    Ok(deps
+        .into_iter()
+        .filter(|dep| {
+            let refs = &usage[dep];
+            !refs.is_empty() && refs.iter().all(|r| r.kind == RefKind::Test)
+        })
+        .collect())
+}
+
+/// Finds `[build-dependencies]` that `build.rs` never references.
+pub fn find_unreferenced_build_deps(project_dir: &Path, ignore: &[String]) -> Result<Vec<String>> {
+    let deps: Vec<String> = manifest_dependencies(project_dir, "build-dependencies")?
+        .into_iter()
+        .filter(|dep| !ignore.contains(dep))
+        .collect();
+    let usage = usage::scan_usage(project_dir, &deps)?;
+
+    Ok(deps
+        .into_iter()
+        .filter(|dep| !usage[dep].iter().any(|r| r.kind == RefKind::Build))
+        .collect())
+}
+
+/// Moves `names` from `[dependencies]` to `[dev-dependencies]`, keeping their specs intact.
+pub fn move_to_dev_dependencies(project_dir: &Path, names: &[String]) -> Result<()> {
+    let mut manifest = read_manifest(project_dir)?;
+
+    for name in names {
+        let Some(item) = manifest
+            .get_mut("dependencies")
+            .and_then(Item::as_table_like_mut)
+            .and_then(|deps| deps.remove(name))
+        else {
+            continue;
+        };
+        let dev = manifest
+            .entry("dev-dependencies")
+            .or_insert_with(|| Item::Table(Table::new()))
+            .as_table_like_mut()
+            .context("[dev-dependencies] is not a table")?;
+        dev.insert(name, item);
+    }
+
+    fs::write(project_dir.join("Cargo.toml"), manifest.to_string())
+        .context("Failed to write Cargo.toml")
+}
+
+/// Unused dependencies of one crate as `unused\t<name>\t<section>` lines: unused
+/// `[dependencies]` and `[build-dependencies]` that `build.rs` never references.
+pub fn porcelain_lines(
+    crate_dir: &Path,
+    lock_path: &Path,
+    ignore: &[String],
+) -> Result<Vec<String>> {
+    let report = find_unused_deps_with_lock(crate_dir, lock_path, ignore)?;
+    let unused_build = find_unreferenced_build_deps(crate_dir, ignore)?;
+    Ok(report
+        .unused
+        .iter()
+        .map(|name| (name, "dependencies"))
+        .chain(unused_build.iter().map(|name| (name, "build-dependencies")))
+        .map(|(name, section)| format!("unused\t{}\t{}", name, section))
+        .collect())
+}
+
+/// Porcelain lines of every crate in `project_dir`, one per unused dependency.
+pub fn unused_porcelain_lines(project_dir: &Path, ignore: &[String]) -> Result<Vec<String>> {
+    let lock_path = project_dir.join("Cargo.lock");
+    let crate_dirs: Vec<PathBuf> = if is_workspace(project_dir)? {
+        workspace_members(project_dir)?
+            .into_iter()
+            .map(|member| member.dir)
+            .collect()
+    } else {
+        vec![project_dir.to_path_buf()]
+    };
+    let per_crate = crate_dirs
+        .par_iter()
+        .map(|dir| porcelain_lines(dir, &lock_path, ignore))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(per_crate.into_iter().flatten().collect())
+}
+
+/// Prints the porcelain lines of every crate in `project_dir`. Returns whether any
+/// unused dependency was found.
+pub fn check_unused_porcelain(project_dir: &Path, ignore: &[String]) -> Result<bool> {
+    let lines = unused_porcelain_lines(project_dir, ignore)?;
+    lines.iter().for_each(|line| println!("{}", line));
+    Ok(!lines.is_empty())
+}
+
+/// Prints only the number of unused dependencies (`check-deps --count-only`).
+/// Returns the count.
+pub fn count_unused_deps(project_dir: &Path, ignore: &[String]) -> Result<usize> {
+    let count = unused_porcelain_lines(project_dir, ignore)?.len();
+    println!("{}", count);
+    Ok(count)
+}
+
+/// What the unused-dependency analysis found in one crate.
+#[derive(Debug, Default, PartialEq)]
+pub struct CrateFindings {
+    pub report: UnusedReport,
+    /// `[dependencies]` referenced only from tests, benches and examples.
+    pub dev_only: Vec<String>,
+    /// `[build-dependencies]` that `build.rs` never references.
+    pub unused_build: Vec<String>,
+}
+
+/// Runs the unused, dev-only and build-dependency checks on one crate.
+pub fn analyze_crate(
+    crate_dir: &Path,
+    lock_path: &Path,
+    ignore: &[String],
+) -> Result<CrateFindings> {
+    Ok(CrateFindings {
+        report: find_unused_deps_with_lock(crate_dir, lock_path, ignore)?,
+        dev_only: find_dev_only_deps(crate_dir, ignore)?,
+        unused_build: find_unreferenced_build_deps(crate_dir, ignore)?,
+    })
+}
+
+/// Analyzes workspace members in parallel. Results come back in the order of `members`
+/// (alphabetical from [`workspace_members`]) whatever order the threads finish in.
+pub fn analyze_members(
+    members: &[WorkspaceMember],
+    lock_path: &Path,
+    ignore: &[String],
+) -> Result<Vec<CrateFindings>> {
+    members
+        .par_iter()
+        .map(|member| analyze_crate(&member.dir, lock_path, ignore))
+        .collect()
+}
+
+/// Reports unused dependencies of every crate in `project_dir`. Returns whether any
+/// unused `[dependencies]` or `[build-dependencies]` entry was found.
+pub fn check_unused_deps(project_dir: &Path, options: &CheckOptions) -> Result<bool> {
+    output::status("🔍 Checking unused dependencies...");
+    let lock_path = project_dir.join("Cargo.lock");
+
+    if !is_workspace(project_dir)? {
+        let findings = analyze_crate(project_dir, &lock_path, &options.ignore)?;
+        return print_findings(project_dir, &findings, options);
+    }
+
+    // `cargo metadata` runs once, before the members are analyzed in parallel.
+    let members = workspace_members(project_dir)?;
+    let findings = analyze_members(&members, &lock_path, &options.ignore)?;
+    let mut found = false;
+    for (member, findings) in members.iter().zip(&findings) {
+        output::line(format!("\n📦 {}", member.name.bold()));
+        found |= print_findings(&member.dir, findings, options)?;
+    }
+    Ok(found)
+}
+
+/// Prints the findings of one crate and applies `--fix`, `--explain` and `--features`.
+fn print_findings(
+    crate_dir: &Path,
+    findings: &CrateFindings,
+    options: &CheckOptions,
+) -> Result<bool> {
+    let CrateFindings {
+        report,
+        dev_only,
+        unused_build,
+    } = findings;
+
+    if report.unused.is_empty() {
+        output::line("✅ No unused dependencies found!".green());
+    } else {
+        output::line("🚨 Unused dependencies found:".red());
+        for dep in &report.unused {
+            output::line(format!("   • {}", dep));
+        }
+    }
+
+    if !dev_only.is_empty() {
+        output::line(
+            "🧪 Used only in tests/benches — consider moving to [dev-dependencies]:".yellow(),
+        );
+        for dep in dev_only {
+            output::line(format!("   • {}", dep));
+        }
+        if options.fix {
+            move_to_dev_dependencies(crate_dir, dev_only)?;
+            output::line("✏️  Moved to [dev-dependencies] in Cargo.toml".green());
+        }
+    }
+
+    if !unused_build.is_empty() {
+        output::line("🏗️  Build dependencies not referenced from build.rs:".yellow());
+        for dep in unused_build {
+            output::line(format!("   • {}", dep));
+        }
+    }
+
+    if options.explain {
+        explain_deps(crate_dir)?;
+    }
+
+    if options.features {
+        audit_features(crate_dir, options.offline)?;
+    }
+
+    if !report.local.is_empty() {
+        output::line("📁 Local/git dependencies (not checked):".dimmed());
+        for dep in &report.local {
+            output::line(format!("   • {}", dep));
+        }
+    }
+
+    if !report.ignored.is_empty() {
+        output::line("🙈 Ignored (configured):".dimmed());
+        for dep in &report.ignored {
+            output::line(format!("   • {}", dep));
+        }
+    }
+    Ok(!report.unused.is_empty() || !unused_build.is_empty())
+}
+
+/// Prints where each `[dependencies]` entry is referenced in the sources.
+fn explain_deps(crate_dir: &Path) -> Result<()> {
+    let deps = manifest_dependencies(crate_dir, "dependencies")?;
+    let usage = usage::scan_usage(crate_dir, &deps)?;
+
+    output::line("🔎 Dependency references:".bold());
+    for dep in &deps {
+        let refs = &usage[dep];
+        output::line(format!("   • {}", dep.cyan()));
+        if refs.is_empty() {
+            output::line(format!("       {}", "no references found".dimmed()));
+            continue;
+        }
+        for r in refs.iter().take(EXPLAIN_LIMIT) {
+            output::line(format!("       {}:{}", r.file.display(), r.line));
+        }
+        if refs.len() > EXPLAIN_LIMIT {
+            output::line(format!("       … and {} more", refs.len() - EXPLAIN_LIMIT));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! Age report for the versions pinned in `Cargo.lock`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::*;
+use semver::Version;
+use std::path::Path;
+
+use crate::cache;
+use crate::index::{self, PublishedVersion};
+use crate::lockfile;
+use crate::output;
+
+/// Default age, in months, above which a pinned version is highlighted.
+pub const DEFAULT_THRESHOLD_MONTHS: u32 = 12;
+
+const DAYS_PER_MONTH: f64 = 30.44;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgeRow {
+    pub name: String,
+    pub pinned: String,
+    pub latest: String,
+    pub age_months: u32,
+    pub versions_behind: usize,
+}
+
+/// Computes the age of `pinned` and how many stable releases were published after it.
+pub fn age_row(
+    name: &str,
+    pinned: &str,
+    versions: &[PublishedVersion],
+    now: DateTime<Utc>,
+) -> Result<AgeRow> {
+    let pinned_version = Version::parse(pinned).context("Invalid pinned version")?;
+    let published = versions
+        .iter()
+        .find(|v| v.num == pinned)
+        .with_context(|| format!("{name} {pinned} not found on crates.io"))?;
+    let created = DateTime::parse_from_rfc3339(&published.created_at)
+        .context("Invalid publish date")?
+        .with_timezone(&Utc);
+
+    let newer: Vec<Version> = versions
+        .iter()
+        .filter(|v| !v.yanked)
+        .filter_map(|v| Version::parse(&v.num).ok())
+        .filter(|v| v.pre.is_empty() && *v > pinned_version)
+        .collect();
+    let latest = newer
+        .iter()
+        .max()
+        .map(Version::to_string)
+        .unwrap_or_else(|| pinned.to_string());
+
+    Ok(AgeRow {
+        name: name.to_string(),
+        pinned: pinned.to_string(),
+        latest,
+        age_months: ((now - created).num_days().max(0) as f64 / DAYS_PER_MONTH) as u32,
+        versions_behind: newer.len(),
+    })
+}
+
+pub fn show_deps_age(project_dir: &Path, threshold_months: u32, offline: bool) -> Result<()> {
+    output::status("🕰️  Checking the age of pinned dependencies...");
+    let lock = lockfile::load_lockfile(&project_dir.join("Cargo.lock"))?;
+    let now = Utc::now();
+
+    let packages: Vec<_> = lock.package.iter().filter(|p| p.is_crates_io()).collect();
+    let fetched = cache::map_concurrent(&packages, |package| {
+        index::fetch_published_versions(&package.name, offline)
+            .and_then(|versions| age_row(&package.name, &package.version, &versions, now))
+    });
+
+    let mut rows = Vec::new();
+    let mut failures = 0;
+    let mut first_error = None;
+    for row in fetched {
+        match row {
+            Ok(row) => rows.push(row),
+            Err(err) => {
+                failures += 1;
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+    rows.sort_by(|a, b| b.age_months.cmp(&a.age_months).then(a.name.cmp(&b.name)));
+
+    output::line(format!(
+        "{:<28} {:>12} {:>12} {:>8} {:>8}",
+        "Crate", "Pinned", "Latest", "Months", "Behind"
+    ));
+    for row in &rows {
+        let age = format!("{:>8}", row.age_months);
+        let age = if row.age_months > threshold_months {
+            age.red().bold()
+        } else {
+            age.normal()
+        };
+        output::line(format!(
+            "{:<28} {:>12} {:>12} {} {:>8}",
+            row.name, row.pinned, row.latest, age, row.versions_behind
+        ));
+    }
+
+    if let Some(err) = first_error {
+        output::warning(format!("⚠️ {failures} package(s) could not be checked: {err:#}").yellow());
+    }
+    Ok(())
+}
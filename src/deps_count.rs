@@ -0,0 +1,128 @@
+//! Direct vs transitive dependency counts from the resolve graph.
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+use crate::metadata::{self, Metadata};
+use crate::output;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Contributor {
+    pub name: String,
+    /// Transitive packages that would disappear from the graph without this dependency.
+    pub unique_transitive: usize,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DepsCount {
+    pub total: usize,
+    pub direct: usize,
+    pub transitive: usize,
+    pub contributors: Vec<Contributor>,
+}
+
+/// Packages reachable from `roots`, never walking through `skip`. Roots are excluded.
+fn reachable(
+    graph: &HashMap<&str, &[String]>,
+    roots: &HashSet<&str>,
+    skip: Option<&str>,
+) -> HashSet<String> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<&str> = roots.iter().copied().collect();
+    while let Some(id) = queue.pop_front() {
+        for dep in graph.get(id).copied().unwrap_or_default() {
+            let dep = dep.as_str();
+            if Some(dep) == skip || roots.contains(dep) || seen.contains(dep) {
+                continue;
+            }
+            seen.insert(dep.to_string());
+            queue.push_back(dep);
+        }
+    }
+    seen
+}
+
+pub fn count_dependencies(metadata: &Metadata) -> Result<DepsCount> {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .context("cargo metadata output has no resolve graph")?;
+    let graph: HashMap<&str, &[String]> = resolve
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node.dependencies.as_slice()))
+        .collect();
+    let names: HashMap<&str, &str> = metadata
+        .packages
+        .iter()
+        .map(|pkg| (pkg.id.as_str(), pkg.name.as_str()))
+        .collect();
+    let roots: HashSet<&str> = metadata
+        .workspace_members
+        .iter()
+        .map(String::as_str)
+        .collect();
+
+    let all = reachable(&graph, &roots, None);
+    let mut direct: Vec<&str> = roots
+        .iter()
+        .flat_map(|root| graph.get(root).copied().unwrap_or_default())
+        .map(String::as_str)
+        .filter(|id| !roots.contains(id))
+        .collect();
+    direct.sort_unstable();
+    direct.dedup();
+
+    let mut contributors: Vec<Contributor> = direct
+        .iter()
+        .map(|id| {
+            let without = reachable(&graph, &roots, Some(id));
+            Contributor {
+                name: names.get(id).copied().unwrap_or(id).to_string(),
+                unique_transitive: all.len() - without.len() - 1,
+            }
+        })
+        .collect();
+    contributors.sort_by(|a, b| {
+        b.unique_transitive
+            .cmp(&a.unique_transitive)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(DepsCount {
+        total: all.len(),
+        direct: direct.len(),
+        transitive: all.len() - direct.len(),
+        contributors,
+    })
+}
+
+pub fn show_deps_count(project_dir: &Path, json: bool) -> Result<()> {
+    let metadata = metadata::cargo_metadata_with_deps(project_dir)?;
+    let count = count_dependencies(&metadata)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&count)?);
+        return Ok(());
+    }
+
+    output::line("🌳 Dependency Graph:".bold().cyan());
+    output::line(format!("   Total packages: {}", count.total));
+    output::line(format!("   Direct:         {}", count.direct));
+    output::line(format!("   Transitive:     {}", count.transitive));
+    output::line(format!(
+        "\n{:<30} {:>16}",
+        "Direct dependency", "Unique transitive"
+    ));
+    for contributor in &count.contributors {
+        output::line(format!(
+            "{:<30} {:>16}",
+            contributor.name.green(),
+            contributor.unique_transitive
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,230 @@
+//! Crates locked at several versions in `Cargo.lock` (`check-dupes`), and unifying them
+//! with `cargo update --precise`.
+
+use anyhow::Result;
+use colored::*;
+use semver::Version;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::executor;
+use crate::lockfile::{self, Lockfile};
+use crate::output;
+
+/// A crate locked at more than one version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    pub name: String,
+    /// Oldest first.
+    pub versions: Vec<String>,
+}
+
+/// One `cargo update -p <name>@<from> --precise <to>` invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateCommand {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl UpdateCommand {
+    /// Arguments after `cargo`. The package is given as `name@version` because a bare
+    /// name is ambiguous once several versions are locked.
+    pub fn args(&self) -> Vec<String> {
+        vec![
+            "update".to_string(),
+            "-p".to_string(),
+            format!("{}@{}", self.name, self.from),
+            "--precise".to_string(),
+            self.to.clone(),
+        ]
+    }
+
+    pub fn display(&self) -> String {
+        let words: Vec<String> = std::iter::once("cargo".to_string())
+            .chain(self.args())
+            .map(|arg| executor::shell_quote(&arg))
+            .collect();
+        words.join(" ")
+    }
+}
+
+/// Versions `cargo update` cannot unify, with the reason.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedDuplicate {
+    pub name: String,
+    pub versions: Vec<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DedupePlan {
+    pub updates: Vec<UpdateCommand>,
+    pub skipped: Vec<SkippedDuplicate>,
+}
+
+/// Registry and git packages locked at several versions, by name. Workspace members
+/// (no `source`) are left out.
+pub fn find_duplicates(lock: &Lockfile) -> Vec<Duplicate> {
+    let mut versions: BTreeMap<&str, Vec<Version>> = BTreeMap::new();
+    for package in lock.package.iter().filter(|p| p.source.is_some()) {
+        if let Ok(version) = Version::parse(&package.version) {
+            versions.entry(&package.name).or_default().push(version);
+        }
+    }
+    versions
+        .into_iter()
+        .filter_map(|(name, mut versions)| {
+            versions.sort();
+            versions.dedup();
+            (versions.len() > 1).then(|| Duplicate {
+                name: name.to_string(),
+                versions: versions.iter().map(Version::to_string).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Versions a caret requirement can move between: same major, or same minor below 1.0.
+fn compatibility_class(version: &Version) -> (u64, u64, u64) {
+    match (version.major, version.minor) {
+        (0, 0) => (0, 0, version.patch),
+        (0, minor) => (0, minor, 0),
+        (major, _) => (major, 0, 0),
+    }
+}
+
+/// Moves every version of a duplicate to the newest semver-compatible one. Versions with
+/// no compatible newer one are skipped: some dependent requires an incompatible release,
+/// which only changing that dependent's requirement can fix.
+pub fn plan_dedupe(duplicates: &[Duplicate]) -> DedupePlan {
+    let mut plan = DedupePlan::default();
+    for duplicate in duplicates {
+        let mut classes: BTreeMap<(u64, u64, u64), Vec<Version>> = BTreeMap::new();
+        for version in duplicate
+            .versions
+            .iter()
+            .filter_map(|v| Version::parse(v).ok())
+        {
+            classes
+                .entry(compatibility_class(&version))
+                .or_default()
+                .push(version);
+        }
+        for versions in classes.values_mut() {
+            versions.sort();
+            let newest = versions.last().unwrap().to_string();
+            for older in &versions[..versions.len() - 1] {
+                plan.updates.push(UpdateCommand {
+                    name: duplicate.name.clone(),
+                    from: older.to_string(),
+                    to: newest.clone(),
+                });
+            }
+        }
+        if classes.len() > 1 {
+            let kept: Vec<String> = classes
+                .values()
+                .map(|versions| versions.last().unwrap().to_string())
+                .collect();
+            plan.skipped.push(SkippedDuplicate {
+                name: duplicate.name.clone(),
+                reason: format!(
+                    "{} are semver-incompatible; a dependent has to change its requirement",
+                    kept.join(" and ")
+                ),
+                versions: kept,
+            });
+        }
+    }
+    plan
+}
+
+fn print_duplicates(duplicates: &[Duplicate]) {
+    output::line(
+        format!(
+            "📦 {} crate(s) locked at several versions:",
+            duplicates.len()
+        )
+        .bold()
+        .cyan(),
+    );
+    for duplicate in duplicates {
+        output::line(format!(
+            "   • {} {}",
+            duplicate.name,
+            duplicate.versions.join(", ")
+        ));
+    }
+}
+
+/// Runs the plan's updates one by one; a failing update (for instance an `=` requirement
+/// that pins the old version) is reported and skipped.
+fn apply_plan(project_dir: &Path, plan: &DedupePlan) -> Result<()> {
+    let mut changed = Vec::new();
+    for update in &plan.updates {
+        let result = Command::new(executor::cargo_bin())
+            .args(update.args())
+            .current_dir(project_dir)
+            .output()
+            .map_err(|err| executor::spawn_error(err, "Failed to execute cargo update"))?;
+        if result.status.success() {
+            changed.push(update);
+            continue;
+        }
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let reason = stderr
+            .lines()
+            .find(|line| line.trim_start().starts_with("error"))
+            .or_else(|| stderr.lines().find(|line| !line.trim().is_empty()))
+            .unwrap_or("cargo update failed")
+            .trim();
+        output::line(format!("⚠️  Skipped {} {}: {}", update.name, update.from, reason).yellow());
+    }
+    if changed.is_empty() {
+        output::line("⚠️  Cargo.lock was not changed.".yellow());
+        return Ok(());
+    }
+    output::line("✅ Updated Cargo.lock:".green());
+    for update in changed {
+        output::line(format!(
+            "   • {} {} → {}",
+            update.name, update.from, update.to
+        ));
+    }
+    Ok(())
+}
+
+/// Lists duplicated crates. With `fix`, prints the `cargo update` commands that unify them
+/// and then runs them, unless `dry_run`.
+pub fn check_dupes(project_dir: &Path, fix: bool, dry_run: bool) -> Result<()> {
+    let lock = lockfile::load_lockfile(&project_dir.join("Cargo.lock"))?;
+    let duplicates = find_duplicates(&lock);
+    if duplicates.is_empty() {
+        output::line("✅ Every crate is locked at a single version!".green());
+        return Ok(());
+    }
+    print_duplicates(&duplicates);
+    if !fix {
+        output::line("\n💡 Run with --fix to unify compatible versions with cargo update.");
+        return Ok(());
+    }
+
+    let plan = plan_dedupe(&duplicates);
+    for skipped in &plan.skipped {
+        output::line(format!("⚠️  Keeping {}: {}", skipped.name, skipped.reason).yellow());
+    }
+    if plan.updates.is_empty() {
+        output::line("⚠️  No duplicate can be unified by cargo update.".yellow());
+        return Ok(());
+    }
+    output::line("\n🔧 Commands:".bold());
+    for update in &plan.updates {
+        println!("   {}", update.display());
+    }
+    if dry_run {
+        return Ok(());
+    }
+    apply_plan(project_dir, &plan)
+}
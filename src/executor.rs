@@ -0,0 +1,814 @@
+//! Runs cargo subcommands and records them in the stats file.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use indicatif::{ProgressBar, ProgressDrawTarget};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::HookConfig;
+use crate::history;
+use crate::logs;
+use crate::metadata;
+use crate::output;
+use crate::performance::{dir_size, format_size};
+use crate::prompt;
+use crate::report::{self, ReportOptions, RunReport};
+use crate::size;
+use crate::stats;
+use crate::test_times;
+
+/// The cargo binary to spawn: `$CARGO` when set (rustup overrides, CI), else `cargo`.
+pub fn cargo_bin() -> String {
+    std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string())
+}
+
+/// Exit status used when the cargo binary cannot be found, as shells do for missing commands.
+pub const EXIT_CARGO_NOT_FOUND: i32 = 127;
+
+/// Raised when spawning cargo fails because the binary does not exist.
+#[derive(Debug)]
+pub struct CargoNotFound {
+    pub program: String,
+}
+
+impl fmt::Display for CargoNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cargo not found on PATH; is Rust installed? (looked for `{}`)\n   \
+             Install it from https://rustup.rs",
+            self.program
+        )
+    }
+}
+
+impl std::error::Error for CargoNotFound {}
+
+/// A cargo run that failed inside a sleek-native command; `main` exits with `code`.
+#[derive(Debug)]
+pub struct CargoFailed {
+    pub message: String,
+    pub code: i32,
+}
+
+impl fmt::Display for CargoFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CargoFailed {}
+
+/// A command declined at its confirmation prompt; `main` exits with 1 so scripts can
+/// tell it from a run.
+#[derive(Debug)]
+pub struct Cancelled {
+    pub command: String,
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cancelled `cargo {}`.", self.command)
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Wraps an error from spawning cargo, turning a missing binary into [`CargoNotFound`].
+pub fn spawn_error(err: io::Error, action: &'static str) -> anyhow::Error {
+    if err.kind() == io::ErrorKind::NotFound {
+        CargoNotFound {
+            program: cargo_bin(),
+        }
+        .into()
+    } else {
+        anyhow::Error::new(err).context(action)
+    }
+}
+
+/// Exit code mirroring a child's `status`: its own code, or 128 plus the signal number
+/// when it was killed by a signal, as shells report it.
+pub fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
+
+/// Whether the cargo in use is a nightly build, which unlocks `-Z` flags.
+pub fn cargo_is_nightly() -> bool {
+    Command::new(cargo_bin())
+        .arg("-V")
+        .output()
+        .map(|out| String::from_utf8_lossy(&out.stdout).contains("nightly"))
+        .unwrap_or(false)
+}
+
+/// Settings shared by every command the executor runs.
+#[derive(Debug, Default, Clone)]
+pub struct ExecOptions {
+    pub verbose: bool,
+    /// Track `build --release` separately from `build` instead of by subcommand only.
+    pub track_args: bool,
+    /// Capture `cargo test` output to record per-test times for `test-times`.
+    pub record_test_times: bool,
+    /// Spawn cargo even when the working directory has no `Cargo.toml`.
+    pub skip_project_check: bool,
+    /// Capture cargo's output into this file while still echoing it.
+    pub log_file: Option<PathBuf>,
+    /// Keep only the last this-many bytes of captured output.
+    pub limit_output: Option<usize>,
+    /// Write a JSON summary of each run here.
+    pub report: Option<ReportOptions>,
+    /// Extra environment variables set on the spawned cargo process.
+    pub env: Vec<(String, String)>,
+    /// Commands that ask for confirmation before running.
+    pub confirm: Vec<String>,
+    /// Skip confirmation prompts (`--yes`).
+    pub assume_yes: bool,
+    /// Arguments from the project's [`ARGS_FILE`], placed before the command-line ones.
+    /// [`execute_cargo_command`] fills them in for the commands that take them.
+    pub default_args: Vec<String>,
+    /// Show a spinner while captured commands run; off with `--no-color`.
+    pub spinner: bool,
+    /// Hooks from `[hooks]` in the config file, by command name; empty with `--no-hooks`.
+    pub hooks: HashMap<String, HookConfig>,
+    /// Alias the command was expanded from; the run is tracked under it as well.
+    pub alias: Option<String>,
+    /// Print the commands that would run, hooks included, instead of running them.
+    pub dry_run: bool,
+    /// Write each run's output to a timestamped log in this directory (`--log-dir`).
+    pub log_dir: Option<PathBuf>,
+    /// Set to the pid of the cargo child while it runs, for [`stop_child`]. The child
+    /// then gets a process group of its own.
+    pub running: Option<RunningChild>,
+    /// Send cargo's stdout to stderr, leaving stdout to `watch --json`.
+    pub stdout_to_stderr: bool,
+    /// Re-runs after a failed attempt (`--retries`); 0 runs the command once.
+    pub retries: u32,
+    /// Wait before the first retry, doubled for each one after it.
+    pub retry_delay: Duration,
+}
+
+/// Project file whose whitespace-separated contents are passed to the commands in
+/// [`ARGS_COMMANDS`].
+pub const ARGS_FILE: &str = ".sleek-args";
+
+/// Commands that take the [`ARGS_FILE`] arguments. Others such as `clean`, `fmt` or
+/// `update` reject flags like `--features`.
+pub const ARGS_COMMANDS: &[&str] = &["build", "check", "test", "run", "clippy", "doc", "bench"];
+
+/// Reads the [`ARGS_FILE`] in `project_dir`; a missing file means no extra arguments.
+pub fn load_project_args(project_dir: &Path) -> Result<Vec<String>> {
+    let path = project_dir.join(ARGS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(content.split_whitespace().map(String::from).collect())
+}
+
+/// The [`ARGS_FILE`] arguments for `command`: none unless it is in [`ARGS_COMMANDS`].
+pub fn project_args_for(command: &str, project_dir: &Path) -> Result<Vec<String>> {
+    if !ARGS_COMMANDS.contains(&command) {
+        return Ok(Vec::new());
+    }
+    load_project_args(project_dir)
+}
+
+/// Parses a `KEY=VALUE` pair given to `--env`. The value may be empty or contain `=`.
+pub fn parse_env_pair(pair: &str) -> Result<(String, String), String> {
+    match pair.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        Some(_) => Err(format!(
+            "missing variable name in `{pair}`, expected KEY=VALUE"
+        )),
+        None => Err(format!("missing `=` in `{pair}`, expected KEY=VALUE")),
+    }
+}
+
+/// The cargo invocation for `command`: project default arguments first so that
+/// `extra_args` from the command line can override them, with `options.env` applied.
+pub fn cargo_command(command: &str, extra_args: &[String], options: &ExecOptions) -> Command {
+    let mut cmd = Command::new(cargo_bin());
+    cmd.arg(command);
+    cmd.args(&options.default_args);
+    cmd.args(extra_args);
+    cmd.envs(options.env.iter().map(|(key, value)| (key, value)));
+    cmd
+}
+
+/// Quotes `arg` for a POSIX shell; plain words are left as they are.
+pub fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%^".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// `cmd` as a copy-pasteable shell line: working directory, environment overrides,
+/// program and arguments, each shell-quoted.
+pub fn render_command(cmd: &Command) -> String {
+    let cwd = cmd
+        .get_current_dir()
+        .map(Path::to_path_buf)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."));
+    let mut words = vec![
+        "cd".to_string(),
+        shell_quote(&cwd.to_string_lossy()),
+        "&&".to_string(),
+    ];
+    for (key, value) in cmd.get_envs() {
+        let key = key.to_string_lossy();
+        match value {
+            Some(value) => words.push(format!("{}={}", key, shell_quote(&value.to_string_lossy()))),
+            None => words.push(format!("-u {}", key)),
+        }
+    }
+    if words[3..].iter().any(|word| word.starts_with("-u ")) {
+        words.insert(3, "env".to_string());
+    }
+    words.push(shell_quote(&cmd.get_program().to_string_lossy()));
+    words.extend(
+        cmd.get_args()
+            .map(|arg| shell_quote(&arg.to_string_lossy())),
+    );
+    words.join(" ")
+}
+
+/// Prints what `execute_cargo_command` would run, in order, without running anything.
+fn print_dry_run(command: &str, extra_args: &[String], options: &ExecOptions) {
+    let hooks = options.hooks.get(command);
+    let print_hooks = |lines: &[String]| {
+        for line in lines {
+            println!("{}", render_command(&hook_command(line)));
+        }
+    };
+    output::line(format!("🧪 Dry run: cargo {}", command).bold().cyan());
+    print_hooks(hooks.map(|h| h.pre.as_slice()).unwrap_or_default());
+    println!(
+        "{}",
+        render_command(&cargo_command(command, extra_args, options))
+    );
+    print_hooks(hooks.map(|h| h.post.as_slice()).unwrap_or_default());
+}
+
+/// Marker written in place of output dropped by [`truncate_output`].
+pub const TRUNCATED_MARKER: &str = "[truncated]";
+
+/// Keeps the last `limit` bytes of `output` (where errors usually are), prefixed with
+/// [`TRUNCATED_MARKER`] when anything was dropped. Never splits a UTF-8 character.
+pub fn truncate_output(output: &[u8], limit: usize) -> Vec<u8> {
+    if output.len() <= limit {
+        return output.to_vec();
+    }
+    let mut start = output.len() - limit;
+    while start < output.len() && output[start] & 0xC0 == 0x80 {
+        start += 1;
+    }
+    let mut kept = format!("{} {} bytes omitted\n", TRUNCATED_MARKER, start).into_bytes();
+    kept.extend_from_slice(&output[start..]);
+    kept
+}
+
+/// A spinner showing `message` while a captured command runs, or `None` when disabled or
+/// when `is_terminal` says stdout is not an interactive terminal.
+pub fn spinner(
+    message: &str,
+    enabled: bool,
+    is_terminal: impl FnOnce() -> bool,
+) -> Option<ProgressBar> {
+    if !enabled || !is_terminal() {
+        return None;
+    }
+    let spinner = ProgressBar::with_draw_target(None, ProgressDrawTarget::stdout());
+    spinner.set_message(message.to_string());
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    Some(spinner)
+}
+
+/// Variables that keep cargo's colors and progress bar while its output is piped through
+/// sleek to a terminal of `width` columns. Variables the user set are left alone.
+pub fn terminal_env(
+    stderr_is_terminal: bool,
+    color: bool,
+    width: Option<u16>,
+    is_set: impl Fn(&str) -> bool,
+) -> Vec<(String, String)> {
+    let mut env = Vec::new();
+    if !stderr_is_terminal {
+        return env;
+    }
+    if color && !is_set("CARGO_TERM_COLOR") {
+        env.push(("CARGO_TERM_COLOR".to_string(), "always".to_string()));
+    }
+    // Cargo only accepts `always` progress together with an explicit width.
+    if let Some(width) = width {
+        if !is_set("CARGO_TERM_PROGRESS_WHEN") && !is_set("CARGO_TERM_PROGRESS_WIDTH") {
+            env.push(("CARGO_TERM_PROGRESS_WHEN".to_string(), "always".to_string()));
+            env.push(("CARGO_TERM_PROGRESS_WIDTH".to_string(), width.to_string()));
+        }
+    }
+    env
+}
+
+/// Copies `reader` to `echo` while appending everything to `captured` and, as it arrives,
+/// to `log`. `spinner` is hidden while echoing so the two don't garble each other.
+fn tee(
+    mut reader: impl Read,
+    mut echo: impl Write,
+    captured: Arc<Mutex<Vec<u8>>>,
+    log: Option<Arc<Mutex<fs::File>>>,
+    spinner: Option<ProgressBar>,
+) {
+    let mut buf = [0u8; 8192];
+    while let Ok(n) = reader.read(&mut buf) {
+        if n == 0 {
+            break;
+        }
+        let mut write = || {
+            let _ = echo.write_all(&buf[..n]);
+            let _ = echo.flush();
+        };
+        match &spinner {
+            Some(spinner) => spinner.suspend(write),
+            None => write(),
+        }
+        captured.lock().unwrap().extend_from_slice(&buf[..n]);
+        if let Some(log) = &log {
+            let _ = log.lock().unwrap().write_all(&buf[..n]);
+        }
+    }
+}
+
+/// Process id of the cargo child while it runs, shared so that another thread can stop
+/// it with [`stop_child`] (watch mode restarting on a change).
+pub type RunningChild = Arc<Mutex<Option<u32>>>;
+
+/// Waits for `child`, publishing its pid in `running` meanwhile.
+fn wait_child(child: &mut Child, running: Option<&RunningChild>) -> io::Result<ExitStatus> {
+    if let Some(running) = running {
+        *running.lock().unwrap() = Some(child.id());
+    }
+    let status = child.wait();
+    if let Some(running) = running {
+        *running.lock().unwrap() = None;
+    }
+    status
+}
+
+/// Asks the child recorded in `running`, if any, to terminate, along with everything it
+/// started (rustc, the test binary, the program being run).
+pub fn stop_child(running: &RunningChild) {
+    let Some(pid) = *running.lock().unwrap() else {
+        return;
+    };
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("taskkill");
+        cmd.args(["/T", "/F", "/PID", &pid.to_string()]);
+        cmd
+    } else {
+        // The child leads its own process group, so `-pid` reaches the whole group.
+        let mut cmd = Command::new("kill");
+        cmd.args(["-TERM", "--", &format!("-{}", pid)]);
+        cmd
+    };
+    let _ = cmd.stdout(Stdio::null()).stderr(Stdio::null()).status();
+}
+
+/// Runs `cmd`, echoing its output while collecting stdout and stderr into one buffer
+/// (and streaming them into `log`).
+fn run_captured(
+    mut cmd: Command,
+    spinner: Option<ProgressBar>,
+    log: Option<Arc<Mutex<fs::File>>>,
+    running: Option<&RunningChild>,
+    stdout_to_stderr: bool,
+) -> io::Result<(ExitStatus, Vec<u8>)> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let stdout = child.stdout.take().map(|out| {
+        let captured = Arc::clone(&captured);
+        let spinner = spinner.clone();
+        let log = log.clone();
+        let echo: Box<dyn Write + Send> = if stdout_to_stderr {
+            Box::new(io::stderr())
+        } else {
+            Box::new(io::stdout())
+        };
+        thread::spawn(move || tee(out, echo, captured, log, spinner))
+    });
+    let stderr = child.stderr.take().map(|err| {
+        let captured = Arc::clone(&captured);
+        let spinner = spinner.clone();
+        let log = log.clone();
+        thread::spawn(move || tee(err, io::stderr(), captured, log, spinner))
+    });
+    let status = wait_child(&mut child, running)?;
+    for handle in stdout.into_iter().chain(stderr) {
+        let _ = handle.join();
+    }
+    if let Some(spinner) = spinner {
+        spinner.finish_and_clear();
+    }
+
+    let captured = std::mem::take(&mut *captured.lock().unwrap());
+    Ok((status, captured))
+}
+
+/// A shell invocation of one hook line.
+pub fn hook_command(line: &str) -> Command {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    cmd.arg(line);
+    cmd
+}
+
+/// Runs hook lines in order and returns the status of the first one that failed.
+pub fn run_hooks(
+    phase: &str,
+    lines: &[String],
+    options: &ExecOptions,
+) -> Result<Option<ExitStatus>> {
+    for line in lines {
+        if options.verbose {
+            output::status(format!("🪝 Running {} hook: {}", phase, line));
+        }
+        let mut cmd = hook_command(line);
+        if options.stdout_to_stderr {
+            cmd.stdout(io::stderr());
+        }
+        let status = cmd
+            .status()
+            .with_context(|| format!("Failed to run {} hook `{}`", phase, line))?;
+        if !status.success() {
+            output::line(
+                format!(
+                    "❌ {} hook `{}` failed with exit code {}",
+                    phase,
+                    line,
+                    exit_code(status)
+                )
+                .red(),
+            );
+            return Ok(Some(status));
+        }
+    }
+    Ok(None)
+}
+
+/// Wait before retry number `attempt` (1-based): `base`, doubled for every later attempt.
+pub fn retry_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1 << attempt.saturating_sub(1).min(16))
+}
+
+/// Whether the command was stopped by a signal (e.g. Ctrl-C) rather than exiting; such
+/// runs are not retried.
+pub fn killed_by_signal(status: ExitStatus) -> bool {
+    status.code().is_none()
+}
+
+/// Summary printed after `clean`, given the target directory size before and after.
+pub fn freed_space_message(before: u64, after: u64) -> String {
+    format!("🧹 Freed {}", format_size(before.saturating_sub(after)))
+}
+
+/// The directory `cargo clean` empties: `--target-dir` from `args`, else
+/// `CARGO_TARGET_DIR`, else the workspace's target directory.
+pub fn clean_target_dir(args: &[String]) -> PathBuf {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--target-dir" {
+            if let Some(dir) = args.next() {
+                return PathBuf::from(dir);
+            }
+        } else if let Some(dir) = arg.strip_prefix("--target-dir=") {
+            return PathBuf::from(dir);
+        }
+    }
+    if let Some(dir) = std::env::var_os("CARGO_TARGET_DIR") {
+        return PathBuf::from(dir);
+    }
+    metadata::cargo_metadata(Path::new("."))
+        .map(|metadata| metadata.target_directory)
+        .unwrap_or_else(|_| PathBuf::from("target"))
+}
+
+/// Whether cargo `args` select the release profile: `--release`, `-r` or
+/// `--profile release`, before any `--`.
+pub fn builds_release(args: &[String]) -> bool {
+    let mut args = args.iter().take_while(|arg| *arg != "--");
+    let mut release = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--release" | "-r" | "--profile=release" => release = true,
+            "--profile" => release = args.next().is_some_and(|profile| profile == "release"),
+            other if other.starts_with("--profile=") => release = false,
+            _ => {}
+        }
+    }
+    release
+}
+
+/// Asks before running a command listed under `[confirm]`, failing with [`Cancelled`]
+/// when the answer is no (or `input` ends first).
+pub fn confirm_or_cancel(
+    command: &str,
+    options: &ExecOptions,
+    interactive: bool,
+    input: impl io::BufRead,
+    output: impl Write,
+) -> Result<()> {
+    let confirmed = prompt::confirm_command(
+        command,
+        &options.confirm,
+        options.assume_yes,
+        interactive,
+        input,
+        output,
+    )?;
+    if !confirmed {
+        bail!(Cancelled {
+            command: command.to_string()
+        });
+    }
+    Ok(())
+}
+
+/// Fails with a friendly hint when `dir` is not a crate or workspace root.
+pub fn ensure_cargo_project(dir: &Path) -> Result<()> {
+    if dir.join("Cargo.toml").is_file() {
+        return Ok(());
+    }
+    let shown = if dir == Path::new(".") {
+        "the current directory".to_string()
+    } else {
+        dir.display().to_string()
+    };
+    bail!(
+        "No Cargo.toml found in {shown}.\n   \
+         Run cargo sleek from a crate or workspace root, \
+         or pass --no-project-check to skip this check."
+    )
+}
+
+pub fn execute_cargo_command(
+    stats_path: &Path,
+    command: &str,
+    extra_args: &[String],
+    options: &ExecOptions,
+) -> Result<ExitStatus> {
+    if !options.skip_project_check {
+        ensure_cargo_project(Path::new("."))?;
+    }
+    let options = &ExecOptions {
+        default_args: project_args_for(command, Path::new("."))?,
+        ..options.clone()
+    };
+    if options.dry_run {
+        print_dry_run(command, extra_args, options);
+        return Ok(ExitStatus::default());
+    }
+    confirm_or_cancel(
+        command,
+        options,
+        io::stdin().is_terminal(),
+        io::stdin().lock(),
+        io::stdout(),
+    )?;
+    let hooks = options.hooks.get(command);
+    if let Some(hooks) = hooks {
+        if let Some(failed) = run_hooks("pre", &hooks.pre, options)? {
+            if !hooks.allow_failure {
+                output::line(
+                    format!("❌ Skipped `cargo {}` after a failing pre hook.", command).red(),
+                );
+                return Ok(failed);
+            }
+        }
+    }
+    output::status(format!(
+        "🚀 Running Cargo command: {}",
+        command.bold().cyan()
+    ));
+
+    if options.verbose {
+        output::line(format!(
+            "🔧 Executing: {}",
+            render_command(&cargo_command(command, extra_args, options))
+        ));
+    }
+
+    let clean_dir = (command == "clean")
+        .then(|| clean_target_dir(extra_args))
+        .filter(|dir| dir.is_dir());
+    let size_before = clean_dir.as_deref().map(dir_size);
+
+    let run_log = match &options.log_dir {
+        Some(dir) => {
+            let words: Vec<String> = ["cargo", command]
+                .into_iter()
+                .chain(options.default_args.iter().map(String::as_str))
+                .chain(extra_args.iter().map(String::as_str))
+                .map(shell_quote)
+                .collect();
+            Some(logs::start_log(dir, command, &words.join(" "))?)
+        }
+        None => None,
+    };
+    let log_path = run_log.as_ref().map(|run_log| run_log.path.clone());
+    let log = run_log.map(|run_log| Arc::new(Mutex::new(run_log.file)));
+    let record_tests = command == "test" && options.record_test_times;
+    // Cargo sees a pipe instead of the terminal while its output is logged or timed.
+    let keep_terminal = (log.is_some() || record_tests) && io::stderr().is_terminal();
+
+    let capture = options.log_file.is_some() || log.is_some() || record_tests;
+    let start = Instant::now();
+    let mut attempt = 1;
+    let (status, output) = loop {
+        let mut cmd = cargo_command(command, extra_args, options);
+        #[cfg(unix)]
+        if options.running.is_some() {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        if keep_terminal {
+            cmd.envs(terminal_env(
+                true,
+                colored::control::SHOULD_COLORIZE.should_colorize(),
+                terminal_size::terminal_size_of(io::stderr()).map(|(width, _)| width.0),
+                |key| std::env::var_os(key).is_some() || options.env.iter().any(|(k, _)| k == key),
+            ));
+        }
+        let (status, output) = if capture {
+            // Cargo draws its own progress bar when the terminal is kept.
+            let spinner = spinner(
+                &format!("cargo {}", command),
+                options.spinner && !keep_terminal,
+                || io::stdout().is_terminal(),
+            );
+            run_captured(
+                cmd,
+                spinner,
+                log.clone(),
+                options.running.as_ref(),
+                options.stdout_to_stderr,
+            )
+            .map_err(|err| spawn_error(err, "Failed to execute cargo command"))?
+        } else {
+            if options.stdout_to_stderr {
+                cmd.stdout(io::stderr());
+            }
+            let status = cmd
+                .spawn()
+                .and_then(|mut child| wait_child(&mut child, options.running.as_ref()))
+                .map_err(|err| spawn_error(err, "Failed to execute cargo command"))?;
+            (status, Vec::new())
+        };
+        if status.success() || attempt > options.retries || killed_by_signal(status) {
+            break (status, output);
+        }
+        let delay = retry_delay(options.retry_delay, attempt);
+        output::line(
+            format!(
+                "🔁 Attempt {}/{} failed with exit code {}; retrying in {:.1?}",
+                attempt,
+                options.retries + 1,
+                exit_code(status),
+                delay
+            )
+            .yellow(),
+        );
+        if let Some(log) = &log {
+            let _ = writeln!(
+                log.lock().unwrap(),
+                "\n# attempt {} failed with exit code {}\n",
+                attempt,
+                exit_code(status)
+            );
+        }
+        thread::sleep(delay);
+        attempt += 1;
+    };
+    let duration = start.elapsed();
+    if let (Some(log_path), Some(log)) = (&log_path, &log) {
+        log.lock()
+            .unwrap()
+            .write_all(logs::log_footer(duration, exit_code(status)).as_bytes())
+            .with_context(|| format!("Failed to write {}", log_path.display()))?;
+        output::status(format!("📝 Log: {}", log_path.display()));
+    }
+    if options.retries > 0 {
+        let outcome = if status.success() {
+            "Succeeded"
+        } else {
+            "Failed"
+        };
+        output::line(format!(
+            "🔁 {} after {} attempt{}",
+            outcome,
+            attempt,
+            if attempt == 1 { "" } else { "s" }
+        ));
+    }
+
+    if let Some(log_file) = &options.log_file {
+        let logged = match options.limit_output {
+            Some(limit) => truncate_output(&output, limit),
+            None => output.clone(),
+        };
+        fs::write(log_file, logged)
+            .with_context(|| format!("Failed to write log file {}", log_file.display()))?;
+    }
+    let all_args: Vec<String> = options
+        .default_args
+        .iter()
+        .chain(extra_args)
+        .cloned()
+        .collect();
+    if command == "build" && builds_release(&all_args) && status.success() {
+        // Size tracking is a bonus; a failed lookup or write shouldn't fail the build.
+        let recorded = size::existing_release().and_then(|binaries| {
+            size::record_release_sizes(Path::new(history::HISTORY_FILE), &binaries)
+        });
+        if let Err(err) = recorded {
+            output::warning(format!("⚠️  Could not record release binary sizes: {err:#}").yellow());
+        }
+    }
+    if let (Some(dir), Some(before)) = (&clean_dir, size_before) {
+        if status.success() {
+            output::line(freed_space_message(before, dir_size(dir)).green());
+        }
+    }
+    let key = stats::tracking_key(command, extra_args, options.track_args);
+    stats::track_command_exit(stats_path, &key, status.code(), duration)?;
+    if let Some(alias) = &options.alias {
+        stats::track_command_exit(stats_path, alias, status.code(), duration)?;
+    }
+    if record_tests {
+        let recorded = test_times::record_test_run(
+            Path::new(history::HISTORY_FILE),
+            &String::from_utf8_lossy(&output),
+            duration,
+        );
+        if let Err(err) = recorded {
+            output::warning(format!("⚠️  Could not record test times: {err:#}").yellow());
+        }
+    }
+    if let Some(report) = &options.report {
+        let run = RunReport {
+            command: command.to_string(),
+            args: extra_args.to_vec(),
+            exit_code: status.code(),
+            duration_ms: duration.as_millis() as u64,
+            success: status.success(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        };
+        if let Err(err) = report::write_report(&report.path, &run, report.append) {
+            output::warning(format!("⚠️  Could not write the run report: {err:#}").yellow());
+        }
+    }
+    if !status.success() {
+        output::line(format!(
+            "❌ Command failed with exit code: {}",
+            exit_code(status)
+        ));
+    }
+    if let Some(hooks) = hooks {
+        run_hooks("post", &hooks.post, options)?;
+    }
+
+    Ok(status)
+}
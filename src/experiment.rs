@@ -0,0 +1,225 @@
+//! Release profile experiments: builds a matrix of codegen settings and compares build
+//! time against binary size (`experiment`).
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::executor;
+use crate::metadata;
+use crate::output;
+use crate::performance::{self, format_size};
+use crate::prompt;
+
+/// `codegen-units` values tried when none are given.
+pub const DEFAULT_CODEGEN_UNITS: [u32; 2] = [1, 16];
+
+/// `lto` settings tried when none are given.
+pub const DEFAULT_LTO: [&str; 3] = ["off", "thin", "fat"];
+
+/// One combination of release profile settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Knobs {
+    pub codegen_units: u32,
+    pub lto: String,
+    /// `None` keeps the profile's own `opt-level`.
+    pub opt_level: Option<String>,
+}
+
+impl Knobs {
+    /// Profile overrides as `CARGO_PROFILE_RELEASE_*` variables, so no manifest is edited.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let mut vars = vec![
+            (
+                "CARGO_PROFILE_RELEASE_CODEGEN_UNITS".to_string(),
+                self.codegen_units.to_string(),
+            ),
+            ("CARGO_PROFILE_RELEASE_LTO".to_string(), self.lto.clone()),
+        ];
+        if let Some(level) = &self.opt_level {
+            vars.push(("CARGO_PROFILE_RELEASE_OPT_LEVEL".to_string(), level.clone()));
+        }
+        vars
+    }
+}
+
+/// Every combination of the given settings, codegen units varying slowest.
+pub fn experiment_matrix(
+    codegen_units: &[u32],
+    lto: &[String],
+    opt_levels: &[String],
+) -> Vec<Knobs> {
+    let levels: Vec<Option<String>> = if opt_levels.is_empty() {
+        vec![None]
+    } else {
+        opt_levels.iter().cloned().map(Some).collect()
+    };
+    let mut matrix = Vec::new();
+    for &units in codegen_units {
+        for lto in lto {
+            for level in &levels {
+                matrix.push(Knobs {
+                    codegen_units: units,
+                    lto: lto.clone(),
+                    opt_level: level.clone(),
+                });
+            }
+        }
+    }
+    matrix
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExperimentResult {
+    pub knobs: Knobs,
+    pub duration: Duration,
+    /// Combined size of the workspace's release binaries.
+    pub binary_bytes: u64,
+}
+
+/// Whether each result is Pareto-optimal: no other result builds at least as fast and
+/// at least as small while being strictly better in one of the two.
+pub fn pareto_front(results: &[ExperimentResult]) -> Vec<bool> {
+    results
+        .iter()
+        .map(|result| {
+            !results.iter().any(|other| {
+                other.duration <= result.duration
+                    && other.binary_bytes <= result.binary_bytes
+                    && (other.duration < result.duration
+                        || other.binary_bytes < result.binary_bytes)
+            })
+        })
+        .collect()
+}
+
+/// Release build with `knobs`; returns its duration and binary size, `None` if it failed.
+fn measure(knobs: &Knobs, members: &[String]) -> Result<Option<(Duration, u64)>> {
+    let start = Instant::now();
+    let output = Command::new(executor::cargo_bin())
+        .args([
+            "build",
+            "--release",
+            "--quiet",
+            "--message-format=json-render-diagnostics",
+        ])
+        .envs(knobs.env_vars())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo build --release"))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let duration = start.elapsed();
+    let bytes = performance::parse_artifacts(&String::from_utf8_lossy(&output.stdout))
+        .iter()
+        .filter(|artifact| members.contains(&artifact.package_id))
+        .filter_map(|artifact| artifact.executable.as_ref())
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum();
+    Ok(Some((duration, bytes)))
+}
+
+fn print_results(results: &[ExperimentResult]) {
+    let pareto = pareto_front(results);
+    output::line(format!(
+        "\n{:<14} {:<6} {:<10} {:>12} {:>12}",
+        "Codegen units", "LTO", "opt-level", "Build time", "Binary size"
+    ));
+    for (result, optimal) in results.iter().zip(pareto) {
+        let row = format!(
+            "{:<14} {:<6} {:<10} {:>12.2?} {:>12}",
+            result.knobs.codegen_units,
+            result.knobs.lto,
+            result.knobs.opt_level.as_deref().unwrap_or("profile"),
+            result.duration,
+            format_size(result.binary_bytes)
+        );
+        if optimal {
+            output::line(format!("{} {}", row.green().bold(), "◀ pareto".green()));
+        } else {
+            output::line(row.dimmed());
+        }
+    }
+    output::line(
+        "\n💡 Rows marked pareto are not beaten on both build time and size; \
+         pick the one whose trade-off suits you.",
+    );
+}
+
+pub fn run_experiment(project_dir: &Path, matrix: &[Knobs], yes: bool) -> Result<()> {
+    if matrix.is_empty() {
+        bail!("Nothing to measure: the experiment matrix is empty");
+    }
+    output::status(
+        format!(
+            "🧪 Running {} clean release builds, one per combination.",
+            matrix.len()
+        )
+        .yellow(),
+    );
+    output::status("   Press Ctrl-C to stop early; finished measurements are kept.");
+    if !prompt::confirm(
+        "🧹 Every build starts from `cargo clean`, deleting the target directory. Continue?",
+        yes,
+    )? {
+        output::line("❌ Cancelled.".yellow());
+        return Ok(());
+    }
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&interrupted);
+    ctrlc::set_handler(move || flag.store(true, Ordering::SeqCst))
+        .context("Failed to install Ctrl-C handler")?;
+
+    let members = metadata::cargo_metadata(project_dir)?.workspace_members;
+    let mut results = Vec::new();
+    for (i, knobs) in matrix.iter().enumerate() {
+        performance::cargo_clean()?;
+        print!(
+            "   [{}/{}] codegen-units={} lto={}{} ... ",
+            i + 1,
+            matrix.len(),
+            knobs.codegen_units,
+            knobs.lto,
+            knobs
+                .opt_level
+                .as_ref()
+                .map(|level| format!(" opt-level={}", level))
+                .unwrap_or_default()
+        );
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        let measured = measure(knobs, &members)?;
+        if interrupted.load(Ordering::SeqCst) {
+            println!("{}", "interrupted".yellow());
+            break;
+        }
+        match measured {
+            Some((duration, binary_bytes)) => {
+                println!("{:.2?}, {}", duration, format_size(binary_bytes));
+                results.push(ExperimentResult {
+                    knobs: knobs.clone(),
+                    duration,
+                    binary_bytes,
+                });
+            }
+            None => println!("{}", "build failed".red()),
+        }
+    }
+
+    if results.is_empty() {
+        output::line("⚠️  No successful builds to compare.".yellow());
+        return Ok(());
+    }
+    print_results(&results);
+    if interrupted.load(Ordering::SeqCst) {
+        output::line("⚠️  Stopped early; results are partial.".yellow());
+    }
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! Cross-checks `[features]` against optional dependencies.
+
+use crate::output;
+use anyhow::{Context, Result};
+use colored::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item};
+
+const DEPENDENCY_SECTIONS: &[&str] = &["dependencies", "build-dependencies"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureIssue {
+    pub feature: String,
+    pub value: String,
+    pub problem: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct FeatureReport {
+    /// Optional dependencies no `[features]` entry refers to.
+    pub orphaned_optional: Vec<String>,
+    /// Feature entries that point at non-optional or missing dependencies.
+    pub issues: Vec<FeatureIssue>,
+}
+
+/// Collects every declared dependency (including `[target.*]` tables) and whether it's optional.
+fn collect_dependencies(manifest: &DocumentMut) -> BTreeMap<String, bool> {
+    let mut tables: Vec<&Item> = DEPENDENCY_SECTIONS
+        .iter()
+        .filter_map(|section| manifest.get(section))
+        .collect();
+    if let Some(targets) = manifest.get("target").and_then(Item::as_table_like) {
+        for (_, target) in targets.iter() {
+            tables.extend(
+                DEPENDENCY_SECTIONS
+                    .iter()
+                    .filter_map(|section| target.get(section)),
+            );
+        }
+    }
+
+    let mut deps = BTreeMap::new();
+    for table in tables.into_iter().filter_map(Item::as_table_like) {
+        for (name, item) in table.iter() {
+            let optional = item
+                .as_table_like()
+                .and_then(|spec| spec.get("optional"))
+                .and_then(Item::as_bool)
+                .unwrap_or(false);
+            let entry = deps.entry(name.to_string()).or_insert(false);
+            *entry |= optional;
+        }
+    }
+    deps
+}
+
+pub fn analyze_features(manifest: &str) -> Result<FeatureReport> {
+    let manifest: DocumentMut = manifest.parse().context("Failed to parse Cargo.toml")?;
+    let deps = collect_dependencies(&manifest);
+
+    let mut features: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    if let Some(table) = manifest.get("features").and_then(Item::as_table_like) {
+        for (name, item) in table.iter() {
+            let values = item
+                .as_array()
+                .map(|array| {
+                    array
+                        .iter()
+                        .filter_map(|v| v.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            features.insert(name.to_string(), values);
+        }
+    }
+
+    let mut report = FeatureReport::default();
+    let mut referenced: BTreeSet<String> = BTreeSet::new();
+    let mut issue = |feature: &str, value: &str, problem: &str| {
+        report.issues.push(FeatureIssue {
+            feature: feature.to_string(),
+            value: value.to_string(),
+            problem: problem.to_string(),
+        });
+    };
+
+    for (feature, values) in &features {
+        for value in values {
+            if let Some(dep) = value.strip_prefix("dep:") {
+                referenced.insert(dep.to_string());
+                match deps.get(dep) {
+                    Some(true) => {}
+                    Some(false) => issue(feature, value, "dependency is not optional"),
+                    None => issue(feature, value, "no such dependency"),
+                }
+            } else if let Some((target, _)) = value.split_once('/') {
+                let (dep, weak) = match target.strip_suffix('?') {
+                    Some(dep) => (dep, true),
+                    None => (target, false),
+                };
+                referenced.insert(dep.to_string());
+                match deps.get(dep) {
+                    Some(false) if weak => {
+                        issue(feature, value, "`?` requires an optional dependency")
+                    }
+                    Some(_) => {}
+                    None => issue(feature, value, "no such dependency"),
+                }
+            } else if !features.contains_key(value) {
+                referenced.insert(value.to_string());
+                match deps.get(value.as_str()) {
+                    Some(true) => {}
+                    Some(false) => issue(feature, value, "dependency is not optional"),
+                    None => issue(feature, value, "no such feature or dependency"),
+                }
+            }
+        }
+    }
+
+    report.orphaned_optional = deps
+        .iter()
+        .filter(|(name, optional)| **optional && !referenced.contains(*name))
+        .map(|(name, _)| name.clone())
+        .collect();
+    Ok(report)
+}
+
+pub fn check_features(project_dir: &Path) -> Result<()> {
+    output::status("🔍 Cross-checking features and optional dependencies...");
+    let manifest =
+        fs::read_to_string(project_dir.join("Cargo.toml")).context("Failed to read Cargo.toml")?;
+    let report = analyze_features(&manifest)?;
+
+    if report.orphaned_optional.is_empty() && report.issues.is_empty() {
+        output::line("✅ Features and optional dependencies are consistent!".green());
+        return Ok(());
+    }
+    if !report.orphaned_optional.is_empty() {
+        output::line("🚨 Optional dependencies not enabled by any feature:".red());
+        for dep in &report.orphaned_optional {
+            output::line(format!("   • {}", dep));
+        }
+    }
+    if !report.issues.is_empty() {
+        output::line("🚨 Feature entries with invalid targets:".red());
+        for issue in &report.issues {
+            output::line(format!(
+                "   • {} → \"{}\": {}",
+                issue.feature.bold(),
+                issue.value,
+                issue.problem
+            ));
+        }
+    }
+    Ok(())
+}
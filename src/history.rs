@@ -0,0 +1,483 @@
+//! Persistent history of `build-time` measurements.
+
+use anyhow::{bail, Context, Result};
+use chrono::DateTime;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::output;
+use crate::performance;
+
+pub const HISTORY_FILE: &str = "build_history.json";
+
+/// Number of most recent runs included in the rolling average.
+pub const ROLLING_WINDOW: usize = 10;
+
+/// Slowdown against the recent median, in percent, above which a warning is printed.
+pub const REGRESSION_WARNING: f64 = 10.0;
+
+/// Number of slowest crates kept with each run.
+pub const TOP_CRATES: usize = 5;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CrateTime {
+    pub name: String,
+    pub seconds: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BuildRecord {
+    pub timestamp: u64,
+    pub duration_ms: u64,
+    /// Custom cargo profile, `None` for the default dev profile.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Number of crates compiled (not fresh) during the run.
+    #[serde(default)]
+    pub crate_count: usize,
+    #[serde(default)]
+    pub top_crates: Vec<CrateTime>,
+    /// Short hash of the `HEAD` commit at measurement time.
+    #[serde(default)]
+    pub commit: Option<String>,
+    /// `cold` for builds from a clean target directory, `warm` for incremental rebuilds.
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Peak resident memory of the build's process tree.
+    #[serde(default)]
+    pub peak_rss_bytes: Option<u64>,
+    /// Feature set measured by `build-time --matrix`, e.g. `serde,tls` or `none`.
+    #[serde(default)]
+    pub features: Option<String>,
+    /// Rustup toolchain measured by `build-time --toolchains`, e.g. `beta`.
+    #[serde(default)]
+    pub toolchain: Option<String>,
+    /// Target triple given with `--target`; `None` for the host.
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Names of the crates compiled (not fresh); `None` for runs recorded before this
+    /// was tracked.
+    #[serde(default)]
+    pub recompiled: Option<Vec<String>>,
+}
+
+/// The runs measured against each other: same profile, kind, feature set and target, so
+/// a release or `--features` build is never compared with plain dev builds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildSlice {
+    pub profile: Option<String>,
+    pub kind: Option<String>,
+    pub features: Option<String>,
+    pub target: Option<String>,
+}
+
+impl BuildSlice {
+    pub fn of(run: &BuildRecord) -> BuildSlice {
+        BuildSlice {
+            profile: run.profile.clone(),
+            kind: run.kind.clone(),
+            features: run.features.clone(),
+            target: run.target.clone(),
+        }
+    }
+
+    pub fn contains(&self, run: &BuildRecord) -> bool {
+        run.profile == self.profile
+            && run.kind == self.kind
+            && run.features == self.features
+            && run.target == self.target
+    }
+
+    /// e.g. `release (cold, features serde,tls, wasm32-unknown-unknown)`.
+    pub fn label(&self) -> String {
+        let details: Vec<String> = [
+            self.kind.clone(),
+            self.features.as_ref().map(|f| format!("features {}", f)),
+            self.target.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let profile = self.profile.as_deref().unwrap_or("dev");
+        if details.is_empty() {
+            profile.to_string()
+        } else {
+            format!("{} ({})", profile, details.join(", "))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BuildHistory {
+    #[serde(default)]
+    pub runs: Vec<BuildRecord>,
+    /// Average duration of the last [`ROLLING_WINDOW`] runs in the latest run's
+    /// [`BuildSlice`].
+    #[serde(default)]
+    pub rolling_average_ms: u64,
+    /// Longest single build ever recorded in the latest run's [`BuildSlice`].
+    #[serde(default)]
+    pub worst: Option<BuildRecord>,
+    /// Named reference runs saved with `build-time --baseline`.
+    #[serde(default)]
+    pub baselines: BTreeMap<String, BuildRecord>,
+    /// Release binary sizes recorded by `size`.
+    #[serde(default)]
+    pub sizes: Vec<SizeRecord>,
+    /// `cargo test` runs made through the executor.
+    #[serde(default)]
+    pub tests: Vec<TestRunRecord>,
+    /// Benchmark medians recorded by `bench`.
+    #[serde(default)]
+    pub benches: Vec<BenchRecord>,
+    /// Startup times measured by `run --measure-startup`.
+    #[serde(default)]
+    pub startups: Vec<StartupRecord>,
+    /// Latest rebuild reason found by `why-rebuild`, by crate name.
+    #[serde(default)]
+    pub rebuild_reasons: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TestTime {
+    pub name: String,
+    pub seconds: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TestRunRecord {
+    pub timestamp: u64,
+    /// Wall-clock time of the whole `cargo test` invocation, including compilation.
+    pub duration_ms: u64,
+    /// Time spent running test binaries, summed from libtest's `finished in` lines.
+    pub suite_ms: u64,
+    /// Slowest tests, slowest first. Suites stand in for tests when libtest does not
+    /// report per-test times.
+    pub slowest: Vec<TestTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BenchRecord {
+    pub timestamp: u64,
+    #[serde(default)]
+    pub commit: Option<String>,
+    /// Median time per iteration in nanoseconds, by benchmark name.
+    pub results: BTreeMap<String, f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct StartupRecord {
+    pub timestamp: u64,
+    #[serde(default)]
+    pub commit: Option<String>,
+    pub binary: String,
+    /// Whether the time to the first output byte was measured instead of the time to exit.
+    #[serde(default)]
+    pub first_output: bool,
+    pub runs: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SizeRecord {
+    pub timestamp: u64,
+    pub binary: String,
+    pub bytes: u64,
+    #[serde(default)]
+    pub commit: Option<String>,
+}
+
+impl BuildHistory {
+    /// Appends a run, refreshing the rolling average and the worst build of its slice, so
+    /// that a cold build or a quick check does not skew a warm build's.
+    pub fn record(&mut self, record: BuildRecord) {
+        let slice = BuildSlice::of(&record);
+        self.runs.push(record);
+
+        let window: Vec<u64> = self.recent(&slice).map(|run| run.duration_ms).collect();
+        self.rolling_average_ms = window.iter().sum::<u64>() / window.len() as u64;
+        self.worst = self
+            .runs
+            .iter()
+            .filter(|run| slice.contains(run))
+            .rev()
+            .max_by_key(|run| run.duration_ms)
+            .cloned();
+    }
+
+    /// The last [`ROLLING_WINDOW`] runs in `slice`, newest first.
+    fn recent<'a>(&'a self, slice: &'a BuildSlice) -> impl Iterator<Item = &'a BuildRecord> {
+        self.runs
+            .iter()
+            .rev()
+            .filter(move |run| slice.contains(run))
+            .take(ROLLING_WINDOW)
+    }
+
+    /// Median duration of the last [`ROLLING_WINDOW`] runs in `slice`.
+    pub fn median_ms(&self, slice: &BuildSlice) -> Option<u64> {
+        let mut durations: Vec<u64> = self.recent(slice).map(|run| run.duration_ms).collect();
+        if durations.is_empty() {
+            return None;
+        }
+        durations.sort_unstable();
+        let mid = durations.len() / 2;
+        Some(if durations.len().is_multiple_of(2) {
+            (durations[mid - 1] + durations[mid]) / 2
+        } else {
+            durations[mid]
+        })
+    }
+}
+
+/// How much slower `current_ms` is than `reference_ms`, in percent (negative when faster).
+pub fn slowdown_percent(current_ms: u64, reference_ms: u64) -> f64 {
+    if reference_ms == 0 {
+        return 0.0;
+    }
+    (current_ms as f64 - reference_ms as f64) / reference_ms as f64 * 100.0
+}
+
+/// Short hash of `HEAD`, if the working directory is a git checkout.
+pub fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Warns when `record` is notably slower than the median of the preceding runs.
+pub fn report_regression(record: &BuildRecord, median_ms: Option<u64>) {
+    let Some(median_ms) = median_ms else {
+        return;
+    };
+    let percent = slowdown_percent(record.duration_ms, median_ms);
+    if percent > REGRESSION_WARNING {
+        output::line(
+            format!(
+                "\n⚠️  {:.0}% slower than the median of the last {} runs ({})",
+                percent,
+                ROLLING_WINDOW,
+                format_ms(median_ms)
+            )
+            .yellow(),
+        );
+    } else {
+        output::line(format!(
+            "\n📉 Within {:.0}% of the recent median ({})",
+            percent.abs(),
+            format_ms(median_ms)
+        ));
+    }
+}
+
+/// Prints how `record` compares with the saved baseline `name`.
+pub fn compare_with_baseline(
+    history: &BuildHistory,
+    name: &str,
+    record: &BuildRecord,
+) -> Result<()> {
+    let Some(baseline) = history.baselines.get(name) else {
+        bail!(
+            "No baseline named `{}`; save one with `build-time --baseline {}`",
+            name,
+            name
+        );
+    };
+    let percent = slowdown_percent(record.duration_ms, baseline.duration_ms);
+    let change = format!("{:+.1}%", percent);
+    output::line(format!(
+        "⚖️  Baseline `{}` ({}{}): {} → {} ({})",
+        name.bold(),
+        format_timestamp(baseline.timestamp),
+        baseline
+            .commit
+            .as_ref()
+            .map(|c| format!(", {}", c))
+            .unwrap_or_default(),
+        format_ms(baseline.duration_ms),
+        format_ms(record.duration_ms),
+        if percent > REGRESSION_WARNING {
+            change.red()
+        } else {
+            change.green()
+        }
+    ));
+    output::line(format!(
+        "   Crates compiled: {} → {}",
+        baseline.crate_count, record.crate_count
+    ));
+    Ok(())
+}
+
+/// Reads the history at `path`, empty if there is none. A file that doesn't parse is
+/// moved aside with a warning so the next save can't overwrite the runs it holds.
+pub fn load_history(path: &Path) -> BuildHistory {
+    let Ok(content) = fs::read_to_string(path) else {
+        return BuildHistory::default();
+    };
+    match serde_json::from_str(&content) {
+        Ok(history) => history,
+        Err(err) => {
+            let stamp = chrono::Utc::now().format("%Y%m%d%H%M%S%3f");
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let backup = path.with_file_name(format!("{stem}.{stamp}.bak.json"));
+            let moved = fs::rename(path, &backup);
+            output::warning(
+                match moved {
+                    Ok(()) => format!(
+                        "⚠️  Could not parse {} ({}); moved it to {} and started a new history",
+                        path.display(),
+                        err,
+                        backup.display()
+                    ),
+                    Err(move_err) => format!(
+                        "⚠️  Could not parse {} ({}) or move it aside ({})",
+                        path.display(),
+                        err,
+                        move_err
+                    ),
+                }
+                .yellow(),
+            );
+            BuildHistory::default()
+        }
+    }
+}
+
+pub fn save_history(path: &Path, history: &BuildHistory) -> Result<()> {
+    let json = serde_json::to_string_pretty(history)?;
+    fs::write(path, json).context("Failed to write build history file")
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+/// Profile and kind of `run`, e.g. `release (cold)`.
+fn profile_label(run: &BuildRecord) -> String {
+    let profile = run.profile.as_deref().unwrap_or("dev");
+    match &run.kind {
+        Some(kind) => format!("{} ({})", profile, kind),
+        None => profile.to_string(),
+    }
+}
+
+fn format_ms(ms: u64) -> String {
+    format!("{:.2}s", ms as f64 / 1000.0)
+}
+
+pub fn show_history(path: &Path) -> Result<()> {
+    let history = load_history(path);
+    if history.runs.is_empty() {
+        output::line("📊 No build history recorded yet.".yellow());
+        return Ok(());
+    }
+
+    output::line("📊 Build History:".bold().cyan());
+    output::line(format!(
+        "{:<4} {:<20} {:>12} {:<14} {:>7} {:>10} {:<9} Slowest",
+        "#", "Date", "Duration", "Profile", "Crates", "Peak mem", "Commit"
+    ));
+    for (i, run) in history.runs.iter().enumerate() {
+        output::line(format!(
+            "{:<4} {:<20} {:>12} {:<14} {:>7} {:>10} {:<9} {}",
+            i + 1,
+            format_timestamp(run.timestamp),
+            format_ms(run.duration_ms),
+            profile_label(run),
+            run.crate_count,
+            run.peak_rss_bytes
+                .map(performance::format_size)
+                .unwrap_or_else(|| "-".to_string()),
+            run.commit.as_deref().unwrap_or("-"),
+            run.top_crates
+                .first()
+                .map(|c| format!("{} ({:.1}s)", c.name, c.seconds))
+                .unwrap_or_default()
+        ));
+    }
+
+    // The average and the worst build cover the runs like the latest one.
+    let slice = BuildSlice::of(history.runs.last().unwrap());
+    output::line(format!(
+        "\n📈 Rolling average of {} builds (last {}): {}",
+        slice.label(),
+        history.recent(&slice).count(),
+        format_ms(history.rolling_average_ms)
+    ));
+    if let Some(worst) = &history.worst {
+        output::line(format!(
+            "🐢 Worst {} build: {} on {}",
+            BuildSlice::of(worst).label(),
+            format_ms(worst.duration_ms).red(),
+            format_timestamp(worst.timestamp)
+        ));
+    }
+    for (name, baseline) in &history.baselines {
+        output::line(format!(
+            "📌 Baseline `{}`: {} on {}",
+            name,
+            format_ms(baseline.duration_ms),
+            format_timestamp(baseline.timestamp)
+        ));
+    }
+    Ok(())
+}
+
+/// Lists recorded binary sizes per binary, with the change from the previous entry.
+pub fn show_size_history(path: &Path) -> Result<()> {
+    let history = load_history(path);
+    if history.sizes.is_empty() {
+        output::line("📦 No binary sizes recorded yet.".yellow());
+        return Ok(());
+    }
+
+    let mut binaries: BTreeMap<&str, Vec<&SizeRecord>> = BTreeMap::new();
+    for record in &history.sizes {
+        binaries.entry(&record.binary).or_default().push(record);
+    }
+    for (binary, records) in binaries {
+        output::line(format!("📦 Size history of {}:", binary).bold().cyan());
+        output::line(format!(
+            "{:<20} {:>12} {:>12} {:<9}",
+            "Date", "Size", "Change", "Commit"
+        ));
+        let mut previous: Option<u64> = None;
+        for record in records {
+            let change = match previous {
+                Some(prev) if prev > 0 => {
+                    let percent = slowdown_percent(record.bytes, prev);
+                    let text = format!("{:>+11.1}%", percent);
+                    if percent > 0.0 {
+                        text.red()
+                    } else {
+                        text.green()
+                    }
+                }
+                _ => format!("{:>12}", "-").normal(),
+            };
+            output::line(format!(
+                "{:<20} {:>12} {} {:<9}",
+                format_timestamp(record.timestamp),
+                crate::performance::format_size(record.bytes),
+                change,
+                record.commit.as_deref().unwrap_or("-")
+            ));
+            previous = Some(record.bytes);
+        }
+    }
+    Ok(())
+}
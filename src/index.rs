@@ -0,0 +1,104 @@
+//! crates.io sparse index and API queries, served through [`crate::cache`].
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::cache;
+
+const INDEX_URL: &str = "https://index.crates.io";
+const API_URL: &str = "https://crates.io/api/v1/crates";
+
+/// One published version of a crate, as listed in the index.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct IndexEntry {
+    pub name: String,
+    pub vers: String,
+    #[serde(default)]
+    pub yanked: bool,
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+    /// Features using newer syntax (`dep:`, `?`), listed separately by the index.
+    #[serde(default)]
+    pub features2: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub deps: Vec<IndexDependency>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct IndexDependency {
+    pub name: String,
+    #[serde(default)]
+    pub optional: bool,
+}
+
+impl IndexEntry {
+    /// Whether `feature` can be enabled on this version: declared in `[features]` or an
+    /// optional dependency's implicit feature (unless hidden behind a `dep:` reference).
+    pub fn has_feature(&self, feature: &str) -> bool {
+        if self.features.contains_key(feature) || self.features2.contains_key(feature) {
+            return true;
+        }
+        let dep_syntax = format!("dep:{feature}");
+        let hidden = self
+            .features
+            .values()
+            .chain(self.features2.values())
+            .flatten()
+            .any(|value| *value == dep_syntax);
+        !hidden
+            && self
+                .deps
+                .iter()
+                .any(|dep| dep.optional && dep.name == feature)
+    }
+}
+
+/// A published version as reported by the crates.io API, with its publish date.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct PublishedVersion {
+    pub num: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+#[derive(Deserialize)]
+struct VersionsResponse {
+    versions: Vec<PublishedVersion>,
+}
+
+/// Path of a crate's file within the sparse index.
+pub fn index_path(name: &str) -> String {
+    let name = name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
+
+/// Parses the newline-delimited JSON body of an index file, skipping malformed lines.
+pub fn parse_index(body: &str) -> Vec<IndexEntry> {
+    body.lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Fetches all published versions of `name` from the sparse index.
+pub fn fetch_versions(name: &str, offline: bool) -> Result<Vec<IndexEntry>> {
+    let url = format!("{INDEX_URL}/{}", index_path(name));
+    let body = cache::fetch(&url, offline)
+        .with_context(|| format!("Failed to query the crates.io index for {name}"))?;
+    Ok(parse_index(&body))
+}
+
+/// Fetches every version of `name` with its publish date from the crates.io API.
+pub fn fetch_published_versions(name: &str, offline: bool) -> Result<Vec<PublishedVersion>> {
+    let body = cache::fetch(&format!("{API_URL}/{name}/versions"), offline)
+        .with_context(|| format!("Failed to query crates.io for {name}"))?;
+    let response: VersionsResponse =
+        serde_json::from_str(&body).context("Failed to parse crates.io versions response")?;
+    Ok(response.versions)
+}
@@ -0,0 +1,47 @@
+//! Cargo-Sleek library: command tracking, dependency analysis, build
+//! performance measurement and cargo execution, usable without the CLI.
+
+pub mod alias;
+pub mod bench;
+pub mod bloat;
+pub mod build_cache;
+pub mod cache;
+pub mod config;
+pub mod dependencies;
+pub mod deps_age;
+pub mod deps_count;
+pub mod dupes;
+pub mod executor;
+pub mod experiment;
+pub mod features;
+pub mod history;
+pub mod index;
+pub mod linker;
+pub mod lint_versions;
+pub mod lock_diff;
+pub mod lockfile;
+pub mod logs;
+pub mod macro_cost;
+pub mod memory;
+pub mod metadata;
+pub mod modernize;
+pub mod msrv;
+pub mod output;
+pub mod performance;
+pub mod pipeline;
+pub mod profile_audit;
+pub mod prompt;
+pub mod rebuild;
+pub mod report;
+pub mod size;
+pub mod startup;
+pub mod stats;
+pub mod target_dir;
+pub mod test_times;
+pub mod timings;
+pub mod toolchain;
+pub mod tune_jobs;
+pub mod usage;
+pub mod watch;
+pub mod workspace;
+pub mod yanked;
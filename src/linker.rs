@@ -0,0 +1,223 @@
+//! Detects the linker a build uses and suggests a faster one when linking is slow.
+
+use colored::*;
+use semver::Version;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::output;
+
+/// Link time, in seconds, above which a faster linker is suggested.
+pub const LINK_TIME_WARNING: f64 = 2.0;
+
+/// The linker configured for a target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Linker {
+    /// The platform's system linker (usually `cc` driving `ld`).
+    Default,
+    /// `rust-lld`, which the toolchain uses by default on some targets.
+    BundledLld,
+    Lld,
+    Mold,
+    /// Some other linker set through `linker = "..."`.
+    Custom(String),
+}
+
+impl Linker {
+    /// Whether this is one of the fast linkers we would otherwise suggest.
+    pub fn is_fast(&self) -> bool {
+        matches!(self, Linker::BundledLld | Linker::Lld | Linker::Mold)
+    }
+}
+
+impl fmt::Display for Linker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Linker::Default => write!(f, "system default"),
+            Linker::BundledLld => write!(f, "rust-lld (toolchain default)"),
+            Linker::Lld => write!(f, "lld"),
+            Linker::Mold => write!(f, "mold"),
+            Linker::Custom(linker) => write!(f, "{}", linker),
+        }
+    }
+}
+
+/// Collects string flags from a `rustflags` value, which may be a string or an array.
+fn flags_of(value: Option<&toml::Value>) -> Option<Vec<String>> {
+    match value? {
+        toml::Value::String(flags) => Some(flags.split_whitespace().map(String::from).collect()),
+        toml::Value::Array(flags) => Some(
+            flags
+                .iter()
+                .filter_map(|flag| flag.as_str().map(String::from))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn classify(name: &str) -> Option<Linker> {
+    if name.contains("mold") {
+        Some(Linker::Mold)
+    } else if name.contains("lld") {
+        Some(Linker::Lld)
+    } else {
+        None
+    }
+}
+
+/// Works out the linker for `triple` from cargo config files (closest first) and the
+/// `RUSTFLAGS` environment value, which takes precedence over configured rustflags.
+pub fn detect_linker(configs: &[String], rustflags: Option<&str>, triple: &str) -> Linker {
+    let mut linker: Option<String> = None;
+    let mut config_flags: Option<Vec<String>> = None;
+    for text in configs {
+        let Ok(config) = text.parse::<toml::Table>() else {
+            continue;
+        };
+        let target = config.get("target").and_then(|t| t.get(triple));
+        if linker.is_none() {
+            linker = target
+                .and_then(|t| t.get("linker"))
+                .and_then(|l| l.as_str())
+                .map(String::from);
+        }
+        if config_flags.is_none() {
+            config_flags = flags_of(target.and_then(|t| t.get("rustflags")))
+                .or_else(|| flags_of(config.get("build").and_then(|b| b.get("rustflags"))));
+        }
+    }
+    let flags = match rustflags {
+        Some(env) => env.split_whitespace().map(String::from).collect(),
+        None => config_flags.unwrap_or_default(),
+    };
+
+    for flag in &flags {
+        let ld = ["-fuse-ld=", "--ld-path="]
+            .iter()
+            .find_map(|prefix| flag.split_once(prefix).map(|(_, value)| value));
+        if let Some(found) = ld.and_then(classify) {
+            return found;
+        }
+        // `-C linker=...` in rustflags overrides the configured linker.
+        if let Some((_, value)) = flag.split_once("linker=") {
+            linker = Some(value.to_string());
+        }
+    }
+    match linker {
+        Some(linker) => classify(&linker).unwrap_or(Linker::Custom(linker)),
+        None => Linker::Default,
+    }
+}
+
+/// Whether `rustc` links with its bundled `rust-lld` on `triple` when nothing is configured.
+/// That is the case for `x86_64-unknown-linux-gnu` since Rust 1.90.
+pub fn uses_rust_lld_by_default(triple: &str, rustc: &Version) -> bool {
+    triple == "x86_64-unknown-linux-gnu" && *rustc >= Version::new(1, 90, 0)
+}
+
+/// Host triple and release of the active `rustc`, from `rustc -vV`.
+pub fn rustc_host() -> Option<(String, Version)> {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(rustc).arg("-vV").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let field = |key: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(key))
+            .map(|value| value.trim().to_string())
+    };
+    let release = field("release:")?;
+    // Nightly and beta releases look like `1.92.0-nightly`.
+    let version = Version::parse(release.split('-').next()?).ok()?;
+    Some((field("host:")?, version))
+}
+
+/// Cargo config files that apply in `dir`, closest first, ending with `$CARGO_HOME`.
+pub fn config_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let start = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")));
+    let dirs = start
+        .ancestors()
+        .map(|d| d.join(".cargo"))
+        .chain(cargo_home);
+    for cargo_dir in dirs {
+        for name in ["config.toml", "config"] {
+            let path = cargo_dir.join(name);
+            if path.is_file() && !files.contains(&path) {
+                files.push(path);
+                break;
+            }
+        }
+    }
+    files
+}
+
+/// A `.cargo/config.toml` snippet switching `triple` to a faster linker.
+pub fn suggestion(triple: &str) -> String {
+    if triple.contains("linux") {
+        format!(
+            "# Install mold first (e.g. `apt install mold clang`)\n\
+             [target.{triple}]\n\
+             linker = \"clang\"\n\
+             rustflags = [\"-C\", \"link-arg=-fuse-ld=mold\"]"
+        )
+    } else if triple.contains("windows-msvc") {
+        format!("[target.{triple}]\nlinker = \"rust-lld.exe\"")
+    } else if triple.contains("apple") {
+        format!(
+            "# Install lld first (e.g. `brew install lld`)\n\
+             [target.{triple}]\n\
+             rustflags = [\"-C\", \"link-arg=-fuse-ld=lld\"]"
+        )
+    } else {
+        format!("[target.{triple}]\nrustflags = [\"-C\", \"link-arg=-fuse-ld=lld\"]")
+    }
+}
+
+/// The linker used for host builds in `dir`, with the host triple.
+pub fn current_linker(dir: &Path) -> Option<(String, Linker)> {
+    let (triple, rustc) = rustc_host()?;
+    let configs: Vec<String> = config_files(dir)
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect();
+    let rustflags = std::env::var("RUSTFLAGS").ok();
+    let mut linker = detect_linker(&configs, rustflags.as_deref(), &triple);
+    if linker == Linker::Default && uses_rust_lld_by_default(&triple, &rustc) {
+        linker = Linker::BundledLld;
+    }
+    Some((triple, linker))
+}
+
+/// Prints the link time of a build and, when it is slow and no fast linker is
+/// configured, the config snippet to switch to one.
+pub fn report_link_time(link_secs: Option<f64>, total_secs: f64, dir: &Path) {
+    match link_secs {
+        Some(link) => output::line(format!(
+            "🔗 Link time: {:.2}s ({:.1}% of the build)",
+            link,
+            link * 100.0 / total_secs.max(f64::EPSILON)
+        )),
+        None => {
+            output::line("🔗 Link time unavailable: section timings need a nightly cargo".dimmed())
+        }
+    }
+    let Some((triple, linker)) = current_linker(dir) else {
+        return;
+    };
+    output::line(format!("   Linker: {}", linker));
+    if link_secs.is_some_and(|link| link > LINK_TIME_WARNING) && !linker.is_fast() {
+        output::line(
+            "💡 Linking is slow; a faster linker can help. Add this to .cargo/config.toml:"
+                .yellow(),
+        );
+        for line in suggestion(&triple).lines() {
+            output::line(format!("   {}", line));
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Lints dependency version requirement styles in `Cargo.toml`.
+
+use anyhow::{Context, Result};
+use colored::*;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Value};
+
+use crate::lockfile::{self, Lockfile};
+use crate::output;
+
+const DEPENDENCY_SECTIONS: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Preferred requirement style written by `--fix`.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionStyle {
+    /// `"1.0.3"`
+    #[default]
+    Full,
+    /// `"1.0"`
+    Minor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    Wildcard,
+    ExactPin,
+    TooBroad,
+    CaretPrefix,
+}
+
+impl LintKind {
+    fn describe(self) -> &'static str {
+        match self {
+            LintKind::Wildcard => "wildcard requirement",
+            LintKind::ExactPin => "unnecessary `=` pin",
+            LintKind::TooBroad => "overly broad requirement",
+            LintKind::CaretPrefix => "redundant `^` prefix",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionLint {
+    /// Dotted table path, e.g. `dependencies` or `target.'cfg(unix)'.dependencies`.
+    pub table: Vec<String>,
+    pub name: String,
+    pub current: String,
+    pub kind: LintKind,
+    /// Replacement written by `--fix`, when one can be determined.
+    pub suggested: Option<String>,
+}
+
+fn classify(req: &str) -> Option<LintKind> {
+    let req = req.trim();
+    if req == "*" || req.ends_with(".*") {
+        Some(LintKind::Wildcard)
+    } else if req.starts_with('=') {
+        Some(LintKind::ExactPin)
+    } else if !req.contains(['.', ',', '<', '>', '~']) {
+        Some(LintKind::TooBroad)
+    } else if req.starts_with('^') {
+        Some(LintKind::CaretPrefix)
+    } else {
+        None
+    }
+}
+
+fn locked_version(lock: &Lockfile, name: &str, req: &str) -> Option<Version> {
+    let req = VersionReq::parse(req).unwrap_or(VersionReq::STAR);
+    lock.package
+        .iter()
+        .filter(|p| p.name == name)
+        .filter_map(|p| Version::parse(&p.version).ok())
+        .filter(|v| req.matches(v))
+        .max()
+}
+
+fn render(version: &Version, style: VersionStyle) -> String {
+    match style {
+        VersionStyle::Full => format!("{}.{}.{}", version.major, version.minor, version.patch),
+        VersionStyle::Minor => format!("{}.{}", version.major, version.minor),
+    }
+}
+
+fn suggest(
+    kind: LintKind,
+    req: &str,
+    name: &str,
+    lock: &Lockfile,
+    style: VersionStyle,
+) -> Option<String> {
+    let trimmed = req.trim().trim_start_matches(['=', '^']).trim();
+    match kind {
+        LintKind::Wildcard | LintKind::TooBroad => {
+            locked_version(lock, name, req).map(|v| render(&v, style))
+        }
+        LintKind::ExactPin | LintKind::CaretPrefix => {
+            crate::msrv::parse_rust_version(trimmed).map(|v| match style {
+                VersionStyle::Full => trimmed.to_string(),
+                VersionStyle::Minor => render(&v, style),
+            })
+        }
+    }
+}
+
+/// All dependency tables in the manifest, as dotted paths.
+fn dependency_tables(manifest: &DocumentMut) -> Vec<Vec<String>> {
+    let mut tables: Vec<Vec<String>> = DEPENDENCY_SECTIONS
+        .iter()
+        .map(|s| vec![s.to_string()])
+        .collect();
+    if let Some(targets) = manifest.get("target").and_then(Item::as_table_like) {
+        for (target, _) in targets.iter() {
+            for section in DEPENDENCY_SECTIONS {
+                tables.push(vec![
+                    "target".into(),
+                    target.to_string(),
+                    section.to_string(),
+                ]);
+            }
+        }
+    }
+    tables.push(vec!["workspace".into(), "dependencies".into()]);
+    tables
+}
+
+fn get_table<'a>(
+    manifest: &'a DocumentMut,
+    path: &[String],
+) -> Option<&'a dyn toml_edit::TableLike> {
+    let mut item = manifest.as_item();
+    for key in path {
+        item = item.get(key)?;
+    }
+    item.as_table_like()
+}
+
+fn get_table_mut<'a>(
+    manifest: &'a mut DocumentMut,
+    path: &[String],
+) -> Option<&'a mut dyn toml_edit::TableLike> {
+    let mut item = manifest.as_item_mut();
+    for key in path {
+        item = item.get_mut(key)?;
+    }
+    item.as_table_like_mut()
+}
+
+/// Returns the requirement string of a dependency entry, skipping workspace-inherited ones.
+fn requirement(item: &Item) -> Option<&str> {
+    match item {
+        Item::Value(Value::String(s)) => Some(s.value()),
+        _ => {
+            let table = item.as_table_like()?;
+            if table.get("workspace").and_then(Item::as_bool) == Some(true) {
+                return None;
+            }
+            table.get("version").and_then(Item::as_str)
+        }
+    }
+}
+
+pub fn lint_manifest(
+    manifest: &str,
+    lock: &Lockfile,
+    style: VersionStyle,
+) -> Result<Vec<VersionLint>> {
+    let manifest: DocumentMut = manifest.parse().context("Failed to parse Cargo.toml")?;
+    let mut lints = Vec::new();
+    for path in dependency_tables(&manifest) {
+        let Some(table) = get_table(&manifest, &path) else {
+            continue;
+        };
+        for (name, item) in table.iter() {
+            let Some(req) = requirement(item) else {
+                continue;
+            };
+            let Some(kind) = classify(req) else {
+                continue;
+            };
+            let package = item
+                .as_table_like()
+                .and_then(|t| t.get("package"))
+                .and_then(Item::as_str)
+                .unwrap_or(name);
+            lints.push(VersionLint {
+                table: path.clone(),
+                name: name.to_string(),
+                current: req.to_string(),
+                kind,
+                suggested: suggest(kind, req, package, lock, style),
+            });
+        }
+    }
+    Ok(lints)
+}
+
+/// Rewrites the requirements of `lints` that have a suggestion, preserving formatting.
+pub fn apply_fixes(manifest: &str, lints: &[VersionLint]) -> Result<String> {
+    let mut doc: DocumentMut = manifest.parse().context("Failed to parse Cargo.toml")?;
+    for lint in lints {
+        let Some(suggested) = &lint.suggested else {
+            continue;
+        };
+        let Some(item) = get_table_mut(&mut doc, &lint.table).and_then(|t| t.get_mut(&lint.name))
+        else {
+            continue;
+        };
+        let target = match item {
+            Item::Value(Value::String(_)) => Some(item),
+            _ => item.as_table_like_mut().and_then(|t| t.get_mut("version")),
+        };
+        if let Some(Item::Value(value @ Value::String(_))) = target {
+            let decor = value.decor().clone();
+            *value = Value::from(suggested.as_str());
+            *value.decor_mut() = decor;
+        }
+    }
+    Ok(doc.to_string())
+}
+
+pub fn lint_versions(project_dir: &Path, style: VersionStyle, fix: bool) -> Result<()> {
+    let manifest_path = project_dir.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path).context("Failed to read Cargo.toml")?;
+    let lock = lockfile::load_lockfile(&project_dir.join("Cargo.lock")).unwrap_or_default();
+    let lints = lint_manifest(&manifest, &lock, style)?;
+
+    output::status("🔍 Linting dependency version requirements...");
+    if lints.is_empty() {
+        output::line("✅ All version requirements are consistent!".green());
+        return Ok(());
+    }
+    for lint in &lints {
+        let suggestion = lint
+            .suggested
+            .as_ref()
+            .map(|s| format!(" → \"{}\"", s).green().to_string())
+            .unwrap_or_default();
+        output::line(format!(
+            "   • [{}] {} = \"{}\": {}{}",
+            lint.table.join("."),
+            lint.name.bold(),
+            lint.current,
+            lint.kind.describe(),
+            suggestion
+        ));
+    }
+
+    if fix {
+        let fixed = apply_fixes(&manifest, &lints)?;
+        fs::write(&manifest_path, fixed).context("Failed to write Cargo.toml")?;
+        let count = lints.iter().filter(|l| l.suggested.is_some()).count();
+        output::line(format!("✏️  Rewrote {count} requirement(s)").green());
+    }
+    Ok(())
+}
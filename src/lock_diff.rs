@@ -0,0 +1,173 @@
+//! Summarizes `Cargo.lock` changes between git revisions.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use semver::Version;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::Command;
+
+use crate::lockfile::{self, Lockfile};
+use crate::output;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PackageVersion {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct VersionChange {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct LockDiff {
+    pub added: Vec<PackageVersion>,
+    pub removed: Vec<PackageVersion>,
+    pub upgraded: Vec<VersionChange>,
+    pub downgraded: Vec<VersionChange>,
+    /// Change in the number of locked packages.
+    pub net_change: i64,
+}
+
+fn versions_by_name(lock: &Lockfile) -> BTreeMap<&str, BTreeSet<&str>> {
+    let mut map: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for package in &lock.package {
+        map.entry(&package.name)
+            .or_default()
+            .insert(&package.version);
+    }
+    map
+}
+
+fn sort_versions<'a>(versions: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut versions: Vec<&str> = versions.collect();
+    versions.sort_by_key(|v| Version::parse(v).ok());
+    versions
+}
+
+pub fn diff_lockfiles(old: &Lockfile, new: &Lockfile) -> LockDiff {
+    let old_map = versions_by_name(old);
+    let new_map = versions_by_name(new);
+    let names: BTreeSet<&str> = old_map.keys().chain(new_map.keys()).copied().collect();
+    let empty = BTreeSet::new();
+
+    let mut diff = LockDiff {
+        net_change: new.package.len() as i64 - old.package.len() as i64,
+        ..Default::default()
+    };
+    let entry = |name: &str, version: &str| PackageVersion {
+        name: name.to_string(),
+        version: version.to_string(),
+    };
+
+    for name in names {
+        let before = old_map.get(name).unwrap_or(&empty);
+        let after = new_map.get(name).unwrap_or(&empty);
+        let removed = sort_versions(before.difference(after).copied());
+        let added = sort_versions(after.difference(before).copied());
+
+        // Pair removed and added versions of the same crate as version changes.
+        let paired = removed.len().min(added.len());
+        for (from, to) in removed.iter().zip(&added).take(paired) {
+            let change = VersionChange {
+                name: name.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+            };
+            if Version::parse(to).ok() >= Version::parse(from).ok() {
+                diff.upgraded.push(change);
+            } else {
+                diff.downgraded.push(change);
+            }
+        }
+        diff.removed
+            .extend(removed[paired..].iter().map(|v| entry(name, v)));
+        diff.added
+            .extend(added[paired..].iter().map(|v| entry(name, v)));
+    }
+    diff
+}
+
+/// Reads `Cargo.lock` at `rev`, or from the working tree when `rev` is `None`.
+fn read_lockfile_at(project_dir: &Path, rev: Option<&str>) -> Result<Lockfile> {
+    let Some(rev) = rev else {
+        return lockfile::load_lockfile(&project_dir.join("Cargo.lock"));
+    };
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!("{rev}:./Cargo.lock"))
+        .current_dir(project_dir)
+        .output()
+        .context("Failed to execute git show")?;
+    if !output.status.success() {
+        bail!(
+            "Could not read Cargo.lock at {rev}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    lockfile::parse_lockfile(&String::from_utf8_lossy(&output.stdout))
+}
+
+pub fn show_lock_diff(
+    project_dir: &Path,
+    rev1: &str,
+    rev2: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let old = read_lockfile_at(project_dir, Some(rev1))?;
+    let new = read_lockfile_at(project_dir, rev2)?;
+    let diff = diff_lockfiles(&old, &new);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+
+    output::line(format!(
+        "{} {} → {}",
+        "🔒 Cargo.lock changes:".bold().cyan(),
+        rev1,
+        rev2.unwrap_or("working tree")
+    ));
+    for package in &diff.added {
+        output::line(format!(
+            "   {} {} {}",
+            "+".green(),
+            package.name,
+            package.version
+        ));
+    }
+    for package in &diff.removed {
+        output::line(format!(
+            "   {} {} {}",
+            "-".red(),
+            package.name,
+            package.version
+        ));
+    }
+    for change in &diff.upgraded {
+        output::line(format!(
+            "   {} {} {} → {}",
+            "↑".green(),
+            change.name,
+            change.from,
+            change.to
+        ));
+    }
+    for change in &diff.downgraded {
+        output::line(format!(
+            "   {} {} {} → {}",
+            "↓".yellow(),
+            change.name,
+            change.from,
+            change.to
+        ));
+    }
+    output::line(format!("📦 Net change: {:+} packages", diff.net_change));
+    Ok(())
+}
@@ -0,0 +1,39 @@
+//! `Cargo.lock` parsing.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+const CRATES_IO_SOURCE: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Lockfile {
+    #[serde(default)]
+    pub package: Vec<LockedPackage>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    pub source: Option<String>,
+}
+
+impl LockedPackage {
+    /// Whether the package comes from the crates.io registry.
+    pub fn is_crates_io(&self) -> bool {
+        self.source.as_deref().is_some_and(|source| {
+            source == CRATES_IO_SOURCE || source == "sparse+https://index.crates.io/"
+        })
+    }
+}
+
+pub fn parse_lockfile(content: &str) -> Result<Lockfile> {
+    toml::from_str(content).context("Failed to parse Cargo.lock")
+}
+
+pub fn load_lockfile(path: &Path) -> Result<Lockfile> {
+    let content = fs::read_to_string(path).context("Failed to read Cargo.lock")?;
+    parse_lockfile(&content)
+}
@@ -0,0 +1,159 @@
+//! Timestamped logs of cargo runs (`--log-dir`) and listing them (`logs`).
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local};
+use colored::*;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::history;
+use crate::output;
+use crate::performance::format_size;
+
+/// Directory `logs` reads when neither `--log-dir` nor `log_dir` in the config is set.
+pub const DEFAULT_LOG_DIR: &str = "logs";
+
+/// `build-2024-06-01T10-22-33.log`: the command, then the local start time.
+pub fn log_file_name(command: &str, started: DateTime<Local>) -> String {
+    format!("{}-{}.log", command, started.format("%Y-%m-%dT%H-%M-%S"))
+}
+
+/// Header lines written before the command's output.
+pub fn log_header(
+    program_line: &str,
+    cwd: &Path,
+    commit: Option<&str>,
+    started: DateTime<Local>,
+) -> String {
+    format!(
+        "# command: {}\n# cwd: {}\n# commit: {}\n# started: {}\n\n",
+        program_line,
+        cwd.display(),
+        commit.unwrap_or("-"),
+        started.to_rfc3339()
+    )
+}
+
+/// Footer appended once the command has finished.
+pub fn log_footer(duration: Duration, exit_code: i32) -> String {
+    format!(
+        "\n# duration: {:.2}s\n# exit code: {}\n",
+        duration.as_secs_f64(),
+        exit_code
+    )
+}
+
+/// A log file being written while its command runs.
+pub struct RunLog {
+    pub path: PathBuf,
+    pub file: File,
+}
+
+/// Creates the log for `command` in `dir` and writes its header.
+pub fn start_log(dir: &Path, command: &str, program_line: &str) -> Result<RunLog> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create log directory {}", dir.display()))?;
+    let started = Local::now();
+    // Two runs in the same second get `-2`, `-3`, ... rather than overwriting each other.
+    let base = log_file_name(command, started);
+    let mut path = dir.join(&base);
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(base.replace(".log", &format!("-{}.log", n)));
+        n += 1;
+    }
+    let mut file =
+        File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let commit = history::git_commit();
+    file.write_all(log_header(program_line, &cwd, commit.as_deref(), started).as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(RunLog { path, file })
+}
+
+/// `.log` files in `dir`, newest first.
+pub fn list_logs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .map(|path| {
+            let modified = fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            (modified, path)
+        })
+        .collect();
+    logs.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+    logs.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Value of a `# key: value` header or footer line.
+fn log_field<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    let prefix = format!("# {}: ", key);
+    content
+        .lines()
+        .rev()
+        .find_map(|line| line.strip_prefix(prefix.as_str()))
+}
+
+/// Prints the `limit` most recent logs, numbered for `logs show <n>`.
+pub fn show_logs(dir: &Path, limit: usize) -> Result<()> {
+    let logs = list_logs(dir);
+    if logs.is_empty() {
+        output::line(
+            format!(
+                "📭 No logs in {} yet. Run a command with --log-dir {} to record one.",
+                dir.display(),
+                dir.display()
+            )
+            .yellow(),
+        );
+        return Ok(());
+    }
+    output::line(
+        format!("📜 Recent logs in {}:", dir.display())
+            .bold()
+            .cyan(),
+    );
+    for (i, path) in logs.iter().take(limit).enumerate() {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let exit = match log_field(&content, "exit code") {
+            Some("0") => "ok".green(),
+            Some(code) => format!("exit {}", code).red(),
+            None => "unfinished".yellow(),
+        };
+        let size = fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+        output::line(format!(
+            "{:>3}  {:<40} {:>10}  {}",
+            i + 1,
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            format_size(size),
+            exit
+        ));
+    }
+    Ok(())
+}
+
+/// Prints log number `n` (1 is the most recent) as it was written.
+pub fn show_log(dir: &Path, n: usize) -> Result<()> {
+    let logs = list_logs(dir);
+    let Some(path) = n.checked_sub(1).and_then(|i| logs.get(i)) else {
+        bail!(
+            "No log #{} in {} ({} log(s) recorded)",
+            n,
+            dir.display(),
+            logs.len()
+        );
+    };
+    let content = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    std::io::stdout()
+        .write_all(&content)
+        .context("Failed to print the log")?;
+    Ok(())
+}
@@ -0,0 +1,217 @@
+//! Compile cost of proc-macro crates and build scripts, which serialize the build graph.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+use crate::metadata::{self, Metadata};
+use crate::output;
+use crate::timings::{self, UnitTiming};
+
+/// Number of top entries that get an explanatory note.
+pub const WORST_OFFENDERS: usize = 3;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CostKind {
+    ProcMacro,
+    BuildScript,
+}
+
+impl CostKind {
+    fn describe(self) -> &'static str {
+        match self {
+            CostKind::ProcMacro => "proc-macro",
+            CostKind::BuildScript => "build script",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MacroCost {
+    pub name: String,
+    pub version: String,
+    pub kind: CostKind,
+    /// Seconds spent compiling the proc macro, or compiling and running the build script.
+    pub seconds: f64,
+    /// Packages in the graph that wait for it: its transitive dependents, plus the
+    /// package itself for a build script.
+    pub dependents: usize,
+    /// Whether one of its units lies on the build's critical path.
+    pub critical: bool,
+}
+
+impl MacroCost {
+    /// Compile seconds weighted by the number of packages waiting on them, shown in the
+    /// `Blocking` column.
+    pub fn blocking(&self) -> f64 {
+        self.seconds * self.dependents as f64
+    }
+}
+
+/// Number of packages that transitively depend on each package id.
+pub fn dependent_counts(metadata: &Metadata) -> Result<HashMap<String, usize>> {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .context("cargo metadata output has no resolve graph")?;
+    let mut reverse: HashMap<&str, Vec<&str>> = HashMap::new();
+    for node in &resolve.nodes {
+        for dep in &node.dependencies {
+            reverse
+                .entry(dep.as_str())
+                .or_default()
+                .push(node.id.as_str());
+        }
+    }
+    let mut counts = HashMap::new();
+    for node in &resolve.nodes {
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut queue: VecDeque<&str> = VecDeque::from([node.id.as_str()]);
+        while let Some(id) = queue.pop_front() {
+            for parent in reverse.get(id).into_iter().flatten() {
+                if seen.insert(parent) {
+                    queue.push_back(parent);
+                }
+            }
+        }
+        counts.insert(node.id.clone(), seen.len());
+    }
+    Ok(counts)
+}
+
+/// Proc-macro crates and build scripts in the graph with their compile cost from `units`,
+/// sorted by blocking impact. Packages without timing data (fresh) are left out.
+pub fn macro_costs(metadata: &Metadata, units: &[UnitTiming]) -> Result<Vec<MacroCost>> {
+    let dependents = dependent_counts(metadata)?;
+    let critical = timings::critical_path(units);
+    let mut costs = Vec::new();
+    for pkg in &metadata.packages {
+        let has_kind = |kind: &str| pkg.targets.iter().any(|t| t.kind.iter().any(|k| k == kind));
+        for (kind, present) in [
+            (CostKind::ProcMacro, has_kind("proc-macro")),
+            (CostKind::BuildScript, has_kind("custom-build")),
+        ] {
+            if !present {
+                continue;
+            }
+            let matching: Vec<&UnitTiming> = units
+                .iter()
+                .filter(|u| {
+                    u.name == pkg.name && (u.version.is_empty() || u.version == pkg.version)
+                })
+                .filter(|u| u.target.contains("build-script") == (kind == CostKind::BuildScript))
+                .filter(|u| u.duration > 0.0)
+                .collect();
+            if matching.is_empty() {
+                continue;
+            }
+            costs.push(MacroCost {
+                name: pkg.name.clone(),
+                version: pkg.version.clone(),
+                kind,
+                seconds: matching.iter().map(|u| u.duration).sum(),
+                dependents: dependents.get(&pkg.id).copied().unwrap_or(0)
+                    + usize::from(kind == CostKind::BuildScript),
+                critical: matching.iter().any(|u| critical.contains(&u.i)),
+            });
+        }
+    }
+    costs.sort_by(|a, b| {
+        b.blocking()
+            .total_cmp(&a.blocking())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(costs)
+}
+
+fn offender_note(cost: &MacroCost) -> String {
+    let advice = match cost.kind {
+        CostKind::ProcMacro => {
+            "check whether all of its derives and features are needed, \
+             or whether a lighter alternative exists"
+        }
+        CostKind::BuildScript => {
+            "check whether it compiles C code or generates bindings that could be \
+             pre-generated or feature-gated"
+        }
+    };
+    format!(
+        "{} {} holds up {} dependent(s) for {:.2}s{}: {}",
+        cost.name,
+        cost.kind.describe(),
+        cost.dependents,
+        cost.seconds,
+        if cost.critical {
+            " on the critical path"
+        } else {
+            ""
+        },
+        advice
+    )
+}
+
+pub fn show_macro_cost(project_dir: &Path, json: bool) -> Result<()> {
+    let metadata = metadata::cargo_metadata_with_deps(project_dir)?;
+    let Some(units) = timings::load_timing_report(&metadata.target_directory) else {
+        bail!(
+            "No timing report found in {}.\n   \
+             Run `cargo sleek build-time --cold` first to record compile times.",
+            metadata
+                .target_directory
+                .join(timings::TIMING_REPORT)
+                .display()
+        );
+    };
+    let costs = macro_costs(&metadata, &units)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&costs)?);
+        return Ok(());
+    }
+    if costs.is_empty() {
+        output::line(
+            "✅ No proc-macro or build script compile times in the last timing report \
+             (everything was fresh? try `cargo sleek build-time --cold`)"
+                .green(),
+        );
+        return Ok(());
+    }
+
+    output::line(
+        "🧩 Proc macros and build scripts, by blocking impact:"
+            .bold()
+            .cyan(),
+    );
+    output::line(format!(
+        "{:<4} {:<30} {:<14} {:>9} {:>11} {:>10}",
+        "#", "Crate", "Kind", "Time", "Dependents", "Blocking"
+    ));
+    for (i, cost) in costs.iter().enumerate() {
+        let row = format!(
+            "{:<4} {:<30} {:<14} {:>8.2}s {:>11} {:>10.1}",
+            i + 1,
+            format!("{} v{}", cost.name, cost.version),
+            cost.kind.describe(),
+            cost.seconds,
+            cost.dependents,
+            cost.blocking()
+        );
+        if cost.critical {
+            output::line(format!("{} {}", row.red(), "◀ critical path".red()));
+        } else {
+            output::line(row);
+        }
+    }
+
+    output::line("");
+    for cost in costs
+        .iter()
+        .filter(|cost| cost.blocking() > 0.0)
+        .take(WORST_OFFENDERS)
+    {
+        output::line(format!("💡 {}", offender_note(cost)).yellow());
+    }
+    Ok(())
+}
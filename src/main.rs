@@ -1,243 +1,1586 @@
-use clap::{Arg, ArgMatches, Command as ClapCommand};
-use colored::*;
-use serde::{Deserialize, Serialize};
-use serde_json;
-use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
-use std::process::Command;
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
-use anyhow::{Context, Result};
+use anyhow::{bail, Result};
+use cargo_sleek::{
+    alias, bench, bloat, build_cache, cache, config, dependencies, deps_age, deps_count, dupes,
+    executor, experiment, features, history, lint_versions, lock_diff, logs, macro_cost, memory,
+    metadata, modernize, msrv, output, performance, pipeline, profile_audit, rebuild, report, size,
+    startup, stats, target_dir, test_times, timings, tune_jobs, watch, workspace, yanked,
+};
+use clap::{Arg, ArgAction, ArgMatches, Command as ClapCommand};
+use std::ffi::OsString;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-const STATS_FILE: &str = "command_stats.json";
-
-#[derive(Serialize, Deserialize, Debug, Default)]
-struct CommandStats {
-    usage_count: u32,
-    last_used: u64,
+fn passthrough_values(args: &ArgMatches, id: &str) -> Vec<String> {
+    args.try_get_many::<String>(id)
+        .ok()
+        .flatten()
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default()
 }
 
-/// -------------------- MODULE: stats --------------------
-mod stats {
-    use super::*;
-    use chrono::NaiveDateTime;
-
-    pub fn load_stats() -> HashMap<String, CommandStats> {
-        if !Path::new(STATS_FILE).exists() {
-            return HashMap::new();
-        }
-        let file = fs::File::open(STATS_FILE).ok();
-        if let Some(file) = file {
-            let reader = std::io::BufReader::new(file);
-            if let Ok(stats) = serde_json::from_reader(reader) {
-                return stats;
-            }
-        }
-        HashMap::new()
-    }
-
-    pub fn save_stats(stats: &HashMap<String, CommandStats>) -> Result<()> {
-        let json = serde_json::to_string_pretty(stats)?;
-        fs::write(STATS_FILE, json).context("Failed to write stats file")
-    }
-
-    pub fn track_command(command: &str) -> Result<()> {
-        let mut stats = load_stats();
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        let entry = stats.entry(command.to_string()).or_default();
-        entry.usage_count += 1;
-        entry.last_used = now;
-
-        save_stats(&stats)?;
-        Ok(())
-    }
-
-    pub fn show_stats() -> Result<()> {
-        let stats = load_stats();
-        if stats.is_empty() {
-            println!("{}", "📊 No command usage data available.".yellow());
-            return Ok(());
-        }
-
-        let mut sorted: Vec<_> = stats.iter().collect();
-        sorted.sort_by(|a, b| b.1.usage_count.cmp(&a.1.usage_count));
-
-        println!("{}", "📊 Most Used Cargo Commands:".bold().cyan());
-        println!("{:<4} {:<20} {:>8} {:>20}", "#", "Command", "Count", "Last Used");
-
-        for (i, (cmd, data)) in sorted.iter().enumerate() {
-            let last_used = NaiveDateTime::from_timestamp_opt(data.last_used as i64, 0)
-                .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
-                .unwrap_or_else(|| "N/A".to_string());
-
-            println!(
-                "{:<4} {:<20} {:>8} {:>20}",
-                i + 1,
-                cmd.green().bold(),
-                data.usage_count,
-                last_used
-            );
-        }
-        Ok(())
-    }
-
-    pub fn reset_stats(args: &ArgMatches) -> Result<()> {
-        if args.get_flag("force") {
-            fs::write(STATS_FILE, "{}")?;
-            println!("✅ Command stats have been reset!");
-        } else {
-            println!("⚠️ Run with `cargo sleek reset --force` to confirm.");
-        }
-        Ok(())
+/// The command line without the `sleek` that cargo inserts when it runs
+/// `cargo sleek <x>` as `cargo-sleek sleek <x>`.
+fn cli_args() -> Vec<OsString> {
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+    if args.get(1).is_some_and(|arg| arg == "sleek") {
+        args.remove(1);
     }
+    args
 }
 
-/// -------------------- MODULE: performance --------------------
-mod performance {
-    use super::*;
-
-    pub fn analyze_build_time(verbose: bool) -> Result<()> {
-        println!("📊 Analyzing build performance...\n");
-        let start = Instant::now();
-
-        let status = Command::new("cargo")
-            .arg("build")
-            .arg("--timings")
-            .status()
-            .context("Failed to execute cargo build --timings")?;
-
-        let duration = start.elapsed();
-
-        if status.success() {
-            let size = fs::metadata("target/debug")
-                .map(|m| m.len() / 1024)
-                .unwrap_or_default();
-            println!("🚀 Build completed in {:.2?}", duration);
-            println!("📦 Approx. build size: {} KB", size);
-            if verbose {
-                println!("🕓 Timing report saved in `target/cargo-timings/`");
-            }
-        } else {
-            println!("❌ Build failed. Check logs for details.");
-        }
-
-        Ok(())
-    }
+/// Whether `--dry-run` ended up among the cargo arguments, as in
+/// `cargo sleek build --release --dry-run`; it is sleek's flag there too.
+fn passthrough_dry_run(args: &ArgMatches) -> bool {
+    passthrough_values(args, "args")
+        .iter()
+        .take_while(|arg| *arg != "--")
+        .any(|arg| arg == "--dry-run")
 }
 
-/// -------------------- MODULE: dependencies --------------------
-mod dependencies {
-    use super::*;
-
-    pub fn check_unused_deps() -> Result<()> {
-        println!("🔍 Checking unused dependencies...");
-        let cargo_toml = fs::read_to_string("Cargo.toml").context("Failed to read Cargo.toml")?;
-        let cargo_lock = fs::read_to_string("Cargo.lock").unwrap_or_default();
-
-        let mut unused = vec![];
-        let mut in_deps = false;
-
-        for line in cargo_toml.lines() {
-            let trimmed = line.trim();
-
-            if trimmed.starts_with("[dependencies]") {
-                in_deps = true;
-                continue;
-            }
-            if trimmed.starts_with('[') {
-                in_deps = false;
-            }
-
-            if in_deps {
-                if let Some(dep) = trimmed.split('=').next() {
-                    let dep = dep.trim();
-                    if !cargo_lock.contains(dep) {
-                        unused.push(dep.to_string());
-                    }
-                }
-            }
-        }
-
-        if unused.is_empty() {
-            println!("{}", "✅ No unused dependencies found!".green());
-        } else {
-            println!("{}", "🚨 Unused dependencies found:".red());
-            for dep in unused {
-                println!("   • {}", dep);
-            }
-        }
-        Ok(())
+/// Collects the passthrough arguments of an executor-backed subcommand.
+fn extra_args(args: &ArgMatches) -> Vec<String> {
+    let values = |id: &str| passthrough_values(args, id);
+    let mut extra = values("args");
+    let cargo_args = extra.iter().take_while(|arg| *arg != "--").count();
+    let for_program = extra.split_off(cargo_args);
+    extra.retain(|arg| arg != "--dry-run");
+    extra.extend(for_program);
+    let program = values("program-args");
+    if !program.is_empty() {
+        extra.push("--".to_string());
+        extra.extend(program);
     }
+    extra
 }
 
-/// -------------------- MODULE: executor --------------------
-mod executor {
-    use super::*;
+/// Arguments passed on to cargo, e.g. `--release`. Arguments after a leading `--` are
+/// kept behind `--` for the program or test harness cargo runs, as with cargo itself.
+fn passthrough_args(help: &'static str) -> [Arg; 2] {
+    [
+        Arg::new("args")
+            .help(help)
+            .num_args(1..)
+            .allow_hyphen_values(true),
+        Arg::new("program-args")
+            .help("Arguments after -- for the program cargo runs")
+            .num_args(1..)
+            .last(true),
+    ]
+}
 
-    pub fn execute_cargo_command(command: &str, args: &ArgMatches, verbose: bool) -> Result<()> {
-        println!("🚀 Running Cargo command: {}", command.bold().cyan());
-        stats::track_command(command)?;
+const CONFIRM_HELP: &str = "Don't ask for confirmation (see [confirm] in .sleek.toml)";
 
-        let mut cmd = Command::new("cargo");
-        cmd.arg(command);
+/// `--yes`/`-y`, skipping a confirmation prompt.
+fn yes_arg(help: &'static str) -> Arg {
+    Arg::new("yes")
+        .long("yes")
+        .short('y')
+        .help(help)
+        .action(ArgAction::SetTrue)
+}
 
-        if let Some(extra_args) = args.get_many::<String>("args") {
-            cmd.args(extra_args.map(|s| s.as_str()));
+/// -------------------- MAIN --------------------
+fn main() {
+    if let Err(err) = run() {
+        if let Some(not_found) = err.downcast_ref::<executor::CargoNotFound>() {
+            output::warning(format!("❌ {}", not_found));
+            std::process::exit(executor::EXIT_CARGO_NOT_FOUND);
         }
-
-        if verbose {
-            println!("🔧 Executing: {:?}", cmd);
+        if let Some(cancelled) = err.downcast_ref::<executor::Cancelled>() {
+            output::warning(format!("❌ {}", cancelled));
+            std::process::exit(1);
         }
-
-        let status = cmd.status().context("Failed to execute cargo command")?;
-        if !status.success() {
-            println!("❌ Command failed with exit code: {:?}", status.code());
+        if let Some(failed) = err.downcast_ref::<executor::CargoFailed>() {
+            output::warning(format!("❌ {}", failed));
+            std::process::exit(failed.code);
         }
-
-        Ok(())
+        output::warning(format!("❌ {:?}", err));
+        std::process::exit(1);
     }
 }
 
-/// -------------------- MAIN --------------------
-fn main() -> Result<()> {
+const PASSTHROUGH_HELP: &str = "Aliases from [aliases] in .sleek.toml are expanded first. \
+     Any other subcommand (nextest, publish, tree, ...) is passed through to cargo with its \
+     arguments, and tracked and timed like the ones above; put cargo-sleek flags such as \
+     --retries before its name.";
+
+fn run() -> Result<()> {
+    // Loaded before parsing so `--help` can list the configured aliases; an invalid
+    // config is only reported once parsing has handled `--help` and `--version`.
+    let config = config::load_config(Path::new(config::CONFIG_FILE));
+    let aliases_help = config
+        .as_ref()
+        .ok()
+        .and_then(|config| alias::aliases_help(&config.aliases));
+    let after_help = match aliases_help {
+        Some(aliases) => format!("{}\n\n{}", PASSTHROUGH_HELP, aliases),
+        None => PASSTHROUGH_HELP.to_string(),
+    };
     let matches = ClapCommand::new("cargo-sleek")
         .version("1.1")
         .about("Tracks, analyzes, and optimizes your Cargo workflow 🚀")
+        .after_help(after_help)
+        .allow_external_subcommands(true)
+        .external_subcommand_value_parser(clap::value_parser!(String))
         .arg(
             Arg::new("verbose")
                 .long("verbose")
                 .short('v')
                 .help("Enable verbose logging")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("stats-file")
+                .long("stats-file")
+                .value_name("PATH")
+                .help("Stats file to use for this run (overrides SLEEK_STATS_FILE and config)")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true),
+        )
+        .arg(
+            Arg::new("track-args")
+                .long("track-args")
+                .help("Track commands with their arguments (e.g. `build --release`)")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("record-test-times")
+                .long("record-test-times")
+                .help("Capture `cargo test` output to record per-test times for `test-times`")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("offline")
+                .long("offline")
+                .help("Never access the network; use cached crates.io data only")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("no-project-check")
+                .long("no-project-check")
+                .help("Run even when the current directory has no Cargo.toml")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("log")
+                .long("log")
+                .value_name("FILE")
+                .help("Capture cargo output of build/run/clean into FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true),
+        )
+        .arg(
+            Arg::new("log-dir")
+                .long("log-dir")
+                .value_name("DIR")
+                .help("Also write each cargo run's output to a timestamped log in DIR")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true),
+        )
+        .arg(
+            Arg::new("limit-output")
+                .long("limit-output")
+                .value_name("BYTES")
+                .help("Keep only the last BYTES of captured output")
+                .value_parser(clap::value_parser!(usize))
+                .requires("log")
+                .global(true),
+        )
+        .arg(
+            Arg::new("report-json")
+                .long("report-json")
+                .value_name("FILE")
+                .help("Write a JSON summary of each cargo run to FILE")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true),
+        )
+        .arg(
+            Arg::new("report-append")
+                .long("report-append")
+                .help("Append reports as JSON lines instead of overwriting")
+                .action(ArgAction::SetTrue)
+                .requires("report-json")
+                .global(true),
+        )
+        .arg(
+            Arg::new("env")
+                .long("env")
+                .value_name("KEY=VALUE")
+                .help("Set an environment variable for the cargo command (repeatable)")
+                .value_parser(executor::parse_env_pair)
+                .action(ArgAction::Append)
+                .global(true),
+        )
+        .arg(
+            Arg::new("no-color")
+                .long("no-color")
+                .help("Disable colored output")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("retries")
+                .long("retries")
+                .value_name("N")
+                .help("Re-run a failing cargo command up to N times (not after a signal)")
+                .value_parser(clap::value_parser!(u32))
+                .global(true),
+        )
+        .arg(
+            Arg::new("retry-delay")
+                .long("retry-delay")
+                .value_name("DURATION")
+                .help("Wait before the first retry, doubled for each later one")
+                .default_value("1s")
+                .value_parser(performance::parse_budget)
+                .global(true),
+        )
+        .arg(
+            Arg::new("dry-run")
+                .long("dry-run")
+                .help("Print the shell-quoted commands (hooks included) instead of running them")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("quiet")
+                .long("quiet")
+                .help("Only cargo's own output and results; no banners, emoji or color")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("plain")
+                .long("plain")
+                .help("No emoji or color, `error:`/`info:` prefixes (default when CI is set or stdout is not a terminal)")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("no-hooks")
+                .long("no-hooks")
+                .help("Skip the pre/post hooks from [hooks] in .sleek.toml")
+                .action(ArgAction::SetTrue)
                 .global(true),
         )
-        .subcommand(ClapCommand::new("stats").about("Show command usage statistics"))
+        .subcommand(
+            ClapCommand::new("stats")
+                .about("Show command usage statistics")
+                .arg(
+                    Arg::new("since").long("since").value_name("WHEN").help(
+                        "Only include commands used since a date (2024-06-01) or duration (7d)",
+                    ),
+                )
+                .arg(
+                    Arg::new("restore")
+                        .long("restore")
+                        .help("Restore the most recent backup taken before a reset")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("graph")
+                        .long("graph")
+                        .help("Chart invocations per day over the last 30 days")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("where")
+                        .long("where")
+                        .help("Print the absolute path of the stats file and exit")
+                        .conflicts_with("open-dir")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("open-dir")
+                        .long("open-dir")
+                        .help("Open the directory holding the stats file")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("first-used")
+                        .long("first-used")
+                        .help("Add a First Used column (also shown with --verbose)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("toml")
+                        .long("toml")
+                        .help("Print the stats as TOML instead of a table")
+                        .conflicts_with("graph")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("merge")
+                        .long("merge")
+                        .value_name("FILE")
+                        .help("Add the counts of another stats file, e.g. from another machine")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .conflicts_with_all(["restore", "graph"]),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .value_name("PATH")
+                        .help("Write the report to a file (without color) instead of stdout")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("summary")
+                .about("Overview of command usage and build times")
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .value_name("PATH")
+                        .help("Write the summary to a file (without color) instead of stdout")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                ),
+        )
         .subcommand(
             ClapCommand::new("reset")
                 .about("Reset usage statistics")
-                .arg(Arg::new("force").long("force").help("Force reset stats")),
-        )
-        .subcommand(ClapCommand::new("check-deps").about("Check for unused dependencies"))
-        .subcommand(ClapCommand::new("build-time").about("Analyze build performance"))
-        .subcommand(ClapCommand::new("build").about("Run cargo build"))
-        .subcommand(ClapCommand::new("clean").about("Run cargo clean"))
-        .subcommand(ClapCommand::new("run").about("Run the project"))
-        .get_matches();
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .help("Force reset stats")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("check-deps")
+                .about("Check for unused dependencies")
+                .arg(
+                    Arg::new("ignore")
+                        .long("ignore")
+                        .value_name("CRATE")
+                        .help("Dependency to skip (repeatable, merged with .sleek.toml)")
+                        .value_delimiter(',')
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("fix")
+                        .long("fix")
+                        .help("Move test-only dependencies to [dev-dependencies]")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("explain")
+                        .long("explain")
+                        .help("Show where each dependency is referenced in the sources")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("features")
+                        .long("features")
+                        .help("List the features each dependency requests and flag suspicious ones")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("porcelain")
+                        .long("porcelain")
+                        .help(
+                            "Print `unused<TAB>name<TAB>section` lines for scripts; exit 1 if any",
+                        )
+                        .conflicts_with_all(["fix", "explain", "features"])
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("count-only")
+                        .long("count-only")
+                        .help("Print only the number of unused dependencies; exit 1 if any")
+                        .conflicts_with_all(["fix", "explain", "features", "porcelain"])
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("ci")
+                        .long("ci")
+                        .help("Exit with a non-zero status when unused dependencies are found")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("cache")
+                .about("Manage the crates.io response cache")
+                .subcommand_required(true)
+                .subcommand(ClapCommand::new("stats").about("Show cache location and size"))
+                .subcommand(ClapCommand::new("clear").about("Delete all cached responses")),
+        )
+        .subcommand(
+            ClapCommand::new("yanked").about("Report yanked crate versions pinned in Cargo.lock"),
+        )
+        .subcommand(
+            ClapCommand::new("deps-age")
+                .about("Show how old the versions pinned in Cargo.lock are")
+                .arg(
+                    Arg::new("threshold")
+                        .long("threshold")
+                        .value_name("MONTHS")
+                        .help("Highlight versions older than this many months")
+                        .value_parser(clap::value_parser!(u32)),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("deps-count")
+                .about("Count direct and transitive dependencies")
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the report as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("check-dupes")
+                .about("List crates locked at several versions in Cargo.lock")
+                .arg(
+                    Arg::new("fix")
+                        .long("fix")
+                        .help("Unify compatible versions with `cargo update -p <crate> --precise <newest>`")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("workspace-deps")
+                .about("Check that members inherit [workspace.dependencies]")
+                .arg(
+                    Arg::new("fix")
+                        .long("fix")
+                        .help("Rewrite eligible member entries to `workspace = true`")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("check-features")
+                .about("Cross-check [features] against optional dependencies"),
+        )
+        .subcommand(
+            ClapCommand::new("lock-diff")
+                .about("Summarize Cargo.lock changes between two git revisions")
+                .arg(Arg::new("rev1").default_value("HEAD").help("Base revision"))
+                .arg(Arg::new("rev2").help("Target revision (default: working tree)"))
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the diff as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("modernize")
+                .about("Suggest std or lighter replacements for obsolete dependencies")
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print findings as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("msrv-check")
+                .about("Find dependencies that need a newer compiler than rust-version")
+                .arg(
+                    Arg::new("ci")
+                        .long("ci")
+                        .help("Exit with a non-zero status when incompatibilities are found")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("lint-versions")
+                .about("Flag wildcard, pinned and overly broad version requirements")
+                .arg(
+                    Arg::new("fix")
+                        .long("fix")
+                        .help("Rewrite requirements to the configured style")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("bloat")
+                .about("Summarize the biggest functions or crates using cargo-bloat")
+                .arg(
+                    Arg::new("crates")
+                        .long("crates")
+                        .help("Group sizes by crate instead of by function")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("top")
+                        .long("top")
+                        .value_name("N")
+                        .help("Number of entries to show")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("10"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the summary as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("macro-cost")
+                .about("List proc macros and build scripts by how long they block the build")
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the report as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("cache-report")
+                .about("Measure how much sccache or the incremental cache speeds up builds")
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the report as JSON")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(yes_arg("Skip the confirmation before `cargo clean`")),
+        )
+        .subcommand(
+            ClapCommand::new("rebuild-frequency")
+                .about("Show which crates recompile most often across recorded builds")
+                .arg(
+                    Arg::new("last")
+                        .long("last")
+                        .value_name("N")
+                        .help("Number of recent builds to include")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("20"),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("why-rebuild")
+                .about("Explain why cargo rebuilds crates that should be fresh"),
+        )
+        .subcommand(
+            ClapCommand::new("audit-profile")
+                .about("Flag common misconfigurations in [profile.*] settings")
+                .arg(
+                    Arg::new("fix")
+                        .long("fix")
+                        .help("Write the suggested settings to Cargo.toml")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(yes_arg("Apply fixes without asking")),
+        )
+        .subcommand(
+            ClapCommand::new("build-time")
+                .about("Analyze build performance")
+                .arg(
+                    Arg::new("history")
+                        .long("history")
+                        .help("Show previously recorded builds instead of building")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("release")
+                        .long("release")
+                        .help("Measure a release build")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("profile"),
+                )
+                .arg(
+                    Arg::new("profile")
+                        .long("profile")
+                        .value_name("NAME")
+                        .help("Build with a custom cargo profile (e.g. dist)"),
+                )
+                .arg(
+                    Arg::new("target")
+                        .long("target")
+                        .value_name("TRIPLE")
+                        .help("Cross-compile for the given target triple")
+                        .value_parser(performance::validate_target_triple),
+                )
+                .arg(
+                    Arg::new("features")
+                        .long("features")
+                        .short('F')
+                        .value_name("FEATURES")
+                        .help("Features to enable (comma separated, repeatable)")
+                        .value_delimiter(',')
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("no-default-features")
+                        .long("no-default-features")
+                        .help("Build without the default features")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("top")
+                        .long("top")
+                        .value_name("N")
+                        .help("Number of slowest crates to list (default 10)")
+                        .value_parser(clap::value_parser!(usize))
+                        .conflicts_with("all"),
+                )
+                .arg(
+                    Arg::new("all")
+                        .long("all")
+                        .help("List every compiled crate")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the measurement as JSON")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("cold")
+                        .long("cold")
+                        .help("Run `cargo clean` first and measure a from-scratch build")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("compare-warm")
+                        .long("compare-warm")
+                        .help("Measure a cold build, then an incremental rebuild")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("matrix")
+                        .long("matrix")
+                        .value_name("FEATURES")
+                        .help(
+                            "Compare clean builds per feature set (`default`, `none` or a \
+                             comma-separated list); defaults to [build-time] matrix",
+                        )
+                        .num_args(0..=1)
+                        .action(ArgAction::Append)
+                        .conflicts_with_all(["compare-warm", "baseline", "compare", "json"]),
+                )
+                .arg(
+                    Arg::new("doc")
+                        .long("doc")
+                        .help("Time `cargo doc --no-deps` against a full `cargo doc` and size target/doc")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all([
+                            "matrix",
+                            "toolchains",
+                            "check-delta",
+                            "compare-warm",
+                            "cold",
+                            "baseline",
+                            "compare",
+                            "json",
+                        ]),
+                )
+                .arg(
+                    Arg::new("toolchains")
+                        .long("toolchains")
+                        .value_name("TOOLCHAINS")
+                        .help("Compare clean builds per rustup toolchain (e.g. stable,beta,1.74.0)")
+                        .value_delimiter(',')
+                        .action(ArgAction::Append)
+                        .conflicts_with_all([
+                            "matrix",
+                            "compare-warm",
+                            "baseline",
+                            "compare",
+                            "json",
+                        ]),
+                )
+                .arg(
+                    Arg::new("mem-limit")
+                        .long("mem-limit")
+                        .value_name("SIZE")
+                        .help("Warn when peak build memory exceeds SIZE (e.g. 6G)")
+                        .value_parser(memory::parse_size),
+                )
+                .arg(
+                    Arg::new("max")
+                        .long("max")
+                        .value_name("DURATION")
+                        .help("Fail when the build takes longer than DURATION (e.g. 4m30s)")
+                        .value_parser(performance::parse_budget),
+                )
+                .arg(
+                    Arg::new("max-regression")
+                        .long("max-regression")
+                        .value_name("PERCENT")
+                        .help(
+                            "Fail when the build is more than PERCENT slower than the \
+                             --compare baseline or the recent median (e.g. 15%)",
+                        )
+                        .value_parser(performance::parse_percent),
+                )
+                .arg(
+                    Arg::new("ci")
+                        .long("ci")
+                        .help("Exit with a non-zero status when a limit is exceeded")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("check-delta")
+                        .long("check-delta")
+                        .help("Compare an incremental `cargo check` with an incremental build")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all([
+                            "matrix",
+                            "toolchains",
+                            "compare-warm",
+                            "cold",
+                            "baseline",
+                            "compare",
+                            "json",
+                        ]),
+                )
+                .arg(
+                    Arg::new("open")
+                        .long("open")
+                        .help("Open the HTML timings report in the browser afterwards")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("quick")
+                        .long("quick")
+                        .help("Use `cargo check` instead of full builds in the matrix")
+                        .action(ArgAction::SetTrue)
+                        .requires("matrix"),
+                )
+                .arg(yes_arg("Don't ask before cleaning the target directory"))
+                .arg(
+                    Arg::new("baseline")
+                        .long("baseline")
+                        .value_name("NAME")
+                        .help("Save this run as a named baseline"),
+                )
+                .arg(
+                    Arg::new("compare")
+                        .long("compare")
+                        .value_name("NAME")
+                        .help("Compare this run with a saved baseline"),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("size")
+                .about("Break down release binary size by section and crate")
+                .arg(
+                    Arg::new("no-build")
+                        .long("no-build")
+                        .help("Analyze existing release binaries instead of building")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the breakdown as JSON")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("history")
+                        .long("history")
+                        .help("Show recorded sizes over time")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("max-size")
+                        .long("max-size")
+                        .value_name("SIZE")
+                        .help("Fail when a binary is larger than this, e.g. 12MB")
+                        .value_parser(memory::parse_size)
+                        .conflicts_with("history"),
+                )
+                .arg(
+                    Arg::new("max-growth")
+                        .long("max-growth")
+                        .value_name("PERCENT")
+                        .help("Fail when a binary grew more than this since its last recorded size, e.g. 5%")
+                        .value_parser(performance::parse_percent)
+                        .conflicts_with("history"),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("target-size")
+                .about("Show target directory size per profile and area"),
+        )
+        .subcommand(
+            ClapCommand::new("clean-smart")
+                .about("Delete only build artifacts that haven't been touched recently")
+                .arg(
+                    Arg::new("older-than")
+                        .long("older-than")
+                        .value_name("AGE")
+                        .default_value(target_dir::DEFAULT_OLDER_THAN)
+                        .help("Delete artifacts untouched for this long (e.g. 30d, 2w)"),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help("List stale artifacts without deleting them")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(yes_arg("Delete without asking")),
+        )
+        .subcommand(
+            ClapCommand::new("tune-jobs")
+                .about("Time builds with different --jobs values and recommend one")
+                .arg(
+                    Arg::new("jobs")
+                        .long("jobs")
+                        .value_name("N,N,...")
+                        .help("Job counts to try (default: spread around the CPU count)")
+                        .value_delimiter(',')
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("samples")
+                        .long("samples")
+                        .value_name("N")
+                        .help("Builds per job count")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("3"),
+                )
+                .arg(
+                    Arg::new("clean")
+                        .long("clean")
+                        .help("Measure clean builds instead of incremental rebuilds")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("apply")
+                        .long("apply")
+                        .help("Write the recommendation to .cargo/config.toml")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(yes_arg("Don't ask before cleaning the target directory")),
+        )
+        .subcommand(
+            ClapCommand::new("experiment")
+                .about("Compare build time and binary size across release codegen settings")
+                .arg(
+                    Arg::new("codegen-units")
+                        .long("codegen-units")
+                        .value_name("N,N,...")
+                        .help("codegen-units values to try")
+                        .value_delimiter(',')
+                        .value_parser(clap::value_parser!(u32))
+                        .default_value("1,16"),
+                )
+                .arg(
+                    Arg::new("lto")
+                        .long("lto")
+                        .value_name("MODE,...")
+                        .help("LTO settings to try")
+                        .value_delimiter(',')
+                        .value_parser(["off", "thin", "fat"])
+                        .default_value("off,thin,fat"),
+                )
+                .arg(
+                    Arg::new("opt-level")
+                        .long("opt-level")
+                        .value_name("LEVEL,...")
+                        .help("opt-level values to try (default: keep the profile's)")
+                        .value_delimiter(',')
+                        .value_parser(["0", "1", "2", "3", "s", "z"]),
+                )
+                .arg(yes_arg("Don't ask before cleaning the target directory")),
+        )
+        .subcommand(
+            ClapCommand::new("pipeline")
+                .about("Run the cargo steps configured under [pipeline] in .sleek.toml")
+                .arg(
+                    Arg::new("keep-going")
+                        .long("keep-going")
+                        .help("Continue with later steps after a failure")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("fail-fast")
+                        .long("fail-fast")
+                        .help("Stop at the first failing step (the default)")
+                        .conflicts_with("keep-going")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("aliases").about("List the command aliases configured in .sleek.toml"),
+        )
+        .subcommand(
+            ClapCommand::new("logs")
+                .about("List recent logs written with --log-dir")
+                .arg(
+                    Arg::new("last")
+                        .long("last")
+                        .value_name("N")
+                        .help("Number of logs to list")
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .subcommand(
+                    ClapCommand::new("show")
+                        .about("Print a log; 1 is the most recent")
+                        .arg(
+                            Arg::new("n")
+                                .required(true)
+                                .value_parser(clap::value_parser!(usize)),
+                        ),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("chain")
+                .about("Run cargo steps in sequence, e.g. `chain fmt clippy test` or a name from [chains]")
+                .arg(
+                    Arg::new("steps")
+                        .required(true)
+                        .num_args(1..)
+                        .help("Cargo commands (quote to pass arguments) or names of configured chains"),
+                )
+                .arg(
+                    Arg::new("keep-going")
+                        .long("keep-going")
+                        .help("Continue with later steps after a failure")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("watch")
+                .about("Re-run a cargo command whenever src/, Cargo.toml or watched paths change")
+                .after_help("Watch options go before the command; anything after it is passed to cargo.")
+                .arg(Arg::new("command").required(true).help("Cargo command to run, e.g. test"))
+                .args(passthrough_args("Arguments passed on to cargo"))
+                .arg(
+                    Arg::new("path")
+                        .long("path")
+                        .value_name("PATH")
+                        .help("Also watch PATH (repeatable; see [watch] paths in .sleek.toml)")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("watch-glob")
+                        .long("watch-glob")
+                        .value_name("GLOB")
+                        .help("Only changes matching GLOB re-run the command (repeatable)")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("ignore")
+                        .long("ignore")
+                        .value_name("GLOB")
+                        .help("Never re-run for changes matching GLOB (repeatable)")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("debounce")
+                        .long("debounce")
+                        .value_name("MS")
+                        .help("Wait for MS milliseconds without changes before re-running")
+                        .default_value("300")
+                        .value_parser(clap::value_parser!(u64)),
+                )
+                .arg(
+                    Arg::new("clear")
+                        .long("clear")
+                        .help("Clear the screen before each run")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print a JSON line per run instead of the status line")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("build")
+                .about("Run cargo build")
+                .arg(yes_arg(CONFIRM_HELP))
+                .args(passthrough_args("Arguments passed on to cargo build")),
+        )
+        .subcommand(
+            ClapCommand::new("clean")
+                .about("Run cargo clean")
+                .arg(yes_arg(CONFIRM_HELP))
+                .args(passthrough_args("Arguments passed on to cargo clean")),
+        )
+        .subcommand(
+            ClapCommand::new("test")
+                .about("Run cargo test and record test times")
+                .arg(yes_arg(CONFIRM_HELP))
+                .args(passthrough_args("Arguments passed on to cargo test")),
+        )
+        .subcommand(
+            ClapCommand::new("check")
+                .about("Run cargo check")
+                .arg(yes_arg(CONFIRM_HELP))
+                .args(passthrough_args("Arguments passed on to cargo check")),
+        )
+        .subcommand(
+            ClapCommand::new("clippy")
+                .about("Run cargo clippy")
+                .arg(yes_arg(CONFIRM_HELP))
+                .args(passthrough_args("Arguments passed on to cargo clippy")),
+        )
+        .subcommand(
+            ClapCommand::new("fmt")
+                .about("Run cargo fmt")
+                .arg(yes_arg(CONFIRM_HELP))
+                .args(passthrough_args("Arguments passed on to cargo fmt")),
+        )
+        .subcommand(
+            ClapCommand::new("doc")
+                .about("Run cargo doc")
+                .arg(yes_arg(CONFIRM_HELP))
+                .args(passthrough_args("Arguments passed on to cargo doc")),
+        )
+        .subcommand(
+            ClapCommand::new("bench")
+                .about("Run cargo bench and compare the results with the previous run")
+                .arg(
+                    Arg::new("history")
+                        .long("history")
+                        .value_name("NAME")
+                        .help("Show the recorded trend of one benchmark instead of running"),
+                )
+                .arg(
+                    Arg::new("max-regression")
+                        .long("max-regression")
+                        .value_name("PERCENT")
+                        .help("Slowdown flagged as a regression (default 5%, see [bench] in .sleek.toml)")
+                        .value_parser(performance::parse_percent),
+                )
+                .arg(
+                    Arg::new("ci")
+                        .long("ci")
+                        .help("Exit with a non-zero status when a benchmark regressed")
+                        .action(ArgAction::SetTrue),
+                )
+                .args(passthrough_args("Arguments passed on to cargo bench")),
+        )
+        .subcommand(
+            ClapCommand::new("test-times")
+                .about("Show the slowest tests and the suite time trend")
+                .arg(
+                    Arg::new("top")
+                        .long("top")
+                        .value_name("N")
+                        .help("Number of slowest tests to list")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("10"),
+                ),
+        )
+        .subcommand(
+            ClapCommand::new("run")
+                .about("Run the project")
+                .arg(yes_arg(CONFIRM_HELP))
+                .arg(
+                    Arg::new("measure-startup")
+                        .long("measure-startup")
+                        .value_name("N")
+                        .help("Build, then time N launches of the binary (default 10) instead of running it")
+                        .value_parser(clap::value_parser!(usize))
+                        .num_args(0..=1)
+                        .default_missing_value("10"),
+                )
+                .arg(
+                    Arg::new("first-output")
+                        .long("first-output")
+                        .help("Time launches to the first byte of output instead of to exit")
+                        .requires("measure-startup")
+                        .action(ArgAction::SetTrue),
+                )
+                .args(passthrough_args("Arguments passed on to cargo run")),
+        )
+        .get_matches_from(cli_args());
+    let config = config?;
 
     let verbose = matches.get_flag("verbose");
+    let offline = matches.get_flag("offline");
+    let log_dir = matches
+        .get_one::<PathBuf>("log-dir")
+        .cloned()
+        .or_else(|| config.log_dir.clone());
+    if matches.get_flag("no-color") {
+        colored::control::set_override(false);
+    }
+    let output_mode = output::detect(
+        matches.get_flag("quiet"),
+        matches.get_flag("plain"),
+        std::env::var_os("CI").is_some_and(|ci| !ci.is_empty()),
+        std::io::stdout().is_terminal(),
+    );
+    output::set_mode(output_mode);
+    if config.tracking == Some(false) {
+        stats::disable_tracking();
+    }
+    let stats_path = &stats::resolve_stats_path(
+        matches
+            .get_one::<PathBuf>("stats-file")
+            .map(PathBuf::as_path),
+        std::env::var_os(stats::STATS_FILE_ENV).as_deref(),
+        config.stats_file.as_deref(),
+    );
+    let exec_options = executor::ExecOptions {
+        verbose,
+        track_args: matches.get_flag("track-args") || config.track_args,
+        record_test_times: matches.get_flag("record-test-times") || config.record_test_times,
+        skip_project_check: matches.get_flag("no-project-check"),
+        log_file: matches.get_one::<PathBuf>("log").cloned(),
+        log_dir: log_dir.clone(),
+        limit_output: matches.get_one::<usize>("limit-output").copied(),
+        report: matches
+            .get_one::<PathBuf>("report-json")
+            .map(|path| report::ReportOptions {
+                path: path.clone(),
+                append: matches.get_flag("report-append"),
+            }),
+        env: matches
+            .get_many::<(String, String)>("env")
+            .map(|pairs| pairs.cloned().collect())
+            .unwrap_or_default(),
+        confirm: config.confirm.commands.clone(),
+        spinner: !matches.get_flag("no-color") && output_mode == output::OutputMode::Decorated,
+        retries: matches.get_one::<u32>("retries").copied().unwrap_or(0),
+        retry_delay: *matches.get_one::<Duration>("retry-delay").unwrap(),
+        dry_run: matches.get_flag("dry-run")
+            || matches
+                .subcommand()
+                .is_some_and(|(_, sub)| passthrough_dry_run(sub)),
+        hooks: if matches.get_flag("no-hooks") {
+            Default::default()
+        } else {
+            config.hooks.clone()
+        },
+        ..Default::default()
+    };
+
+    let needs_project = match matches.subcommand() {
+        Some(("build-time" | "size", sub)) => !sub.get_flag("history"),
+        Some(("bench", sub)) => !sub.contains_id("history"),
+        Some((name, _)) => matches!(
+            name,
+            "check-deps"
+                | "yanked"
+                | "deps-age"
+                | "deps-count"
+                | "check-dupes"
+                | "workspace-deps"
+                | "check-features"
+                | "modernize"
+                | "msrv-check"
+                | "lint-versions"
+                | "audit-profile"
+                | "why-rebuild"
+                | "macro-cost"
+                | "cache-report"
+                | "bloat"
+                | "target-size"
+                | "clean-smart"
+                | "tune-jobs"
+                | "experiment"
+                | "watch"
+        ),
+        None => false,
+    };
+    if needs_project && !exec_options.skip_project_check {
+        executor::ensure_cargo_project(Path::new("."))?;
+    }
 
     match matches.subcommand() {
-        Some(("stats", _)) => stats::show_stats()?,
-        Some(("reset", sub)) => stats::reset_stats(sub)?,
-        Some(("check-deps", _)) => dependencies::check_unused_deps()?,
-        Some(("build-time", _)) => performance::analyze_build_time(verbose)?,
-        Some(("run", sub)) => executor::execute_cargo_command("run", sub, verbose)?,
-        Some(("build", sub)) => executor::execute_cargo_command("build", sub, verbose)?,
-        Some(("clean", sub)) => executor::execute_cargo_command("clean", sub, verbose)?,
-        _ => println!("❌ Unknown command. Use `cargo sleek --help`."),
+        Some(("stats", sub)) if sub.get_flag("where") => {
+            println!("{}", stats::absolute_stats_path(stats_path)?.display())
+        }
+        Some(("stats", sub)) if sub.get_flag("open-dir") => stats::open_stats_dir(stats_path)?,
+        Some(("stats", _)) if !stats::tracking_enabled() => stats::show_tracking_disabled(),
+        Some(("stats", sub)) if sub.contains_id("merge") => {
+            stats::merge_stats_file(stats_path, sub.get_one::<PathBuf>("merge").unwrap())?
+        }
+        Some(("stats", sub)) if sub.get_flag("restore") => stats::restore_stats(stats_path)?,
+        Some(("stats", sub)) if sub.get_flag("graph") => stats::show_graph(stats_path)?,
+        Some(("stats", sub)) => {
+            let since = sub
+                .get_one::<String>("since")
+                .map(|value| stats::parse_since(value, chrono::Utc::now()))
+                .transpose()?;
+            stats::show_stats(
+                stats_path,
+                since,
+                sub.get_one::<PathBuf>("output").map(PathBuf::as_path),
+                sub.get_flag("toml"),
+                stats::StatsColumns {
+                    first_used: sub.get_flag("first-used") || verbose,
+                    last_exit: verbose,
+                },
+            )?
+        }
+        Some(("summary", sub)) => stats::show_summary(
+            stats_path,
+            Path::new(history::HISTORY_FILE),
+            sub.get_one::<PathBuf>("output").map(PathBuf::as_path),
+        )?,
+        Some(("reset", sub)) => stats::reset_stats(stats_path, sub.get_flag("force"))?,
+        Some(("check-deps", sub)) => {
+            let mut ignore = config.check_deps.ignore.clone();
+            if let Some(extra) = sub.get_many::<String>("ignore") {
+                ignore.extend(extra.cloned());
+            }
+            let options = dependencies::CheckOptions {
+                ignore,
+                fix: sub.get_flag("fix"),
+                explain: sub.get_flag("explain"),
+                features: sub.get_flag("features"),
+                offline,
+            };
+            if sub.get_flag("count-only") {
+                if dependencies::count_unused_deps(Path::new("."), &options.ignore)? > 0 {
+                    std::process::exit(1);
+                }
+            } else if sub.get_flag("porcelain") {
+                if dependencies::check_unused_porcelain(Path::new("."), &options.ignore)? {
+                    std::process::exit(1);
+                }
+            } else if dependencies::check_unused_deps(Path::new("."), &options)?
+                && sub.get_flag("ci")
+            {
+                std::process::exit(1);
+            }
+        }
+        Some(("cache", sub)) => match sub.subcommand() {
+            Some(("clear", _)) => cache::clear_cache()?,
+            _ => cache::show_cache_stats()?,
+        },
+        Some(("yanked", _)) => yanked::check_yanked(Path::new("."), offline)?,
+        Some(("deps-age", sub)) => {
+            let threshold = sub
+                .get_one::<u32>("threshold")
+                .copied()
+                .or(config.deps_age.threshold_months)
+                .unwrap_or(deps_age::DEFAULT_THRESHOLD_MONTHS);
+            deps_age::show_deps_age(Path::new("."), threshold, offline)?
+        }
+        Some(("deps-count", sub)) => {
+            deps_count::show_deps_count(Path::new("."), sub.get_flag("json"))?
+        }
+        Some(("check-dupes", sub)) => {
+            dupes::check_dupes(Path::new("."), sub.get_flag("fix"), exec_options.dry_run)?
+        }
+        Some(("workspace-deps", sub)) => {
+            workspace::check_workspace_deps(Path::new("."), sub.get_flag("fix"))?
+        }
+        Some(("check-features", _)) => features::check_features(Path::new("."))?,
+        Some(("lock-diff", sub)) => lock_diff::show_lock_diff(
+            Path::new("."),
+            sub.get_one::<String>("rev1").unwrap(),
+            sub.get_one::<String>("rev2").map(String::as_str),
+            sub.get_flag("json"),
+        )?,
+        Some(("modernize", sub)) => {
+            modernize::check_modernize(Path::new("."), sub.get_flag("json"))?
+        }
+        Some(("msrv-check", sub)) => {
+            if !msrv::msrv_check(Path::new("."))? && sub.get_flag("ci") {
+                std::process::exit(1);
+            }
+        }
+        Some(("lint-versions", sub)) => lint_versions::lint_versions(
+            Path::new("."),
+            config.lint_versions.style,
+            sub.get_flag("fix"),
+        )?,
+        Some(("bloat", sub)) => bloat::run_bloat(
+            stats_path,
+            sub.get_flag("crates"),
+            *sub.get_one::<usize>("top").unwrap(),
+            sub.get_flag("json"),
+        )?,
+        Some(("cache-report", sub)) => {
+            build_cache::cache_report(sub.get_flag("json"), sub.get_flag("yes"))?
+        }
+        Some(("macro-cost", sub)) => {
+            macro_cost::show_macro_cost(Path::new("."), sub.get_flag("json"))?
+        }
+        Some(("why-rebuild", _)) => {
+            rebuild::why_rebuild(Path::new("."), Path::new(history::HISTORY_FILE))?
+        }
+        Some(("rebuild-frequency", sub)) => rebuild::show_rebuild_frequency(
+            Path::new(history::HISTORY_FILE),
+            *sub.get_one::<usize>("last").unwrap(),
+        )?,
+        Some(("audit-profile", sub)) => {
+            profile_audit::audit_profile(Path::new("."), sub.get_flag("fix"), sub.get_flag("yes"))?
+        }
+        Some(("build-time", sub)) => {
+            let history_path = Path::new(history::HISTORY_FILE);
+            if sub.get_flag("history") {
+                history::show_history(history_path)?
+            } else {
+                let options = performance::BuildOptions {
+                    release: sub.get_flag("release"),
+                    profile: sub.get_one::<String>("profile").cloned(),
+                    target: sub.get_one::<String>("target").cloned(),
+                    features: sub
+                        .get_many::<String>("features")
+                        .map(|values| values.cloned().collect())
+                        .unwrap_or_default(),
+                    no_default_features: sub.get_flag("no-default-features"),
+                    top: if sub.get_flag("all") {
+                        None
+                    } else {
+                        Some(
+                            sub.get_one::<usize>("top")
+                                .copied()
+                                .unwrap_or(timings::DEFAULT_TOP),
+                        )
+                    },
+                    json: sub.get_flag("json"),
+                    baseline: sub.get_one::<String>("baseline").cloned(),
+                    compare: sub.get_one::<String>("compare").cloned(),
+                    cold: sub.get_flag("cold"),
+                    compare_warm: sub.get_flag("compare-warm"),
+                    yes: sub.get_flag("yes"),
+                    check: sub.get_flag("quick"),
+                    mem_limit: sub.get_one::<u64>("mem-limit").copied(),
+                    max_duration: sub.get_one::<Duration>("max").copied(),
+                    max_regression: sub.get_one::<f64>("max-regression").copied(),
+                    ..Default::default()
+                };
+                if sub.contains_id("matrix") {
+                    let mut specs: Vec<String> = sub
+                        .get_many::<String>("matrix")
+                        .map(|values| values.cloned().collect())
+                        .unwrap_or_default();
+                    if specs.is_empty() {
+                        specs = config.build_time.matrix.clone();
+                    }
+                    let sets: Vec<_> = specs
+                        .iter()
+                        .map(|spec| performance::FeatureSet::parse(spec))
+                        .collect();
+                    performance::feature_matrix(history_path, &options, &sets)?
+                } else if let Some(toolchains) = sub.get_many::<String>("toolchains") {
+                    let toolchains: Vec<String> = toolchains.cloned().collect();
+                    performance::toolchain_comparison(history_path, &options, &toolchains)?
+                } else if sub.get_flag("doc") {
+                    performance::doc_build_time(history_path, &options)?
+                } else if sub.get_flag("check-delta") {
+                    performance::check_delta(history_path, &options)?
+                } else {
+                    let within_limits =
+                        performance::analyze_build_time(history_path, &options, verbose)?;
+                    if sub.get_flag("open") {
+                        let metadata = metadata::cargo_metadata(Path::new("."))?;
+                        timings::open_timing_report(&metadata.target_directory)?;
+                    }
+                    if !within_limits
+                        && (sub.get_flag("ci")
+                            || options.max_duration.is_some()
+                            || options.max_regression.is_some())
+                    {
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+        Some(("size", sub)) => {
+            let history_path = Path::new(history::HISTORY_FILE);
+            if sub.get_flag("history") {
+                history::show_size_history(history_path)?
+            } else {
+                let limits = size::SizeLimits {
+                    max_size: sub.get_one::<u64>("max-size").copied(),
+                    max_growth: sub.get_one::<f64>("max-growth").copied(),
+                };
+                if !size::analyze_size(
+                    history_path,
+                    !sub.get_flag("no-build"),
+                    sub.get_flag("json"),
+                    limits,
+                )? {
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(("target-size", _)) => {
+            let metadata = metadata::cargo_metadata(Path::new("."))?;
+            target_dir::show_target_size(&metadata.target_directory)?
+        }
+        Some(("clean-smart", sub)) => {
+            let metadata = metadata::cargo_metadata(Path::new("."))?;
+            let cutoff = stats::parse_since(
+                sub.get_one::<String>("older-than").unwrap(),
+                chrono::Utc::now(),
+            )?;
+            target_dir::clean_smart(
+                &metadata.target_directory,
+                cutoff,
+                sub.get_flag("dry-run"),
+                sub.get_flag("yes"),
+            )?
+        }
+        Some(("tune-jobs", sub)) => {
+            let options = tune_jobs::TuneOptions {
+                jobs: sub
+                    .get_many::<usize>("jobs")
+                    .map(|values| values.copied().collect())
+                    .unwrap_or_else(|| {
+                        tune_jobs::default_job_counts(
+                            std::thread::available_parallelism().map_or(4, |n| n.get()),
+                        )
+                    }),
+                samples: *sub.get_one::<usize>("samples").unwrap(),
+                clean: sub.get_flag("clean"),
+                apply: sub.get_flag("apply"),
+                yes: sub.get_flag("yes"),
+            };
+            tune_jobs::tune_jobs(Path::new("."), Path::new(history::HISTORY_FILE), &options)?
+        }
+        Some(("experiment", sub)) => {
+            let values = |id: &str| -> Vec<String> {
+                sub.get_many::<String>(id)
+                    .map(|values| values.cloned().collect())
+                    .unwrap_or_default()
+            };
+            let codegen_units: Vec<u32> = sub
+                .get_many::<u32>("codegen-units")
+                .map(|values| values.copied().collect())
+                .unwrap_or_default();
+            let matrix =
+                experiment::experiment_matrix(&codegen_units, &values("lto"), &values("opt-level"));
+            experiment::run_experiment(Path::new("."), &matrix, sub.get_flag("yes"))?
+        }
+        Some(("pipeline", sub)) => {
+            let code = pipeline::run_pipeline(
+                stats_path,
+                &config.pipeline.steps,
+                sub.get_flag("keep-going"),
+                &exec_options,
+            )?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Some(("watch", sub)) => {
+            let strings = |id: &str| -> Vec<String> {
+                sub.get_many::<String>(id)
+                    .map(|values| values.cloned().collect())
+                    .unwrap_or_default()
+            };
+            let mut extra_paths = config.watch.paths.clone();
+            if let Some(paths) = sub.get_many::<PathBuf>("path") {
+                extra_paths.extend(paths.cloned());
+            }
+            let mut ignore = config.watch.ignore.clone();
+            ignore.extend(strings("ignore"));
+            let filter =
+                watch::WatchFilter::new(&watch::watch_globs(&strings("watch-glob"), &extra_paths))?
+                    .ignoring(&ignore)?;
+            let options = watch::WatchOptions {
+                command: sub.get_one::<String>("command").unwrap().clone(),
+                args: extra_args(sub),
+                paths: watch::DEFAULT_WATCH_PATHS
+                    .iter()
+                    .map(PathBuf::from)
+                    .chain(extra_paths)
+                    .collect(),
+                filter,
+                debounce: Duration::from_millis(*sub.get_one::<u64>("debounce").unwrap()),
+                clear: sub.get_flag("clear"),
+                json: sub.get_flag("json"),
+            };
+            watch::watch(stats_path, &options, &exec_options)?
+        }
+        Some(("chain", sub)) => {
+            let steps: Vec<String> = sub.get_many::<String>("steps").unwrap().cloned().collect();
+            let code = pipeline::run_chain(
+                stats_path,
+                &steps,
+                &config.chains,
+                sub.get_flag("keep-going"),
+                &exec_options,
+            )?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Some(("bench", sub)) => {
+            let history_path = Path::new(history::HISTORY_FILE);
+            if let Some(name) = sub.get_one::<String>("history") {
+                bench::show_bench_history(history_path, name)?
+            } else {
+                let threshold = sub
+                    .get_one::<f64>("max-regression")
+                    .copied()
+                    .or(config.bench.max_regression)
+                    .unwrap_or(bench::DEFAULT_REGRESSION_THRESHOLD);
+                if !bench::run_bench(history_path, stats_path, &extra_args(sub), threshold)?
+                    && sub.get_flag("ci")
+                {
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(("test-times", sub)) => test_times::show_test_times(
+            Path::new(history::HISTORY_FILE),
+            *sub.get_one::<usize>("top").unwrap(),
+        )?,
+        Some(("run", sub)) if sub.contains_id("measure-startup") => startup::measure_startup(
+            Path::new(history::HISTORY_FILE),
+            stats_path,
+            &extra_args(sub),
+            *sub.get_one::<usize>("measure-startup").unwrap(),
+            sub.get_flag("first-output"),
+        )?,
+        Some((
+            command @ ("run" | "build" | "clean" | "test" | "check" | "clippy" | "fmt" | "doc"),
+            sub,
+        )) => {
+            let options = executor::ExecOptions {
+                assume_yes: sub.get_flag("yes"),
+                ..exec_options
+            };
+            let status =
+                executor::execute_cargo_command(stats_path, command, &extra_args(sub), &options)?;
+            if !status.success() {
+                std::process::exit(executor::exit_code(status));
+            }
+        }
+        Some(("aliases", _)) => alias::show_aliases(&config.aliases),
+        Some(("logs", sub)) => {
+            let dir = log_dir.unwrap_or_else(|| PathBuf::from(logs::DEFAULT_LOG_DIR));
+            match sub.subcommand() {
+                Some(("show", show)) => logs::show_log(&dir, *show.get_one::<usize>("n").unwrap())?,
+                _ => logs::show_logs(&dir, *sub.get_one::<usize>("last").unwrap())?,
+            }
+        }
+        Some((command, sub)) => {
+            // Not a sleek subcommand: expand a configured alias, else hand it to cargo as is.
+            let mut args: Vec<String> = sub
+                .get_many::<String>("")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default();
+            let mut options = exec_options.clone();
+            let mut command = command.to_string();
+            if let Some(mut expansion) = alias::resolve_alias(&command, &config.aliases)? {
+                options.alias = Some(command);
+                command = expansion.remove(0);
+                expansion.extend(args);
+                args = expansion;
+            }
+            if command == "sleek" {
+                // `cargo sleek` would run this binary again, and again.
+                bail!("`sleek` can't be passed through to cargo; drop the repeated `sleek`");
+            }
+            let status = executor::execute_cargo_command(stats_path, &command, &args, &options)?;
+            if !status.success() {
+                std::process::exit(executor::exit_code(status));
+            }
+        }
+        None => output::warning("❌ Unknown command. Use `cargo sleek --help`."),
     }
 
     Ok(())
@@ -0,0 +1,151 @@
+//! Peak memory of a process tree, sampled while it runs.
+
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Time between two memory samples.
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Resident memory of one process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessMemory {
+    pub pid: u32,
+    pub ppid: u32,
+    pub rss_bytes: u64,
+}
+
+/// Parses `ps -A -o pid=,ppid=,rss=` output (RSS in KiB).
+pub fn parse_ps_output(output: &str) -> Vec<ProcessMemory> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().map(str::parse::<u64>);
+            let (Some(Ok(pid)), Some(Ok(ppid)), Some(Ok(rss))) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return None;
+            };
+            Some(ProcessMemory {
+                pid: pid as u32,
+                ppid: ppid as u32,
+                rss_bytes: rss * 1024,
+            })
+        })
+        .collect()
+}
+
+/// Parses the `PPid` and `VmRSS` lines of a `/proc/<pid>/status` file.
+pub fn parse_proc_status(pid: u32, status: &str) -> Option<ProcessMemory> {
+    let field = |key: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|value| value.split_whitespace().next())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    Some(ProcessMemory {
+        pid,
+        ppid: field("PPid:")? as u32,
+        // Kernel threads and zombies have no VmRSS line.
+        rss_bytes: field("VmRSS:").unwrap_or(0) * 1024,
+    })
+}
+
+/// All processes currently running, from procfs on Linux and `ps` elsewhere.
+pub fn snapshot() -> Vec<ProcessMemory> {
+    if let Ok(entries) = fs::read_dir("/proc") {
+        let processes: Vec<ProcessMemory> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|pid| {
+                let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+                parse_proc_status(pid, &status)
+            })
+            .collect();
+        if !processes.is_empty() {
+            return processes;
+        }
+    }
+    Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid=,rss="])
+        .output()
+        .map(|out| parse_ps_output(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+/// Combined RSS of `root` and all of its descendants.
+pub fn tree_rss(processes: &[ProcessMemory], root: u32) -> u64 {
+    let mut children: HashMap<u32, Vec<&ProcessMemory>> = HashMap::new();
+    for process in processes {
+        children.entry(process.ppid).or_default().push(process);
+    }
+    let mut total = processes
+        .iter()
+        .find(|p| p.pid == root)
+        .map_or(0, |p| p.rss_bytes);
+    let mut stack = vec![root];
+    while let Some(pid) = stack.pop() {
+        for child in children.get(&pid).into_iter().flatten() {
+            // Guard against pid reuse creating a cycle.
+            if child.pid != root {
+                total += child.rss_bytes;
+                stack.push(child.pid);
+            }
+        }
+    }
+    total
+}
+
+/// Parses a size such as `6G`, `512M`, `1.5GiB` or a plain byte count (binary units).
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let trimmed = text.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size `{text}`, expected e.g. 6G or 512M"))?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => return Err(format!("unknown unit in `{text}`, use K, M, G or T")),
+    };
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// Samples the memory of a process tree on a background thread until finished.
+pub struct PeakSampler {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<u64>,
+}
+
+impl PeakSampler {
+    pub fn start(pid: u32) -> PeakSampler {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let mut peak = 0;
+            while !flag.load(Ordering::Relaxed) {
+                peak = peak.max(tree_rss(&snapshot(), pid));
+                thread::sleep(SAMPLE_INTERVAL);
+            }
+            peak
+        });
+        PeakSampler { stop, handle }
+    }
+
+    /// Stops sampling and returns the peak, `None` when nothing could be measured.
+    pub fn finish(self) -> Option<u64> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle.join().ok().filter(|&peak| peak > 0)
+    }
+}
@@ -0,0 +1,149 @@
+//! Typed subset of `cargo metadata --format-version 1` output.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use crate::executor;
+
+/// `cargo metadata` results already fetched by this process, keyed by canonical project
+/// directory and whether `--no-deps` was used.
+static CACHE: OnceLock<Mutex<HashMap<(PathBuf, bool), Metadata>>> = OnceLock::new();
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Metadata {
+    pub packages: Vec<Package>,
+    pub workspace_members: Vec<String>,
+    pub target_directory: PathBuf,
+    /// Directory of the workspace root manifest, which holds the `[profile]` tables.
+    #[serde(default)]
+    pub workspace_root: PathBuf,
+    /// Dependency graph; absent when run with `--no-deps`.
+    #[serde(default)]
+    pub resolve: Option<Resolve>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Resolve {
+    pub nodes: Vec<Node>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Node {
+    pub id: String,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Package {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    /// Declared `rust-version`, if any.
+    #[serde(default)]
+    pub rust_version: Option<String>,
+    pub manifest_path: PathBuf,
+    #[serde(default)]
+    pub targets: Vec<Target>,
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+}
+
+/// A dependency as declared in a package's manifest.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Dependency {
+    pub name: String,
+    /// `dev` or `build`; `None` for normal dependencies.
+    #[serde(default)]
+    pub kind: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Target {
+    pub name: String,
+    pub kind: Vec<String>,
+}
+
+impl Metadata {
+    /// Packages that belong to the workspace, in metadata order.
+    pub fn members(&self) -> impl Iterator<Item = &Package> {
+        self.packages
+            .iter()
+            .filter(|pkg| self.workspace_members.contains(&pkg.id))
+    }
+
+    /// Names of the packages workspace members depend on directly, sorted and deduplicated.
+    /// Empty without a resolve graph.
+    pub fn direct_dependency_names(&self) -> Vec<String> {
+        let Some(resolve) = &self.resolve else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = resolve
+            .nodes
+            .iter()
+            .filter(|node| self.workspace_members.contains(&node.id))
+            .flat_map(|node| &node.dependencies)
+            .filter(|id| !self.workspace_members.contains(id))
+            .filter_map(|id| self.packages.iter().find(|pkg| &pkg.id == id))
+            .map(|pkg| pkg.name.clone())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+}
+
+pub fn parse_metadata(json: &str) -> Result<Metadata> {
+    serde_json::from_str(json).context("Failed to parse cargo metadata")
+}
+
+/// Runs `cargo metadata --no-deps` for the manifest in `project_dir`, once per process.
+pub fn cargo_metadata(project_dir: &Path) -> Result<Metadata> {
+    cached_metadata(project_dir, true)
+}
+
+/// Runs `cargo metadata` including the resolved dependency graph, once per process.
+pub fn cargo_metadata_with_deps(project_dir: &Path) -> Result<Metadata> {
+    cached_metadata(project_dir, false)
+}
+
+fn cached_metadata(project_dir: &Path, no_deps: bool) -> Result<Metadata> {
+    let dir = project_dir
+        .canonicalize()
+        .unwrap_or_else(|_| project_dir.to_path_buf());
+    let cache = CACHE.get_or_init(Default::default);
+    if let Some(metadata) = cache.lock().unwrap().get(&(dir.clone(), no_deps)) {
+        return Ok(metadata.clone());
+    }
+    let metadata = run_metadata(project_dir, no_deps)?;
+    cache
+        .lock()
+        .unwrap()
+        .insert((dir, no_deps), metadata.clone());
+    Ok(metadata)
+}
+
+fn run_metadata(project_dir: &Path, no_deps: bool) -> Result<Metadata> {
+    let mut cmd = Command::new(executor::cargo_bin());
+    cmd.args(["metadata", "--format-version", "1"]);
+    if no_deps {
+        cmd.arg("--no-deps");
+    }
+    let output = cmd
+        .arg("--manifest-path")
+        .arg(project_dir.join("Cargo.toml"))
+        .output()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo metadata"))?;
+    if !output.status.success() {
+        bail!(
+            "cargo metadata failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_metadata(&String::from_utf8_lossy(&output.stdout))
+}
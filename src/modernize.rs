@@ -0,0 +1,157 @@
+//! Flags dependencies that have std or lighter modern replacements.
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item};
+
+use crate::msrv::parse_rust_version;
+use crate::output;
+
+/// A dependency with a recommended replacement.
+#[derive(Debug, Clone, Copy)]
+pub struct Advisory {
+    pub crate_name: &'static str,
+    pub replacement: &'static str,
+    /// Minimum Rust version the replacement needs, when it lives in std.
+    pub min_rust: Option<&'static str>,
+}
+
+/// Curated replacement table. Add new entries here.
+pub const ADVISORIES: &[Advisory] = &[
+    Advisory {
+        crate_name: "lazy_static",
+        replacement: "std::sync::LazyLock",
+        min_rust: Some("1.80"),
+    },
+    Advisory {
+        crate_name: "once_cell",
+        replacement: "std::sync::OnceLock / std::sync::LazyLock",
+        min_rust: Some("1.80"),
+    },
+    Advisory {
+        crate_name: "atty",
+        replacement: "std::io::IsTerminal",
+        min_rust: Some("1.70"),
+    },
+    Advisory {
+        crate_name: "structopt",
+        replacement: "clap (derive feature)",
+        min_rust: None,
+    },
+    Advisory {
+        crate_name: "num_cpus",
+        replacement: "std::thread::available_parallelism",
+        min_rust: Some("1.59"),
+    },
+    Advisory {
+        crate_name: "memoffset",
+        replacement: "core::mem::offset_of!",
+        min_rust: Some("1.77"),
+    },
+    Advisory {
+        crate_name: "matches",
+        replacement: "the matches! macro",
+        min_rust: Some("1.42"),
+    },
+    Advisory {
+        crate_name: "tempdir",
+        replacement: "tempfile",
+        min_rust: None,
+    },
+    Advisory {
+        crate_name: "failure",
+        replacement: "thiserror / anyhow",
+        min_rust: None,
+    },
+    Advisory {
+        crate_name: "error-chain",
+        replacement: "thiserror / anyhow",
+        min_rust: None,
+    },
+];
+
+const DEPENDENCY_SECTIONS: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub dependency: String,
+    pub section: String,
+    pub replacement: String,
+    pub min_rust: Option<String>,
+    /// False when the declared `rust-version` is older than `min_rust`.
+    pub msrv_compatible: bool,
+}
+
+pub fn find_modernizations(manifest: &str) -> Result<Vec<Finding>> {
+    let manifest: DocumentMut = manifest.parse().context("Failed to parse Cargo.toml")?;
+    let declared = manifest
+        .get("package")
+        .and_then(|pkg| pkg.get("rust-version"))
+        .and_then(Item::as_str)
+        .and_then(parse_rust_version);
+
+    let mut findings = Vec::new();
+    for section in DEPENDENCY_SECTIONS {
+        let Some(deps) = manifest.get(section).and_then(Item::as_table_like) else {
+            continue;
+        };
+        for (name, _) in deps.iter() {
+            let Some(advisory) = ADVISORIES
+                .iter()
+                .find(|a| a.crate_name == name || a.crate_name.replace('_', "-") == name)
+            else {
+                continue;
+            };
+            let required = advisory.min_rust.and_then(parse_rust_version);
+            let msrv_compatible = match (&declared, &required) {
+                (Some(declared), Some(required)) => declared >= required,
+                _ => true,
+            };
+            findings.push(Finding {
+                dependency: name.to_string(),
+                section: section.to_string(),
+                replacement: advisory.replacement.to_string(),
+                min_rust: advisory.min_rust.map(str::to_string),
+                msrv_compatible,
+            });
+        }
+    }
+    Ok(findings)
+}
+
+pub fn check_modernize(project_dir: &Path, json: bool) -> Result<()> {
+    let manifest =
+        fs::read_to_string(project_dir.join("Cargo.toml")).context("Failed to read Cargo.toml")?;
+    let findings = find_modernizations(&manifest)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&findings)?);
+        return Ok(());
+    }
+
+    output::status("🔍 Looking for dependencies with modern replacements...");
+    if findings.is_empty() {
+        output::line("✅ No outdated dependency patterns found!".green());
+        return Ok(());
+    }
+    for finding in &findings {
+        let requirement = match &finding.min_rust {
+            Some(min) if finding.msrv_compatible => format!(" (Rust {min}+)"),
+            Some(min) => format!(" (needs Rust {min}+, above your rust-version)")
+                .yellow()
+                .to_string(),
+            None => String::new(),
+        };
+        output::line(format!(
+            "   • {} [{}] → {}{}",
+            finding.dependency.bold(),
+            finding.section,
+            finding.replacement.green(),
+            requirement
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+//! Checks dependencies against the declared minimum supported Rust version.
+
+use anyhow::{bail, Result};
+use colored::*;
+use semver::Version;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use crate::metadata::{self, Metadata};
+use crate::output;
+
+/// Parses a `rust-version` such as `1.70` or `1.70.1`, padding missing components.
+pub fn parse_rust_version(version: &str) -> Option<Version> {
+    let mut parts: Vec<&str> = version.trim().split('.').collect();
+    while parts.len() < 3 {
+        parts.push("0");
+    }
+    Version::parse(&parts.join(".")).ok()
+}
+
+/// A dependency that needs a newer compiler than the declared MSRV.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MsrvViolation {
+    pub name: String,
+    pub version: String,
+    pub rust_version: String,
+    /// Package names from a workspace member down to the offending package.
+    pub path: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct MsrvReport {
+    pub declared: String,
+    pub violations: Vec<MsrvViolation>,
+    /// Dependencies that don't declare a `rust-version`.
+    pub unverifiable: usize,
+}
+
+/// Compares every non-workspace package against the lowest `rust-version` declared by
+/// a workspace member.
+pub fn check_metadata(metadata: &Metadata) -> Result<MsrvReport> {
+    let Some(declared) = metadata
+        .members()
+        .filter_map(|pkg| pkg.rust_version.as_deref())
+        .min_by_key(|v| parse_rust_version(v))
+    else {
+        bail!("No `rust-version` declared in Cargo.toml");
+    };
+    let declared_version = parse_rust_version(declared);
+
+    // Breadth-first parents give the shortest path from a member to each package.
+    let graph: HashMap<&str, &[String]> = metadata
+        .resolve
+        .as_ref()
+        .map(|resolve| {
+            resolve
+                .nodes
+                .iter()
+                .map(|node| (node.id.as_str(), node.dependencies.as_slice()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut parent: HashMap<&str, Option<&str>> = HashMap::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    for member in &metadata.workspace_members {
+        parent.insert(member, None);
+        queue.push_back(member);
+    }
+    while let Some(id) = queue.pop_front() {
+        for dep in graph.get(id).copied().unwrap_or_default() {
+            if !parent.contains_key(dep.as_str()) {
+                parent.insert(dep, Some(id));
+                queue.push_back(dep);
+            }
+        }
+    }
+    let names: HashMap<&str, &str> = metadata
+        .packages
+        .iter()
+        .map(|pkg| (pkg.id.as_str(), pkg.name.as_str()))
+        .collect();
+
+    let mut report = MsrvReport {
+        declared: declared.to_string(),
+        ..Default::default()
+    };
+    for pkg in &metadata.packages {
+        if metadata.workspace_members.contains(&pkg.id) {
+            continue;
+        }
+        let Some(required) = pkg.rust_version.as_deref() else {
+            report.unverifiable += 1;
+            continue;
+        };
+        if parse_rust_version(required) <= declared_version {
+            continue;
+        }
+
+        let mut path = Vec::new();
+        let mut current = Some(pkg.id.as_str());
+        while let Some(id) = current {
+            path.push(names.get(id).copied().unwrap_or(id).to_string());
+            current = parent.get(id).copied().flatten();
+        }
+        path.reverse();
+        report.violations.push(MsrvViolation {
+            name: pkg.name.clone(),
+            version: pkg.version.clone(),
+            rust_version: required.to_string(),
+            path,
+        });
+    }
+    report
+        .violations
+        .sort_by_key(|v| std::cmp::Reverse(parse_rust_version(&v.rust_version)));
+    Ok(report)
+}
+
+/// Prints the MSRV report and returns whether every verifiable dependency is compatible.
+pub fn msrv_check(project_dir: &Path) -> Result<bool> {
+    let metadata = metadata::cargo_metadata_with_deps(project_dir)?;
+    let report = check_metadata(&metadata)?;
+
+    output::line(format!(
+        "🦀 Declared rust-version: {}",
+        report.declared.bold()
+    ));
+    if report.violations.is_empty() {
+        output::line("✅ All dependencies support the declared MSRV.".green());
+    } else {
+        output::line("🚨 Dependencies requiring a newer compiler:".red());
+        for violation in &report.violations {
+            output::line(format!(
+                "   • {} {} needs Rust {}",
+                violation.name.bold(),
+                violation.version,
+                violation.rust_version.red()
+            ));
+            output::line(format!(
+                "       via {}",
+                violation.path.join(" → ").dimmed()
+            ));
+        }
+    }
+    if report.unverifiable > 0 {
+        output::line(
+            format!(
+                "ℹ️  {} package(s) declare no rust-version and could not be verified.",
+                report.unverifiable
+            )
+            .dimmed(),
+        );
+    }
+    Ok(report.violations.is_empty())
+}
@@ -0,0 +1,131 @@
+//! How cargo-sleek prints its own messages: decorated with emoji and color, plain for
+//! CI logs, or quiet. Cargo's output is never touched.
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Emoji and color, for interactive terminals.
+    Decorated,
+    /// No emoji or color; status lines start with `error:`, `warning:`, `ok:` or `info:`.
+    Plain,
+    /// Like [`OutputMode::Plain`], with progress banners dropped so mostly cargo's own
+    /// output remains.
+    Quiet,
+}
+
+static MODE: AtomicU8 = AtomicU8::new(0);
+static TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Picks the mode from `--quiet`, `--plain`, the `CI` variable and whether stdout is a
+/// terminal. Anything but an interactive, non-CI terminal gets plain output.
+pub fn detect(quiet: bool, plain: bool, ci: bool, terminal: bool) -> OutputMode {
+    if quiet {
+        OutputMode::Quiet
+    } else if plain || ci || !terminal {
+        OutputMode::Plain
+    } else {
+        OutputMode::Decorated
+    }
+}
+
+/// Sets the mode for the rest of the process; plain and quiet also turn color off.
+pub fn set_mode(mode: OutputMode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+    if mode != OutputMode::Decorated {
+        colored::control::set_override(false);
+    }
+}
+
+pub fn mode() -> OutputMode {
+    match MODE.load(Ordering::Relaxed) {
+        1 => OutputMode::Plain,
+        2 => OutputMode::Quiet,
+        _ => OutputMode::Decorated,
+    }
+}
+
+/// Emoji and the invisible characters that join or restyle them.
+fn is_emoji(c: char) -> bool {
+    matches!(c,
+        '\u{1F000}'..='\u{1FAFF}'
+        | '\u{2300}'..='\u{23FF}'
+        | '\u{2600}'..='\u{27BF}'
+        | '\u{2B00}'..='\u{2BFF}'
+        | '\u{2139}'
+        | '\u{25B6}'
+        | '\u{25C0}'
+        | '\u{FE0F}'
+        | '\u{200D}')
+}
+
+/// Prefix replacing the emoji a status line starts with.
+fn plain_prefix(emoji: char) -> &'static str {
+    match emoji {
+        '❌' | '🚨' => "error: ",
+        '⚠' => "warning: ",
+        '✅' => "ok: ",
+        _ => "info: ",
+    }
+}
+
+/// `text` without emoji. A line that starts with one (after any indentation) gets a
+/// prefix instead, e.g. `❌ Build failed` becomes `error: Build failed`.
+pub fn plain(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            let body = line.trim_start();
+            let indent = &line[..line.len() - body.len()];
+            let prefix = body
+                .chars()
+                .next()
+                .filter(|c| is_emoji(*c))
+                .map(plain_prefix)
+                .unwrap_or("");
+            let rest: String = body.chars().filter(|c| !is_emoji(*c)).collect();
+            let rest = if prefix.is_empty() {
+                rest.as_str()
+            } else {
+                rest.trim_start()
+            };
+            format!("{indent}{prefix}{rest}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prints every message to stderr from now on, leaving stdout to machine-readable
+/// output such as `watch --json`.
+pub fn use_stderr() {
+    TO_STDERR.store(true, Ordering::Relaxed);
+}
+
+/// `text` as the current mode prints it.
+pub fn render(text: &str) -> String {
+    match mode() {
+        OutputMode::Decorated => text.to_string(),
+        OutputMode::Plain | OutputMode::Quiet => plain(text),
+    }
+}
+
+/// Prints a result: a report heading, table row or outcome.
+pub fn line(text: impl Display) {
+    if TO_STDERR.load(Ordering::Relaxed) {
+        eprintln!("{}", render(&text.to_string()));
+    } else {
+        println!("{}", render(&text.to_string()));
+    }
+}
+
+/// Prints a progress banner such as `🚀 Running Cargo command`; dropped with `--quiet`.
+pub fn status(text: impl Display) {
+    if mode() != OutputMode::Quiet {
+        line(text);
+    }
+}
+
+/// Prints a warning to stderr, keeping stdout clean for `--json` and the like.
+pub fn warning(text: impl Display) {
+    eprintln!("{}", render(&text.to_string()));
+}
@@ -0,0 +1,1225 @@
+//! Build performance measurement.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::executor;
+use crate::history::{self, BuildRecord, BuildSlice, CrateTime};
+use crate::linker;
+use crate::memory;
+use crate::metadata::{self, Metadata, Target};
+use crate::output;
+use crate::prompt;
+use crate::size;
+use crate::timings::{self, CrateTiming};
+use crate::toolchain;
+
+/// Paths of the executables cargo produces for the workspace's bin targets in `profile_dir`
+/// (e.g. `debug`, `release`).
+pub fn binary_paths(metadata: &Metadata, profile_dir: &str) -> Vec<PathBuf> {
+    let out_dir = metadata.target_directory.join(profile_dir);
+    metadata
+        .members()
+        .flat_map(|pkg| &pkg.targets)
+        .filter(|target| target.kind.iter().any(|kind| kind == "bin"))
+        .map(|target| out_dir.join(format!("{}{}", target.name, std::env::consts::EXE_SUFFIX)))
+        .collect()
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.50 MB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.2} {}", size, UNITS[unit])
+    }
+}
+
+/// How `build-time` invokes cargo.
+#[derive(Debug, Default, Clone)]
+pub struct BuildOptions {
+    /// Build with `--release`.
+    pub release: bool,
+    /// Custom cargo profile, passed as `--profile <name>`.
+    pub profile: Option<String>,
+    /// Run `cargo check` instead of a full build.
+    pub check: bool,
+    /// Run `cargo doc` instead of a full build.
+    pub doc: bool,
+    /// Document only the workspace members, passed as `--no-deps`.
+    pub no_deps: bool,
+    /// Target triple to cross-compile for, passed as `--target`.
+    pub target: Option<String>,
+    /// Features to enable, passed as `--features`.
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+    /// Request `timing-info` JSON messages; needs a nightly cargo.
+    pub timings_json: bool,
+    /// Slowest crates to list; `None` lists all of them.
+    pub top: Option<usize>,
+    /// Print the measurement as JSON instead of a report.
+    pub json: bool,
+    /// Save this run as a named baseline.
+    pub baseline: Option<String>,
+    /// Compare this run with a previously saved baseline.
+    pub compare: Option<String>,
+    /// Run `cargo clean` first to measure a from-scratch build.
+    pub cold: bool,
+    /// Measure a cold build, then an incremental rebuild after touching the entry point.
+    pub compare_warm: bool,
+    /// Skip the confirmation before `cargo clean`.
+    pub yes: bool,
+    /// Peak memory, in bytes, above which the build is reported as over the limit.
+    pub mem_limit: Option<u64>,
+    /// Build time above which the build fails its budget.
+    pub max_duration: Option<Duration>,
+    /// Slowdown, in percent, over the baseline or recent median that fails the budget.
+    pub max_regression: Option<f64>,
+    /// Rustup toolchain to build with, through `rustup run`.
+    pub toolchain: Option<String>,
+}
+
+impl BuildOptions {
+    /// Arguments passed to cargo for the measured build.
+    pub fn cargo_args(&self) -> Vec<String> {
+        let command = if self.doc {
+            "doc"
+        } else if self.check {
+            "check"
+        } else {
+            "build"
+        };
+        let mut args: Vec<String> = [command, "--message-format=json-render-diagnostics"]
+            .map(String::from)
+            .into();
+        if self.timings_json {
+            // Section timings break each unit into frontend, codegen and link phases.
+            args.extend(
+                [
+                    "--timings=html,json",
+                    "-Zunstable-options",
+                    "-Zsection-timings",
+                ]
+                .map(String::from),
+            );
+        } else {
+            args.push("--timings".to_string());
+        }
+        if self.no_deps {
+            args.push("--no-deps".to_string());
+        }
+        if self.release {
+            args.push("--release".to_string());
+        }
+        if let Some(profile) = &self.profile {
+            args.extend(["--profile".to_string(), profile.clone()]);
+        }
+        if let Some(target) = &self.target {
+            args.extend(["--target".to_string(), target.clone()]);
+        }
+        if !self.features.is_empty() {
+            args.extend(["--features".to_string(), self.features.join(",")]);
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+        args
+    }
+
+    /// Cargo invocation for the measured build, through `rustup run` for a toolchain.
+    pub fn command(&self) -> Command {
+        let mut cmd = match &self.toolchain {
+            Some(toolchain) => {
+                let mut cmd = Command::new("rustup");
+                cmd.args(["run", toolchain, "cargo"]);
+                cmd
+            }
+            None => Command::new(executor::cargo_bin()),
+        };
+        cmd.args(self.cargo_args());
+        cmd
+    }
+
+    /// Profile the build uses, `None` for the default dev profile.
+    pub fn profile_name(&self) -> Option<&str> {
+        self.profile
+            .as_deref()
+            .or(self.release.then_some("release"))
+    }
+
+    /// Directory the build writes into: cross builds nest under the triple, as in
+    /// `target/<triple>/release`.
+    pub fn output_dir(&self, target_dir: &Path) -> PathBuf {
+        let base = match &self.target {
+            Some(triple) => target_dir.join(triple),
+            None => target_dir.to_path_buf(),
+        };
+        profile_target_dir(&base, self.profile_name().unwrap_or("dev"))
+    }
+}
+
+/// Checks that `triple` looks like a target triple such as `aarch64-unknown-linux-gnu`:
+/// two to five dash-separated lowercase parts, starting with the architecture.
+pub fn validate_target_triple(triple: &str) -> Result<String, String> {
+    let parts: Vec<&str> = triple.split('-').collect();
+    let plausible = (2..=5).contains(&parts.len())
+        && parts.iter().all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.')
+        })
+        && parts[0].starts_with(|c: char| c.is_ascii_lowercase());
+    if plausible {
+        Ok(triple.to_string())
+    } else {
+        Err(format!(
+            "`{triple}` does not look like a target triple (e.g. aarch64-unknown-linux-gnu); \
+             see `rustc --print target-list`"
+        ))
+    }
+}
+
+/// One feature combination compared by `build-time --matrix`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureSet {
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+}
+
+impl FeatureSet {
+    /// Parses `default` (the default features), `none` (`--no-default-features`) or a
+    /// comma-separated feature list, which may include `none` to drop the defaults.
+    pub fn parse(spec: &str) -> FeatureSet {
+        let mut set = FeatureSet::default();
+        for feature in spec.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            match feature {
+                "none" => set.no_default_features = true,
+                "default" => {}
+                feature => set.features.push(feature.to_string()),
+            }
+        }
+        set
+    }
+
+    /// Label used in the comparison table and the history, the inverse of [`FeatureSet::parse`].
+    pub fn label(&self) -> String {
+        match (self.no_default_features, self.features.is_empty()) {
+            (false, true) => "default".to_string(),
+            (true, true) => "none".to_string(),
+            (false, false) => self.features.join(","),
+            (true, false) => format!("none,{}", self.features.join(",")),
+        }
+    }
+}
+
+/// Output directory cargo uses for `profile`: `dev` and `test` build into `debug`,
+/// `bench` into `release`, custom profiles into a directory of the same name.
+pub fn profile_target_dir(target_dir: &Path, profile: &str) -> PathBuf {
+    let dir = match profile {
+        "dev" | "test" => "debug",
+        "bench" => "release",
+        other => other,
+    };
+    target_dir.join(dir)
+}
+
+/// A `compiler-artifact` message from `cargo build --message-format=json`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Artifact {
+    pub package_id: String,
+    pub target: Target,
+    #[serde(default)]
+    pub filenames: Vec<PathBuf>,
+    #[serde(default)]
+    pub executable: Option<PathBuf>,
+}
+
+impl Artifact {
+    /// Final outputs of the artifact: the executable for bins, the library files otherwise.
+    pub fn outputs(&self) -> Vec<&Path> {
+        match &self.executable {
+            Some(exe) => vec![exe.as_path()],
+            None => self
+                .filenames
+                .iter()
+                .filter(|f| !matches!(f.extension().and_then(|e| e.to_str()), Some("rmeta" | "d")))
+                .map(PathBuf::as_path)
+                .collect(),
+        }
+    }
+}
+
+/// Extracts the artifact messages from cargo's JSON message stream, ignoring other lines.
+pub fn parse_artifacts(output: &str) -> Vec<Artifact> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|msg| msg["reason"] == "compiler-artifact")
+        .filter_map(|msg| serde_json::from_value(msg).ok())
+        .collect()
+}
+
+/// Total size of the files under `path`, recursively. Missing paths count as empty.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// The directory the build wrote into, e.g. `target/debug` or `target/<triple>/release`.
+///
+/// Taken from the artifact paths so custom `--target-dir`s and cross-compile triples are
+/// honoured; artifacts live either directly in it or in its `deps/` subdirectory.
+pub fn profile_dir(artifacts: &[Artifact]) -> Option<PathBuf> {
+    let output = artifacts.iter().flat_map(Artifact::outputs).next()?;
+    let parent = output.parent()?;
+    if parent.file_name().is_some_and(|name| name == "deps") {
+        parent.parent().map(Path::to_path_buf)
+    } else {
+        Some(parent.to_path_buf())
+    }
+}
+
+fn report_artifact_sizes(artifacts: &[Artifact], metadata: &Metadata, options: &BuildOptions) {
+    let members: Vec<Artifact> = artifacts
+        .iter()
+        .filter(|a| metadata.workspace_members.contains(&a.package_id))
+        .cloned()
+        .collect();
+    for artifact in &members {
+        for output in artifact.outputs() {
+            if let Ok(meta) = fs::metadata(output) {
+                let name = output.file_name().unwrap_or_default().to_string_lossy();
+                output::line(format!("🔹 {}: {}", name, format_size(meta.len())));
+            }
+        }
+    }
+    let dir = if options.profile_name().is_some() || options.target.is_some() {
+        let dir = options.output_dir(&metadata.target_directory);
+        if !dir.is_dir() {
+            output::line(
+                format!(
+                    "⚠️  Profile directory {} was not created by the build",
+                    dir.display()
+                )
+                .yellow(),
+            );
+        }
+        Some(dir).filter(|dir| dir.is_dir())
+    } else {
+        profile_dir(&members).or_else(|| profile_dir(artifacts))
+    };
+    if let Some(dir) = dir {
+        output::line(format!(
+            "📦 Profile output ({}): {}",
+            dir.display(),
+            format_size(dir_size(&dir))
+        ));
+    }
+    output::line(format!(
+        "🗄️  Total target directory: {}",
+        format_size(dir_size(&metadata.target_directory))
+    ));
+}
+
+/// Result of one successful measured `cargo build`.
+struct Measurement {
+    duration: Duration,
+    stdout: String,
+    crates: Vec<CrateTiming>,
+    /// Seconds spent linking, when cargo recorded section timings.
+    link: Option<f64>,
+    /// Peak resident memory of cargo and the compilers it spawned.
+    peak_rss: Option<u64>,
+}
+
+/// Runs and measures the build. The inner error is cargo's exit code when the build failed.
+fn run_build(
+    options: &BuildOptions,
+    target_dir: &Path,
+) -> Result<std::result::Result<Measurement, i32>> {
+    let start = Instant::now();
+    let child = options
+        .command()
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo build --timings"))?;
+    let sampler = memory::PeakSampler::start(child.id());
+    let output = child
+        .wait_with_output()
+        .context("Failed to wait for cargo build")?;
+    let duration = start.elapsed();
+    let peak_rss = sampler.finish();
+    if !output.status.success() {
+        return Ok(Err(executor::exit_code(output.status)));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let mut units = timings::parse_timing_messages(&stdout);
+    if units.is_empty() {
+        units = timings::load_timing_report(target_dir).unwrap_or_default();
+    }
+    Ok(Ok(Measurement {
+        duration,
+        stdout,
+        crates: timings::crate_timings(&units),
+        link: timings::link_seconds(&units),
+        peak_rss,
+    }))
+}
+
+/// Error for a failed measured build, so `main` exits with cargo's `code`.
+fn build_failed(code: i32, message: impl Into<String>) -> anyhow::Error {
+    executor::CargoFailed {
+        message: message.into(),
+        code,
+    }
+    .into()
+}
+
+/// The feature set of `options` as recorded in the history; `None` for the defaults.
+fn features_label(options: &BuildOptions) -> Option<String> {
+    (!options.features.is_empty() || options.no_default_features).then(|| {
+        FeatureSet {
+            features: options.features.clone(),
+            no_default_features: options.no_default_features,
+        }
+        .label()
+    })
+}
+
+/// The history runs a build with `options` and `kind` is compared with.
+fn history_slice(options: &BuildOptions, kind: Option<&str>) -> BuildSlice {
+    BuildSlice {
+        profile: options.profile_name().map(String::from),
+        kind: kind.map(String::from),
+        features: features_label(options),
+        target: options.target.clone(),
+    }
+}
+
+fn build_record(
+    options: &BuildOptions,
+    measurement: &Measurement,
+    kind: Option<&str>,
+) -> BuildRecord {
+    BuildRecord {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+        duration_ms: measurement.duration.as_millis() as u64,
+        profile: options.profile_name().map(String::from),
+        crate_count: measurement.crates.len(),
+        top_crates: measurement
+            .crates
+            .iter()
+            .take(history::TOP_CRATES)
+            .map(|c| CrateTime {
+                name: c.name.clone(),
+                seconds: c.seconds,
+            })
+            .collect(),
+        commit: history::git_commit(),
+        kind: kind.map(String::from),
+        peak_rss_bytes: measurement.peak_rss,
+        features: features_label(options),
+        toolchain: options.toolchain.clone(),
+        target: options.target.clone(),
+        recompiled: Some(measurement.crates.iter().map(|c| c.name.clone()).collect()),
+    }
+}
+
+/// Runs `cargo clean` after confirmation. Returns `false` if the user declined.
+fn clean_target(assume_yes: bool) -> Result<bool> {
+    if !prompt::confirm(
+        "🧹 A cold build runs `cargo clean` first, deleting the target directory. Continue?",
+        assume_yes,
+    )? {
+        return Ok(false);
+    }
+    cargo_clean()?;
+    Ok(true)
+}
+
+/// Runs `cargo clean` without tracking it.
+pub fn cargo_clean() -> Result<()> {
+    let status = Command::new(executor::cargo_bin())
+        .arg("clean")
+        .status()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo clean"))?;
+    if !status.success() {
+        bail!("cargo clean failed");
+    }
+    Ok(())
+}
+
+/// A file whose modification time was bumped to now. The original time is restored when
+/// it is dropped, so measurements leave the tree as they found it.
+#[derive(Debug)]
+pub struct TouchedFile {
+    pub path: PathBuf,
+    original: SystemTime,
+}
+
+fn set_modified(path: &Path, time: SystemTime) -> std::io::Result<()> {
+    fs::File::options()
+        .append(true)
+        .open(path)
+        .and_then(|file| file.set_modified(time))
+}
+
+impl TouchedFile {
+    pub fn touch(path: &Path) -> Result<TouchedFile> {
+        let original = fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        set_modified(path, SystemTime::now())
+            .with_context(|| format!("Failed to touch {}", path.display()))?;
+        Ok(TouchedFile {
+            path: path.to_path_buf(),
+            original,
+        })
+    }
+}
+
+impl Drop for TouchedFile {
+    fn drop(&mut self) {
+        let _ = set_modified(&self.path, self.original);
+    }
+}
+
+/// Bumps the modification time of the first workspace member's `src/lib.rs` or
+/// `src/main.rs`, forcing an incremental rebuild of that crate.
+pub fn touch_entry_point(metadata: &Metadata) -> Result<TouchedFile> {
+    let entry = metadata
+        .members()
+        .filter_map(|pkg| pkg.manifest_path.parent())
+        .flat_map(|dir| [dir.join("src/lib.rs"), dir.join("src/main.rs")])
+        .find(|path| path.is_file())
+        .context("No src/lib.rs or src/main.rs found to touch")?;
+    TouchedFile::touch(&entry)
+}
+
+/// Slowest crates listed when a build exceeds its time budget.
+pub const BUDGET_TOP_CRATES: usize = 3;
+
+/// Warns on stderr, keeping `--json` output clean, when `peak` exceeds `limit`.
+/// Returns whether the build stayed within it.
+fn check_mem_limit(peak: Option<u64>, limit: Option<u64>) -> bool {
+    match (peak, limit) {
+        (Some(peak), Some(limit)) if peak > limit => {
+            output::warning(
+                format!(
+                    "⚠️  Peak memory {} exceeds the limit of {}",
+                    format_size(peak),
+                    format_size(limit)
+                )
+                .red(),
+            );
+            false
+        }
+        _ => true,
+    }
+}
+
+/// Parses a build time budget such as `4m30s`, `90s`, `1h` or a plain number of seconds.
+pub fn parse_budget(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration `{text}`, expected e.g. 4m30s or 90s");
+    let trimmed = text.trim();
+    // `inf`, `NaN`, negative and overflowing values are rejected rather than panicking.
+    let seconds = |value: f64| {
+        if value.is_finite() && value >= 0.0 {
+            Duration::try_from_secs_f64(value).map_err(|_| invalid())
+        } else {
+            Err(invalid())
+        }
+    };
+    if let Ok(value) = trimmed.parse::<f64>() {
+        return seconds(value);
+    }
+    let mut total = 0.0;
+    let mut number = String::new();
+    for c in trimmed.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            _ => return Err(invalid()),
+        };
+        let value: f64 = number.parse().map_err(|_| invalid())?;
+        total += value * unit;
+        number.clear();
+    }
+    if trimmed.is_empty() {
+        return Err(invalid());
+    }
+    // A trailing bare number counts as seconds, as in `4m30`.
+    if !number.is_empty() {
+        total += number.parse::<f64>().map_err(|_| invalid())?;
+    }
+    seconds(total)
+}
+
+/// Parses a percentage such as `15%` or `15`. `inf`, `NaN` and negative values are
+/// rejected, as `parse_budget` does, instead of yielding a limit that never fires.
+pub fn parse_percent(text: &str) -> Result<f64, String> {
+    text.trim()
+        .trim_end_matches('%')
+        .parse::<f64>()
+        .ok()
+        .filter(|percent| percent.is_finite() && *percent >= 0.0)
+        .ok_or_else(|| format!("invalid percentage `{text}`, expected e.g. 15%"))
+}
+
+/// A build time budget that a measured build exceeded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetViolation {
+    pub measured_ms: u64,
+    pub limit_ms: u64,
+    /// Where the limit comes from, e.g. `--max-regression 15% over the recent median`.
+    pub source: String,
+}
+
+/// Checks `measured_ms` against an absolute budget and a regression limit relative to
+/// `reference`, a labelled earlier duration such as the recent median.
+pub fn check_budget(
+    measured_ms: u64,
+    max: Option<Duration>,
+    max_regression: Option<f64>,
+    reference: Option<(&str, u64)>,
+) -> Vec<BudgetViolation> {
+    let mut violations = Vec::new();
+    if let Some(max) = max {
+        let limit_ms = max.as_millis() as u64;
+        if measured_ms > limit_ms {
+            violations.push(BudgetViolation {
+                measured_ms,
+                limit_ms,
+                source: "--max".to_string(),
+            });
+        }
+    }
+    if let (Some(percent), Some((label, reference_ms))) = (max_regression, reference) {
+        if history::slowdown_percent(measured_ms, reference_ms) > percent {
+            violations.push(BudgetViolation {
+                measured_ms,
+                limit_ms: (reference_ms as f64 * (1.0 + percent / 100.0)).round() as u64,
+                source: format!("--max-regression {}% over {}", percent, label),
+            });
+        }
+    }
+    violations
+}
+
+/// Explains budget failures on stderr, with the slowest crates as a starting point.
+fn report_budget(violations: &[BudgetViolation], crates: &[CrateTiming]) {
+    for violation in violations {
+        output::warning(
+            format!(
+                "❌ Build took {:.2}s, over the limit of {:.2}s ({})",
+                violation.measured_ms as f64 / 1000.0,
+                violation.limit_ms as f64 / 1000.0,
+                violation.source
+            )
+            .red(),
+        );
+    }
+    if !violations.is_empty() && !crates.is_empty() {
+        let slowest: Vec<String> = crates
+            .iter()
+            .take(BUDGET_TOP_CRATES)
+            .map(|c| format!("{} ({:.2}s)", c.name, c.seconds))
+            .collect();
+        output::warning(format!("   Slowest crates: {}", slowest.join(", ")));
+    }
+}
+
+/// Measures a build and reports on it. Returns `false` when the build exceeded a
+/// configured limit such as `--mem-limit` or `--max`, and an error when it failed or
+/// the cold build was cancelled.
+pub fn analyze_build_time(
+    history_path: &Path,
+    options: &BuildOptions,
+    verbose: bool,
+) -> Result<bool> {
+    if !options.json {
+        output::status("📊 Analyzing build performance...\n");
+    }
+    let options = &BuildOptions {
+        timings_json: executor::cargo_is_nightly(),
+        ..options.clone()
+    };
+    let cold = options.cold || options.compare_warm;
+    if cold && !clean_target(options.yes)? {
+        bail!("Cold build cancelled");
+    }
+    let kind = cold.then_some("cold");
+
+    let metadata = metadata::cargo_metadata_with_deps(Path::new("."))?;
+    let measurement = run_build(options, &metadata.target_directory)?
+        .map_err(|code| build_failed(code, "Build failed. Check logs for details."))?;
+    let crates = &measurement.crates;
+    let duration = measurement.duration;
+
+    let record = build_record(options, &measurement, kind);
+    let mut history = history::load_history(history_path);
+    let median_ms = history.median_ms(&BuildSlice::of(&record));
+
+    let shown = options.top.unwrap_or(crates.len()).min(crates.len());
+    let mut report = serde_json::json!({
+        "duration_ms": record.duration_ms,
+        "kind": kind,
+        "median_ms": median_ms,
+        "commit": record.commit,
+        "crates": &crates[..shown],
+        "link_secs": measurement.link,
+        "peak_rss_bytes": measurement.peak_rss,
+    });
+    let mut within_limits = check_mem_limit(measurement.peak_rss, options.mem_limit);
+    let reference = match &options.compare {
+        Some(name) => history
+            .baselines
+            .get(name)
+            .map(|baseline| (format!("baseline `{}`", name), baseline.duration_ms)),
+        None => median_ms.map(|median| ("the recent median".to_string(), median)),
+    };
+    if options.max_regression.is_some() && reference.is_none() {
+        output::warning(
+            "⚠️  No baseline or build history to compare with; skipping --max-regression".yellow(),
+        );
+    }
+    let violations = check_budget(
+        record.duration_ms,
+        options.max_duration,
+        options.max_regression,
+        reference.as_ref().map(|(label, ms)| (label.as_str(), *ms)),
+    );
+    report_budget(&violations, crates);
+    within_limits &= violations.is_empty();
+    if !options.json {
+        output::line(format!("🚀 Build completed in {:.2?}", duration));
+        if let Some(peak) = measurement.peak_rss {
+            output::line(format!("🧠 Peak memory: {}", format_size(peak)));
+        }
+        let artifacts = parse_artifacts(&measurement.stdout);
+        report_artifact_sizes(&artifacts, &metadata, options);
+        output::line("");
+        timings::show_crate_timings(crates, options.top)?;
+        timings::show_heaviest_deps(crates, &metadata.direct_dependency_names(), options.top);
+        output::line("");
+        linker::report_link_time(measurement.link, duration.as_secs_f64(), Path::new("."));
+        if verbose {
+            output::line("🕓 Timing report saved in `target/cargo-timings/`");
+        }
+        history::report_regression(&record, median_ms);
+        if let Some(name) = &options.compare {
+            history::compare_with_baseline(&history, name, &record)?;
+        }
+    }
+
+    if let Some(name) = &options.baseline {
+        history.baselines.insert(name.clone(), record.clone());
+        if !options.json {
+            output::line(format!("📌 Saved this run as baseline `{}`", name));
+        }
+    }
+    history.record(record);
+    if options.release {
+        let binaries: Vec<(String, PathBuf)> = parse_artifacts(&measurement.stdout)
+            .into_iter()
+            .filter(|artifact| metadata.workspace_members.contains(&artifact.package_id))
+            .filter_map(|artifact| Some((artifact.target.name, artifact.executable?)))
+            .collect();
+        history.sizes.extend(size::size_records(&binaries));
+    }
+
+    if options.compare_warm {
+        let touched = touch_entry_point(&metadata)?;
+        if !options.json {
+            output::status(format!(
+                "\n👆 Touched {}, rebuilding...",
+                touched.path.display()
+            ));
+        }
+        let warm = match run_build(options, &metadata.target_directory)? {
+            Ok(warm) => warm,
+            Err(code) => {
+                history::save_history(history_path, &history)?;
+                return Err(build_failed(
+                    code,
+                    "Incremental rebuild failed. Check logs for details.",
+                ));
+            }
+        };
+        let ratio = duration.as_secs_f64() / warm.duration.as_secs_f64().max(f64::EPSILON);
+        report["warm_ms"] = (warm.duration.as_millis() as u64).into();
+        report["ratio"] = ratio.into();
+        report["warm_link_secs"] = warm.link.into();
+        report["warm_peak_rss_bytes"] = warm.peak_rss.into();
+        within_limits &= check_mem_limit(warm.peak_rss, options.mem_limit);
+        if !options.json {
+            output::line(format!("🧊 Cold build:          {:.2?}", duration));
+            output::line(format!("🔥 Incremental rebuild: {:.2?}", warm.duration));
+            output::line(format!("⚡ Incremental is {:.1}x faster", ratio));
+            linker::report_link_time(warm.link, warm.duration.as_secs_f64(), Path::new("."));
+        }
+        history.record(build_record(options, &warm, Some("warm")));
+    }
+    history::save_history(history_path, &history)?;
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    }
+    Ok(within_limits)
+}
+
+/// Combined size of the workspace members' final artifacts.
+fn member_artifact_bytes(artifacts: &[Artifact], metadata: &Metadata) -> u64 {
+    artifacts
+        .iter()
+        .filter(|a| metadata.workspace_members.contains(&a.package_id))
+        .flat_map(Artifact::outputs)
+        .filter_map(|output| fs::metadata(output).ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// Times a clean build (or `cargo check` with `base.check`) for each feature set and
+/// prints a comparison table. Runs are recorded in the history tagged with their set.
+pub fn feature_matrix(history_path: &Path, base: &BuildOptions, sets: &[FeatureSet]) -> Result<()> {
+    if sets.is_empty() {
+        bail!(
+            "No feature sets to compare.\n   \
+             Pass them as `--matrix default --matrix serde,tls` or set \
+             `matrix` under [build-time] in .sleek.toml"
+        );
+    }
+    let mode = if base.check { "check" } else { "build" };
+    output::status(format!(
+        "🧪 Comparing {} feature set(s) with clean `cargo {}` runs...",
+        sets.len(),
+        mode
+    ));
+    if !prompt::confirm(
+        "🧹 Each run starts with `cargo clean`, deleting the target directory. Continue?",
+        base.yes,
+    )? {
+        bail!("Matrix cancelled");
+    }
+
+    let metadata = metadata::cargo_metadata(Path::new("."))?;
+    let kind = if base.check { "matrix-check" } else { "matrix" };
+    let mut history = history::load_history(history_path);
+    let mut rows: Vec<(String, Option<Duration>, Option<u64>)> = Vec::new();
+    let mut worst = 0;
+    for set in sets {
+        let label = set.label();
+        output::status(format!("\n▶️  {}", label.bold()));
+        let options = BuildOptions {
+            features: set.features.clone(),
+            no_default_features: set.no_default_features,
+            ..base.clone()
+        };
+        cargo_clean()?;
+        let measurement = match run_build(&options, &metadata.target_directory)? {
+            Ok(measurement) => measurement,
+            Err(code) => {
+                output::line(format!("❌ Build failed for `{}`", label).red());
+                worst = worst.max(code);
+                rows.push((label, None, None));
+                continue;
+            }
+        };
+        let bytes = (!base.check)
+            .then(|| member_artifact_bytes(&parse_artifacts(&measurement.stdout), &metadata));
+        history.record(build_record(&options, &measurement, Some(kind)));
+        rows.push((label, Some(measurement.duration), bytes));
+    }
+    history::save_history(history_path, &history)?;
+
+    let reference = rows.iter().find_map(|(_, duration, _)| *duration);
+    output::line(format!("\n{}", "📊 Feature matrix:".bold().cyan()));
+    output::line(format!(
+        "{:<30} {:>12} {:>10} {:>12}",
+        "Features", "Duration", "vs first", "Artifacts"
+    ));
+    for (label, duration, bytes) in &rows {
+        let Some(duration) = duration else {
+            output::line(format!("{:<30} {:>12}", label, "failed".red()));
+            continue;
+        };
+        let change = reference
+            .map(|reference| {
+                history::slowdown_percent(duration.as_millis() as u64, reference.as_millis() as u64)
+            })
+            .map(|percent| format!("{:+.1}%", percent))
+            .unwrap_or_default();
+        output::line(format!(
+            "{:<30} {:>12} {:>10} {:>12}",
+            label,
+            format!("{:.2?}", duration),
+            change,
+            bytes.map(format_size).unwrap_or_else(|| "-".to_string())
+        ));
+    }
+    let failed: Vec<&str> = rows
+        .iter()
+        .filter(|(_, duration, _)| duration.is_none())
+        .map(|(label, _, _)| label.as_str())
+        .collect();
+    if !failed.is_empty() {
+        return Err(build_failed(
+            worst,
+            format!("Build failed for {}", failed.join(", ")),
+        ));
+    }
+    Ok(())
+}
+
+/// One toolchain's result in [`toolchain_comparison`].
+struct ToolchainRun {
+    name: String,
+    duration: Option<Duration>,
+    bytes: Option<u64>,
+    /// Duration of the previous recorded run with the same toolchain, in ms.
+    previous_ms: Option<u64>,
+}
+
+/// Times a clean build with each rustup toolchain and prints durations and artifact
+/// sizes side by side, with the previous run of each toolchain for reference.
+pub fn toolchain_comparison(
+    history_path: &Path,
+    base: &BuildOptions,
+    toolchains: &[String],
+) -> Result<()> {
+    let toolchains = toolchain::ensure_installed(toolchains, base.yes)?;
+    if toolchains.is_empty() {
+        bail!("No installed toolchains to compare");
+    }
+    output::status(format!(
+        "🦀 Comparing {} toolchain(s) with clean builds...",
+        toolchains.len()
+    ));
+    if !prompt::confirm(
+        "🧹 Each run starts with `cargo clean`, deleting the target directory. Continue?",
+        base.yes,
+    )? {
+        bail!("Comparison cancelled");
+    }
+
+    let metadata = metadata::cargo_metadata(Path::new("."))?;
+    let mut history = history::load_history(history_path);
+    let mut rows: Vec<ToolchainRun> = Vec::new();
+    let mut worst = 0;
+    for name in &toolchains {
+        output::status(format!("\n▶️  {}", name.bold()));
+        let options = BuildOptions {
+            toolchain: Some(name.clone()),
+            ..base.clone()
+        };
+        let previous_ms = history
+            .runs
+            .iter()
+            .rev()
+            .find(|run| {
+                run.kind.as_deref() == Some("toolchain")
+                    && run.toolchain.as_deref() == Some(name.as_str())
+                    && run.profile.as_deref() == options.profile_name()
+            })
+            .map(|run| run.duration_ms);
+        let row = ToolchainRun {
+            name: name.clone(),
+            duration: None,
+            bytes: None,
+            previous_ms,
+        };
+        cargo_clean()?;
+        let measurement = match run_build(&options, &metadata.target_directory)? {
+            Ok(measurement) => measurement,
+            Err(code) => {
+                output::line(format!("❌ Build failed with `{}`", name).red());
+                worst = worst.max(code);
+                rows.push(row);
+                continue;
+            }
+        };
+        history.record(build_record(&options, &measurement, Some("toolchain")));
+        rows.push(ToolchainRun {
+            duration: Some(measurement.duration),
+            bytes: Some(member_artifact_bytes(
+                &parse_artifacts(&measurement.stdout),
+                &metadata,
+            )),
+            ..row
+        });
+    }
+    history::save_history(history_path, &history)?;
+
+    let reference = rows.iter().find_map(|row| row.duration);
+    output::line(format!("\n{}", "📊 Toolchain comparison:".bold().cyan()));
+    output::line(format!(
+        "{:<20} {:>12} {:>10} {:>12} {:>12}",
+        "Toolchain", "Duration", "vs first", "Artifacts", "Previous"
+    ));
+    for row in &rows {
+        let previous = row
+            .previous_ms
+            .map(|ms| format!("{:.2}s", ms as f64 / 1000.0))
+            .unwrap_or_else(|| "-".to_string());
+        let Some(duration) = row.duration else {
+            output::line(format!(
+                "{:<20} {:>12} {:>10} {:>12} {:>12}",
+                row.name,
+                "failed".red(),
+                "",
+                "",
+                previous
+            ));
+            continue;
+        };
+        let change = reference
+            .map(|reference| {
+                history::slowdown_percent(duration.as_millis() as u64, reference.as_millis() as u64)
+            })
+            .map(|percent| format!("{:+.1}%", percent))
+            .unwrap_or_default();
+        output::line(format!(
+            "{:<20} {:>12} {:>10} {:>12} {:>12}",
+            row.name,
+            format!("{:.2?}", duration),
+            change,
+            row.bytes
+                .map(format_size)
+                .unwrap_or_else(|| "-".to_string()),
+            previous
+        ));
+    }
+    let failed: Vec<&str> = rows
+        .iter()
+        .filter(|row| row.duration.is_none())
+        .map(|row| row.name.as_str())
+        .collect();
+    if !failed.is_empty() {
+        return Err(build_failed(
+            worst,
+            format!("Build failed with {}", failed.join(", ")),
+        ));
+    }
+    Ok(())
+}
+
+/// Size of the generated documentation of each dependency under `doc_dir`, largest
+/// first. `crates` pairs package names with their library target names, which name
+/// the `doc/<crate>` and `doc/src/<crate>` directories.
+pub fn doc_crate_sizes(doc_dir: &Path, crates: &[(String, String)]) -> Vec<(String, u64)> {
+    let mut sizes: Vec<(String, u64)> = crates
+        .iter()
+        .map(|(package, lib)| {
+            let dir_name = lib.replace('-', "_");
+            let bytes =
+                dir_size(&doc_dir.join(&dir_name)) + dir_size(&doc_dir.join("src").join(&dir_name));
+            (package.clone(), bytes)
+        })
+        .filter(|(_, bytes)| *bytes > 0)
+        .collect();
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sizes
+}
+
+/// Removes generated documentation so the next `cargo doc` starts from scratch.
+fn clean_docs() -> Result<()> {
+    let status = Command::new(executor::cargo_bin())
+        .args(["clean", "--doc"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo clean --doc"))?;
+    if !status.success() {
+        bail!("cargo clean --doc failed");
+    }
+    Ok(())
+}
+
+/// Times `cargo doc --no-deps` and a full `cargo doc` from scratch, reports the size of
+/// `target/doc` and the dependencies that dominate it, and records both runs.
+pub fn doc_build_time(history_path: &Path, options: &BuildOptions) -> Result<()> {
+    output::status("📚 Measuring documentation builds...\n");
+    let metadata = metadata::cargo_metadata_with_deps(Path::new("."))?;
+    let mut history = history::load_history(history_path);
+    let mut durations = Vec::new();
+    for (no_deps, kind) in [(true, "doc-no-deps"), (false, "doc")] {
+        let options = BuildOptions {
+            doc: true,
+            no_deps,
+            ..options.clone()
+        };
+        let median_ms = history.median_ms(&history_slice(&options, Some(kind)));
+        clean_docs()?;
+        let measurement = match run_build(&options, &metadata.target_directory)? {
+            Ok(measurement) => measurement,
+            Err(code) => {
+                history::save_history(history_path, &history)?;
+                return Err(build_failed(
+                    code,
+                    "cargo doc failed. Check logs for details.",
+                ));
+            }
+        };
+        history.record(build_record(&options, &measurement, Some(kind)));
+        durations.push((kind, measurement.duration, median_ms));
+    }
+    history::save_history(history_path, &history)?;
+
+    output::line("");
+    for (kind, duration, median_ms) in &durations {
+        let label = if *kind == "doc" {
+            "cargo doc"
+        } else {
+            "cargo doc --no-deps"
+        };
+        let trend = median_ms
+            .map(|median| {
+                format!(
+                    " (recent median {:.2}s, {:+.1}%)",
+                    median as f64 / 1000.0,
+                    history::slowdown_percent(duration.as_millis() as u64, median)
+                )
+            })
+            .unwrap_or_default();
+        output::line(format!(
+            "📚 {:<20} {:>10}{}",
+            label,
+            format!("{:.2?}", duration),
+            trend
+        ));
+    }
+    if let [(_, members, _), (_, full, _)] = durations[..] {
+        output::line(format!(
+            "🔗 Dependencies add {:.2?} to the documentation build",
+            full.saturating_sub(members)
+        ));
+    }
+
+    let doc_dir = match &options.target {
+        Some(triple) => metadata.target_directory.join(triple).join("doc"),
+        None => metadata.target_directory.join("doc"),
+    };
+    output::line(format!(
+        "🗄️  {}: {}",
+        doc_dir.display(),
+        format_size(dir_size(&doc_dir))
+    ));
+    let dependencies: Vec<(String, String)> = metadata
+        .packages
+        .iter()
+        .filter(|pkg| !metadata.workspace_members.contains(&pkg.id))
+        .filter_map(|pkg| {
+            let lib = pkg.targets.iter().find(|t| {
+                t.kind
+                    .iter()
+                    .any(|k| matches!(k.as_str(), "lib" | "rlib" | "proc-macro"))
+            })?;
+            Some((pkg.name.clone(), lib.name.clone()))
+        })
+        .collect();
+    let sizes = doc_crate_sizes(&doc_dir, &dependencies);
+    if !sizes.is_empty() {
+        output::line(format!("\n{}", "📦 Largest dependency docs:".bold().cyan()));
+        let shown = options.top.unwrap_or(sizes.len());
+        for (i, (name, bytes)) in sizes.iter().take(shown).enumerate() {
+            output::line(format!(
+                "{:<4} {:<30} {:>12}",
+                i + 1,
+                name,
+                format_size(*bytes)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Measures an incremental `cargo check` and an incremental `cargo build` after the same
+/// file touch, reports the gap and records both runs in the history.
+pub fn check_delta(history_path: &Path, options: &BuildOptions) -> Result<()> {
+    output::status("📊 Measuring incremental `cargo check` against `cargo build`...\n");
+    let metadata = metadata::cargo_metadata(Path::new("."))?;
+    let check = BuildOptions {
+        check: true,
+        ..options.clone()
+    };
+    let build = BuildOptions {
+        check: false,
+        ..options.clone()
+    };
+
+    // Bring both up to date first so only the touched crate is rebuilt.
+    output::status("🔧 Warming up...");
+    for warm_up in [&check, &build] {
+        run_build(warm_up, &metadata.target_directory)?
+            .map_err(|code| build_failed(code, "Build failed. Check logs for details."))?;
+    }
+
+    let touched = touch_entry_point(&metadata)?;
+    output::status(format!("\n👆 Touched {}", touched.path.display()));
+    let checked = run_build(&check, &metadata.target_directory)?
+        .map_err(|code| build_failed(code, "cargo check failed. Check logs for details."))?;
+    let built = run_build(&build, &metadata.target_directory)?
+        .map_err(|code| build_failed(code, "Build failed. Check logs for details."))?;
+    drop(touched);
+
+    let mut history = history::load_history(history_path);
+    let previous_gap = history
+        .median_ms(&history_slice(&build, Some("incremental-build")))
+        .zip(history.median_ms(&history_slice(&check, Some("incremental-check"))))
+        .map(|(build_ms, check_ms)| build_ms.saturating_sub(check_ms));
+
+    let delta = built.duration.saturating_sub(checked.duration);
+    let ratio = built.duration.as_secs_f64() / checked.duration.as_secs_f64().max(f64::EPSILON);
+    output::line(format!("\n✅ Incremental check: {:.2?}", checked.duration));
+    output::line(format!("🔨 Incremental build: {:.2?}", built.duration));
+    output::line(
+        format!(
+            "⚡ check saves {:.2?} per iteration ({:.1}x faster)",
+            delta, ratio
+        )
+        .green(),
+    );
+    if let Some(previous) = previous_gap {
+        output::line(format!(
+            "📈 Recent median gap: {:.2?} (now {:.2?})",
+            Duration::from_millis(previous),
+            delta
+        ));
+    }
+
+    history.record(build_record(&check, &checked, Some("incremental-check")));
+    history.record(build_record(&build, &built, Some("incremental-build")));
+    history::save_history(history_path, &history)
+}
@@ -0,0 +1,210 @@
+//! Runs ordered lists of cargo commands: the steps configured under `[pipeline]`, and
+//! ad-hoc or named `[chains]` for `cargo sleek chain`.
+
+use anyhow::{bail, Result};
+use colored::*;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::executor::{self, ExecOptions};
+use crate::output;
+use crate::stats::{display_width, pad, Align};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepOutcome {
+    Passed,
+    Failed,
+    /// Not run because an earlier step failed.
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepResult {
+    pub step: String,
+    pub outcome: StepOutcome,
+    pub duration: Duration,
+}
+
+/// Runs `steps` in order with `run_step`, which reports whether a step succeeded.
+/// Stops at the first failure unless `keep_going` is set; remaining steps are skipped.
+pub fn run_steps<F>(steps: &[String], keep_going: bool, mut run_step: F) -> Result<Vec<StepResult>>
+where
+    F: FnMut(&str) -> Result<bool>,
+{
+    let mut results = Vec::new();
+    let mut failed = false;
+
+    for step in steps {
+        if failed && !keep_going {
+            results.push(StepResult {
+                step: step.clone(),
+                outcome: StepOutcome::Skipped,
+                duration: Duration::ZERO,
+            });
+            continue;
+        }
+        let start = Instant::now();
+        let success = run_step(step)?;
+        failed |= !success;
+        results.push(StepResult {
+            step: step.clone(),
+            outcome: if success {
+                StepOutcome::Passed
+            } else {
+                StepOutcome::Failed
+            },
+            duration: start.elapsed(),
+        });
+    }
+    Ok(results)
+}
+
+/// Renders the step summary like the stats table: header first, columns sized to the
+/// data, then the total time. `color` paints the status column.
+pub fn render_summary(results: &[StepResult], color: bool) -> Vec<String> {
+    const HEADERS: [&str; 4] = ["#", "Step", "Status", "Duration"];
+    const ALIGN: [Align; 4] = [Align::Left, Align::Left, Align::Left, Align::Right];
+
+    let cells: Vec<[String; 4]> = results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            let status = match result.outcome {
+                StepOutcome::Passed => "passed",
+                StepOutcome::Failed => "failed",
+                StepOutcome::Skipped => "skipped",
+            };
+            let duration = match result.outcome {
+                StepOutcome::Skipped => "-".to_string(),
+                _ => format!("{:.2?}", result.duration),
+            };
+            [
+                (i + 1).to_string(),
+                result.step.clone(),
+                status.to_string(),
+                duration,
+            ]
+        })
+        .collect();
+    let widths: Vec<usize> = (0..HEADERS.len())
+        .map(|col| {
+            cells
+                .iter()
+                .map(|row| display_width(&row[col]))
+                .chain([display_width(HEADERS[col])])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let header = (0..HEADERS.len())
+        .map(|col| pad(HEADERS[col], widths[col], ALIGN[col]))
+        .collect::<Vec<_>>()
+        .join("  ");
+    let mut lines = vec![header];
+    for (row, result) in cells.iter().zip(results) {
+        let mut padded: Vec<String> = (0..HEADERS.len())
+            .map(|col| pad(&row[col], widths[col], ALIGN[col]))
+            .collect();
+        if color {
+            padded[2] = match result.outcome {
+                StepOutcome::Passed => padded[2].green(),
+                StepOutcome::Failed => padded[2].red().bold(),
+                StepOutcome::Skipped => padded[2].dimmed(),
+            }
+            .to_string();
+        }
+        lines.push(padded.join("  "));
+    }
+    let total: Duration = results.iter().map(|result| result.duration).sum();
+    lines.push(format!("Total: {:.2?}", total));
+    lines
+}
+
+/// Runs `steps` through the executor, prints a summary under `heading` and returns the
+/// results with the highest exit code of the failed steps (0 when all passed).
+fn execute_steps(
+    stats_path: &Path,
+    steps: &[String],
+    keep_going: bool,
+    options: &ExecOptions,
+    heading: &str,
+) -> Result<(Vec<StepResult>, i32)> {
+    let mut worst = 0;
+    let results = run_steps(steps, keep_going, |step| {
+        let mut parts = step.split_whitespace().map(str::to_string);
+        let command = parts.next().unwrap_or_default();
+        let args: Vec<String> = parts.collect();
+        let status = executor::execute_cargo_command(stats_path, &command, &args, options)?;
+        if !status.success() {
+            worst = worst.max(executor::exit_code(status));
+        }
+        Ok(status.success())
+    })?;
+
+    if options.dry_run {
+        return Ok((results, worst));
+    }
+    output::line(format!("\n{}", heading.bold().cyan()));
+    for line in render_summary(&results, true) {
+        output::line(line);
+    }
+    Ok((results, worst))
+}
+
+/// Runs the configured pipeline through the executor and prints a summary. Returns the
+/// exit code of the worst failed step, 0 when every step passed.
+pub fn run_pipeline(
+    stats_path: &Path,
+    steps: &[String],
+    keep_going: bool,
+    options: &ExecOptions,
+) -> Result<i32> {
+    if steps.is_empty() {
+        bail!("No pipeline configured. Add `[pipeline] steps = [\"fmt\", \"clippy\", \"test\"]` to .sleek.toml");
+    }
+
+    let (_, worst) = execute_steps(
+        stats_path,
+        steps,
+        keep_going,
+        options,
+        "📋 Pipeline Summary:",
+    )?;
+    Ok(worst)
+}
+
+/// Expands the arguments of `chain`: a name from `[chains]` becomes its steps, anything
+/// else is a step of its own.
+pub fn expand_chain(args: &[String], chains: &HashMap<String, Vec<String>>) -> Vec<String> {
+    args.iter()
+        .flat_map(|arg| match chains.get(arg) {
+            Some(steps) => steps.clone(),
+            None => vec![arg.clone()],
+        })
+        .collect()
+}
+
+/// Runs a chain of steps and prints a summary. Returns the exit code of the worst
+/// failed step, 0 when every step passed.
+pub fn run_chain(
+    stats_path: &Path,
+    args: &[String],
+    chains: &HashMap<String, Vec<String>>,
+    keep_going: bool,
+    options: &ExecOptions,
+) -> Result<i32> {
+    let steps = expand_chain(args, chains);
+    if steps.is_empty() {
+        bail!("Chain `{}` has no steps", args.join(" "));
+    }
+    let (_, worst) = execute_steps(
+        stats_path,
+        &steps,
+        keep_going,
+        options,
+        "⛓️  Chain Summary:",
+    )?;
+    Ok(worst)
+}
@@ -0,0 +1,230 @@
+//! Audits the `[profile.*]` tables of the workspace root `Cargo.toml`.
+
+use anyhow::{Context, Result};
+use colored::*;
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+use crate::metadata;
+use crate::output;
+use crate::prompt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileFinding {
+    /// Dotted table path, e.g. `profile.release` or `profile.dev.package."*"`.
+    pub table: Vec<String>,
+    pub key: String,
+    /// Current value as written in TOML; `None` when the key is not set.
+    pub current: Option<String>,
+    /// Value written by `--fix`, as TOML; `None` for advisory findings.
+    pub suggested: Option<String>,
+    pub reason: &'static str,
+}
+
+impl ProfileFinding {
+    fn location(&self) -> String {
+        let table: Vec<String> = self
+            .table
+            .iter()
+            .map(|key| {
+                if key
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+                {
+                    key.clone()
+                } else {
+                    format!("\"{}\"", key)
+                }
+            })
+            .collect();
+        format!("[{}] {}", table.join("."), self.key)
+    }
+}
+
+fn path(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+fn get<'a>(doc: &'a DocumentMut, table: &[String], key: &str) -> Option<&'a Value> {
+    let mut item = doc.as_item();
+    for part in table {
+        item = item.get(part)?;
+    }
+    item.get(key)?.as_value()
+}
+
+fn shown(value: Option<&Value>) -> Option<String> {
+    value.map(|v| {
+        let mut bare = v.clone();
+        bare.decor_mut().clear();
+        bare.to_string()
+    })
+}
+
+/// Checks the profiles in `manifest` against common size and speed tuning.
+/// `has_binaries` enables the release binary checks; `has_dependencies` the dev
+/// dependency optimization check.
+pub fn audit_manifest(
+    manifest: &str,
+    has_binaries: bool,
+    has_dependencies: bool,
+) -> Result<Vec<ProfileFinding>> {
+    let doc: DocumentMut = manifest.parse().context("Failed to parse Cargo.toml")?;
+    let release = path(&["profile", "release"]);
+    let mut findings = Vec::new();
+
+    let debug = get(&doc, &release, "debug");
+    let full_debug = debug.is_some_and(|v| {
+        v.as_bool() == Some(true)
+            || v.as_integer() == Some(2)
+            || matches!(v.as_str(), Some("full" | "limited"))
+    });
+    if full_debug {
+        findings.push(ProfileFinding {
+            table: release.clone(),
+            key: "debug".into(),
+            current: shown(debug),
+            suggested: Some("\"line-tables-only\"".into()),
+            reason: "full debug info bloats release binaries; line tables keep backtraces",
+        });
+    }
+
+    if has_binaries {
+        let lto = get(&doc, &release, "lto");
+        if lto.is_none() || lto.and_then(Value::as_bool) == Some(false) {
+            findings.push(ProfileFinding {
+                table: release.clone(),
+                key: "lto".into(),
+                current: shown(lto),
+                suggested: Some("\"thin\"".into()),
+                reason: "thin LTO shrinks and speeds up binaries at a modest build-time cost",
+            });
+        }
+        if get(&doc, &release, "codegen-units").is_none() {
+            findings.push(ProfileFinding {
+                table: release.clone(),
+                key: "codegen-units".into(),
+                current: None,
+                suggested: Some("1".into()),
+                reason: "one codegen unit lets LLVM optimize across the whole crate",
+            });
+        }
+    }
+
+    let panic = get(&doc, &release, "panic");
+    if panic.and_then(Value::as_str) == Some("abort") {
+        findings.push(ProfileFinding {
+            table: release.clone(),
+            key: "panic".into(),
+            current: shown(panic),
+            suggested: None,
+            reason: "aborting skips unwinding: Drop impls do not run and catch_unwind \
+                     cannot recover; fine for CLIs, risky for servers and plugins",
+        });
+    }
+
+    let deps = path(&["profile", "dev", "package", "*"]);
+    if has_dependencies && get(&doc, &deps, "opt-level").is_none() {
+        findings.push(ProfileFinding {
+            table: deps,
+            key: "opt-level".into(),
+            current: None,
+            suggested: Some("2".into()),
+            reason: "dependencies build at opt-level 0 in dev; optimizing them speeds up \
+                     debug runs without slowing rebuilds of your own code",
+        });
+    }
+    Ok(findings)
+}
+
+/// Writes the suggested values of `findings` into `manifest`, creating tables as needed.
+pub fn apply_fixes(manifest: &str, findings: &[ProfileFinding]) -> Result<String> {
+    let mut doc: DocumentMut = manifest.parse().context("Failed to parse Cargo.toml")?;
+    for finding in findings {
+        let Some(suggested) = &finding.suggested else {
+            continue;
+        };
+        let mut value: Value = suggested
+            .parse()
+            .with_context(|| format!("Invalid suggested value {suggested}"))?;
+        let mut table = doc.as_table_mut();
+        for (depth, key) in finding.table.iter().enumerate() {
+            let item = table.entry(key).or_insert_with(|| {
+                let mut new = Table::new();
+                // Only the innermost table needs a header of its own.
+                new.set_implicit(depth + 1 < finding.table.len());
+                Item::Table(new)
+            });
+            table = item
+                .as_table_mut()
+                .with_context(|| format!("`{}` is not a table", finding.table.join(".")))?;
+        }
+        if let Some(old) = table.get(&finding.key).and_then(Item::as_value) {
+            *value.decor_mut() = old.decor().clone();
+        }
+        table.insert(&finding.key, Item::Value(value));
+    }
+    Ok(doc.to_string())
+}
+
+pub fn audit_profile(project_dir: &Path, fix: bool, yes: bool) -> Result<()> {
+    let metadata = metadata::cargo_metadata(project_dir)?;
+    let has_binaries = metadata.members().any(|pkg| {
+        pkg.targets
+            .iter()
+            .any(|t| t.kind.iter().any(|k| k == "bin"))
+    });
+    let has_dependencies = metadata
+        .members()
+        .any(|pkg| pkg.dependencies.iter().any(|dep| dep.kind.is_none()));
+    let root = if metadata.workspace_root.as_os_str().is_empty() {
+        project_dir.to_path_buf()
+    } else {
+        metadata.workspace_root.clone()
+    };
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    let findings = audit_manifest(&manifest, has_binaries, has_dependencies)?;
+
+    output::status(format!(
+        "🔍 Auditing profile settings in {}...",
+        manifest_path.display()
+    ));
+    if findings.is_empty() {
+        output::line("✅ Profile settings look well tuned!".green());
+        return Ok(());
+    }
+    for finding in &findings {
+        let current = finding.current.as_deref().unwrap_or("(unset)");
+        let suggestion = finding
+            .suggested
+            .as_ref()
+            .map(|s| format!(" → {}", s).green().to_string())
+            .unwrap_or_default();
+        output::line(format!(
+            "   • {} = {}{}",
+            finding.location().bold(),
+            current,
+            suggestion
+        ));
+        output::line(format!("     {}", finding.reason.dimmed()));
+    }
+
+    let fixable = findings.iter().filter(|f| f.suggested.is_some()).count();
+    if fix && fixable > 0 {
+        let question = format!(
+            "✏️  Apply {fixable} change(s) to {}?",
+            manifest_path.display()
+        );
+        if !prompt::confirm(&question, yes)? {
+            output::line("❌ No changes written.".yellow());
+            return Ok(());
+        }
+        let fixed = apply_fixes(&manifest, &findings)?;
+        fs::write(&manifest_path, fixed).context("Failed to write Cargo.toml")?;
+        output::line(format!("✏️  Updated {fixable} setting(s)").green());
+    }
+    Ok(())
+}
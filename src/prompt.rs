@@ -0,0 +1,53 @@
+//! Interactive yes/no confirmation.
+
+use anyhow::{bail, Result};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Reads a yes/no answer to `question` from `input`; anything but `y`/`yes` means no.
+pub fn ask(question: &str, mut input: impl BufRead, mut output: impl Write) -> Result<bool> {
+    write!(output, "{} [y/N] ", question)?;
+    output.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+/// Decides whether `command` may run. Commands not in `listed` always may; listed ones
+/// run with `assume_yes` or outside a terminal, and otherwise need a yes read from `input`.
+pub fn confirm_command(
+    command: &str,
+    listed: &[String],
+    assume_yes: bool,
+    interactive: bool,
+    input: impl BufRead,
+    output: impl Write,
+) -> Result<bool> {
+    if assume_yes || !interactive || !listed.iter().any(|c| c == command) {
+        return Ok(true);
+    }
+    let question = if command == "clean" {
+        "🧹 `cargo clean` deletes the target directory, which can take long to rebuild. Continue?"
+            .to_string()
+    } else {
+        format!("⚠️  Run `cargo {}`?", command)
+    };
+    ask(&question, input, output)
+}
+
+/// Asks `question` on the terminal, or succeeds straight away when `assume_yes` is set.
+/// Fails instead of blocking when stdin is not a terminal.
+pub fn confirm(question: &str, assume_yes: bool) -> Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        bail!(
+            "{} Not asking without a terminal; pass --yes to confirm.",
+            question
+        );
+    }
+    ask(question, io::stdin().lock(), io::stdout())
+}
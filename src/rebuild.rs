@@ -0,0 +1,415 @@
+//! Explains why cargo rebuilds crates that should be fresh.
+
+use anyhow::Result;
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::SystemTime;
+
+use crate::executor;
+use crate::history::{self, BuildRecord};
+use crate::metadata;
+use crate::output;
+
+/// Log filter that makes cargo explain each dirty fingerprint.
+pub const FINGERPRINT_LOG: &str = "cargo::core::compiler::fingerprint=info";
+
+/// Builds recorded per `rebuild-frequency` window by default.
+pub const DEFAULT_FREQUENCY_WINDOW: usize = 20;
+
+/// Share of incremental builds in which a crate must rebuild to be flagged.
+pub const FREQUENT_REBUILD_SHARE: f64 = 0.8;
+
+/// Incremental builds needed before any crate is flagged.
+pub const MIN_INCREMENTAL_RUNS: usize = 3;
+
+/// Variables that differ between shells, terminals and IDEs. A build script that reruns
+/// when one of them changes rebuilds whenever cargo is invoked from somewhere else.
+pub const VOLATILE_ENV_VARS: &[&str] = &[
+    "PATH",
+    "PWD",
+    "OLDPWD",
+    "SHLVL",
+    "HOME",
+    "TERM",
+    "TMPDIR",
+    "TMP",
+    "TEMP",
+    "HOSTNAME",
+    "DISPLAY",
+    "SSH_AUTH_SOCK",
+    "RANDOM",
+    "_",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebuildReason {
+    /// Package as cargo prints it, e.g. `serde v1.0.210`.
+    pub package: String,
+    pub reason: String,
+}
+
+impl RebuildReason {
+    /// The environment variable behind this rebuild, if an env change caused it.
+    pub fn env_var(&self) -> Option<&str> {
+        let rest = self.reason.strip_prefix("the env variable ")?;
+        rest.split_whitespace().next()
+    }
+}
+
+/// Strips the source path from `foo v0.1.0 (/work/foo)`.
+fn package_name(text: &str) -> String {
+    text.split(" (").next().unwrap_or(text).trim().to_string()
+}
+
+/// Parses the `Dirty <package>: <reason>` lines printed by `cargo build -v`.
+pub fn parse_dirty_lines(stderr: &str) -> Vec<RebuildReason> {
+    stderr
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("Dirty "))
+        .filter_map(|rest| {
+            // Split after the `(source path)` when there is one, as the path may contain `: `.
+            let split = rest
+                .find("): ")
+                .map(|at| at + 1)
+                .or_else(|| rest.find(": "))?;
+            Some(RebuildReason {
+                package: package_name(&rest[..split]),
+                reason: rest[split + 2..].trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Turns a cargo `DirtyReason` debug string into plain language.
+fn describe_dirty_reason(reason: &str) -> String {
+    let variant = reason.split([' ', '(', '{']).next().unwrap_or(reason);
+    match variant {
+        "EnvVarChanged" => {
+            let name = reason
+                .split('"')
+                .nth(1)
+                .unwrap_or("an environment variable");
+            format!("the env variable {} changed", name)
+        }
+        "RustcChanged" => "the compiler changed".to_string(),
+        "FeaturesChanged" => "the activated features changed".to_string(),
+        "RustflagsChanged" => "RUSTFLAGS changed".to_string(),
+        "ProfileConfigurationChanged" => "the profile configuration changed".to_string(),
+        "FreshBuild" => "it was never built before".to_string(),
+        "UnitDependencyInfoChanged" | "StaleDepFingerprint" => {
+            "one of its dependencies was rebuilt".to_string()
+        }
+        "FsStatusOutdated" if reason.contains("ChangedEnv") => {
+            let name = reason
+                .split('"')
+                .nth(1)
+                .unwrap_or("an environment variable");
+            format!("the env variable {} changed", name)
+        }
+        "FsStatusOutdated" => "a source file changed".to_string(),
+        _ => reason.to_string(),
+    }
+}
+
+/// Parses `CARGO_LOG=cargo::core::compiler::fingerprint=info` output, for cargo versions
+/// that do not print `Dirty` lines with `-v`.
+pub fn parse_fingerprint_log(stderr: &str) -> Vec<RebuildReason> {
+    let mut reasons = Vec::new();
+    let mut package: Option<String> = None;
+    for line in stderr.lines() {
+        if let Some((_, rest)) = line.split_once("fingerprint dirty for ") {
+            package = Some(package_name(rest.split('/').next().unwrap_or(rest)));
+        } else if let Some((_, reason)) = line.split_once("    dirty: ") {
+            if let Some(package) = package.take() {
+                reasons.push(RebuildReason {
+                    package,
+                    reason: describe_dirty_reason(reason.trim()),
+                });
+            }
+        }
+    }
+    reasons
+}
+
+/// Variables a build script's output registers with `rerun-if-env-changed`.
+pub fn rerun_env_vars(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("cargo::rerun-if-env-changed=")
+                .or_else(|| line.strip_prefix("cargo:rerun-if-env-changed="))
+        })
+        .map(|var| var.trim().to_string())
+        .collect()
+}
+
+/// Build scripts under `profile_dir` (e.g. `target/debug`) that rerun when a volatile
+/// variable changes, as `(package, variable)` pairs.
+pub fn volatile_build_script_vars(profile_dir: &Path) -> Vec<(String, String)> {
+    let Ok(entries) = fs::read_dir(profile_dir.join("build")) else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(output) = fs::read_to_string(entry.path().join("output")) else {
+            continue;
+        };
+        let dir_name = entry.file_name().to_string_lossy().into_owned();
+        // Build directories are named `<package>-<hash>`.
+        let package = dir_name
+            .rsplit_once('-')
+            .map(|(name, _)| name.to_string())
+            .unwrap_or(dir_name);
+        for var in rerun_env_vars(&output) {
+            if VOLATILE_ENV_VARS.contains(&var.as_str()) {
+                found.push((package.clone(), var));
+            }
+        }
+    }
+    found.sort();
+    found.dedup();
+    found
+}
+
+/// Files under `dir` (skipping `target` and hidden directories) modified after `now`.
+pub fn future_mtime_files(dir: &Path, now: SystemTime) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return found;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let Ok(kind) = entry.file_type() else {
+            continue;
+        };
+        if kind.is_dir() {
+            if name != "target" && !name.to_string_lossy().starts_with('.') {
+                found.extend(future_mtime_files(&path, now));
+            }
+        } else if entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .is_ok_and(|modified| modified > now)
+        {
+            found.push(path);
+        }
+    }
+    found
+}
+
+/// Builds recorded by `build-time` that reuse earlier artifacts, as opposed to cold,
+/// matrix, toolchain and doc runs that start from a clean target directory.
+pub fn is_incremental(kind: Option<&str>) -> bool {
+    matches!(
+        kind,
+        None | Some("warm" | "incremental-build" | "incremental-check")
+    )
+}
+
+/// How often one crate was recompiled across the recorded builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebuildCount {
+    pub name: String,
+    /// Builds, of any kind, that compiled the crate.
+    pub rebuilds: usize,
+    /// Incremental builds that compiled the crate.
+    pub incremental_rebuilds: usize,
+}
+
+/// Rebuild counts per crate over `runs`, most rebuilt first, and the number of
+/// incremental runs among them. Runs without recompilation data are skipped.
+pub fn rebuild_counts(runs: &[BuildRecord]) -> (Vec<RebuildCount>, usize) {
+    let mut counts: Vec<RebuildCount> = Vec::new();
+    let mut incremental_runs = 0;
+    for run in runs {
+        let Some(recompiled) = &run.recompiled else {
+            continue;
+        };
+        let incremental = is_incremental(run.kind.as_deref());
+        incremental_runs += usize::from(incremental);
+        let mut names: Vec<&String> = recompiled.iter().collect();
+        names.sort();
+        names.dedup();
+        for name in names {
+            let index = match counts.iter().position(|c| &c.name == name) {
+                Some(index) => index,
+                None => {
+                    counts.push(RebuildCount {
+                        name: name.clone(),
+                        rebuilds: 0,
+                        incremental_rebuilds: 0,
+                    });
+                    counts.len() - 1
+                }
+            };
+            counts[index].rebuilds += 1;
+            counts[index].incremental_rebuilds += usize::from(incremental);
+        }
+    }
+    counts.sort_by(|a, b| {
+        b.incremental_rebuilds
+            .cmp(&a.incremental_rebuilds)
+            .then(b.rebuilds.cmp(&a.rebuilds))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    (counts, incremental_runs)
+}
+
+/// Whether a crate rebuilt in nearly every incremental build, which for a dependency
+/// points at a changing cfg, env var or build script input.
+pub fn rebuilds_constantly(count: &RebuildCount, incremental_runs: usize) -> bool {
+    incremental_runs >= MIN_INCREMENTAL_RUNS
+        && count.incremental_rebuilds as f64 >= incremental_runs as f64 * FREQUENT_REBUILD_SHARE
+}
+
+pub fn show_rebuild_frequency(history_path: &Path, last: usize) -> Result<()> {
+    let history = history::load_history(history_path);
+    let start = history.runs.len().saturating_sub(last);
+    let runs = &history.runs[start..];
+    let (counts, incremental_runs) = rebuild_counts(runs);
+    if counts.is_empty() {
+        output::line(
+            "🔁 No recompilation data recorded yet. Run `cargo sleek build-time` a few times."
+                .yellow(),
+        );
+        return Ok(());
+    }
+    // Workspace members are expected to rebuild after every edit.
+    let members: Vec<String> = metadata::cargo_metadata(Path::new("."))
+        .map(|metadata| metadata.members().map(|pkg| pkg.name.clone()).collect())
+        .unwrap_or_default();
+
+    output::line(
+        format!(
+            "🔁 Rebuilds over the last {} build(s) ({} incremental):",
+            runs.len(),
+            incremental_runs
+        )
+        .bold()
+        .cyan(),
+    );
+    output::line(format!(
+        "{:<4} {:<30} {:>9} {:>12}",
+        "#", "Crate", "Rebuilds", "Incremental"
+    ));
+    for (i, count) in counts.iter().enumerate() {
+        let row = format!(
+            "{:<4} {:<30} {:>9} {:>12}",
+            i + 1,
+            count.name,
+            count.rebuilds,
+            format!("{}/{}", count.incremental_rebuilds, incremental_runs)
+        );
+        if rebuilds_constantly(count, incremental_runs) && !members.contains(&count.name) {
+            output::line(format!("{} {}", row.red(), "◀ rebuilds constantly".red()));
+            match history.rebuild_reasons.get(&count.name) {
+                Some(reason) => {
+                    output::line(format!("       {}", format!("cause: {}", reason).yellow()))
+                }
+                None => output::line(format!(
+                    "       {}",
+                    "run `cargo sleek why-rebuild` to find the cause".dimmed()
+                )),
+            }
+        } else {
+            output::line(row);
+        }
+    }
+    Ok(())
+}
+
+pub fn why_rebuild(project_dir: &Path, history_path: &Path) -> Result<()> {
+    output::status("🔍 Building with fingerprint logging to see what is rebuilt...");
+    let metadata = metadata::cargo_metadata(project_dir)?;
+    let now = SystemTime::now();
+    let output = Command::new(executor::cargo_bin())
+        .args(["build", "-v"])
+        .current_dir(project_dir)
+        .env("CARGO_LOG", FINGERPRINT_LOG)
+        .stdout(Stdio::null())
+        .output()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo build"))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        output::line("❌ Build failed. Check logs for details.".red());
+    }
+
+    let mut reasons = parse_dirty_lines(&stderr);
+    if reasons.is_empty() {
+        reasons = parse_fingerprint_log(&stderr);
+    }
+    let fresh = stderr
+        .lines()
+        .filter(|line| line.trim_start().starts_with("Fresh "))
+        .count();
+    if !reasons.is_empty() {
+        let mut history = history::load_history(history_path);
+        for reason in &reasons {
+            let name = reason.package.split(' ').next().unwrap_or(&reason.package);
+            history
+                .rebuild_reasons
+                .insert(name.to_string(), reason.reason.clone());
+        }
+        history::save_history(history_path, &history)?;
+    }
+
+    if reasons.is_empty() {
+        output::line(format!("✅ Nothing was rebuilt ({} crate(s) fresh)", fresh).green());
+    } else {
+        output::line(
+            format!("🔁 {} crate(s) rebuilt, {} fresh:", reasons.len(), fresh)
+                .bold()
+                .cyan(),
+        );
+        for reason in &reasons {
+            output::line(format!("   • {}: {}", reason.package.bold(), reason.reason));
+        }
+    }
+
+    for reason in &reasons {
+        if let Some(var) = reason.env_var() {
+            output::line(
+                format!(
+                    "⚠️  {} rebuilt because {} changed since the last build; \
+                     keep it identical between your shell, IDE and CI",
+                    reason.package, var
+                )
+                .yellow(),
+            );
+        }
+    }
+    let volatile = volatile_build_script_vars(&metadata.target_directory.join("debug"));
+    for (package, var) in &volatile {
+        output::line(
+            format!(
+                "⚠️  The build script of {} declares rerun-if-env-changed={}; \
+                 {} often differs between shells and IDEs, forcing rebuilds",
+                package, var, var
+            )
+            .yellow(),
+        );
+    }
+
+    let future: Vec<PathBuf> = metadata
+        .members()
+        .filter_map(|pkg| pkg.manifest_path.parent())
+        .flat_map(|dir| future_mtime_files(dir, now))
+        .collect();
+    if !future.is_empty() {
+        output::line(
+            format!(
+                "⚠️  {} file(s) have modification times in the future and look changed on \
+                 every build (fix with `touch`):",
+                future.len()
+            )
+            .yellow(),
+        );
+        for path in &future {
+            output::line(format!("   • {}", path.display()));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,38 @@
+//! Per-run JSON reports for CI dashboards (`--report-json`).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where and how to write run reports.
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub path: PathBuf,
+    /// Append one JSON object per line instead of overwriting the file.
+    pub append: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunReport {
+    pub command: String,
+    pub args: Vec<String>,
+    /// `None` when cargo was terminated by a signal.
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub timestamp: u64,
+}
+
+/// Writes `report` as a single JSON line, replacing or appending to the file.
+pub fn write_report(path: &Path, report: &RunReport, append: bool) -> Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .with_context(|| format!("Failed to open report file {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(report)?).context("Failed to write run report")
+}
@@ -0,0 +1,346 @@
+//! Release binary size breakdown by section and by crate.
+
+use anyhow::{Context, Result};
+use colored::*;
+use object::{Object, ObjectSection, ObjectSymbol, SymbolKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::executor;
+use crate::history::{self, SizeRecord};
+use crate::metadata;
+use crate::output;
+use crate::performance::{self, format_size};
+
+/// Number of crates listed in the human-readable breakdown.
+pub const TOP_CRATES: usize = 15;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SectionSize {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CrateSize {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SizeReport {
+    pub binary: String,
+    pub path: PathBuf,
+    pub file_size: u64,
+    /// File size minus debug info and symbol tables, i.e. roughly what `strip` leaves.
+    pub stripped_size: u64,
+    pub sections: Vec<SectionSize>,
+    /// Code size attributed to crates from symbol names; empty for stripped binaries.
+    pub crates: Vec<CrateSize>,
+}
+
+/// Crate a demangled symbol belongs to, e.g. `serde_json` for
+/// `<serde_json::value::Value as core::fmt::Debug>::fmt`.
+pub fn crate_of_symbol(demangled: &str) -> Option<&str> {
+    let path = demangled.trim_start_matches(['<', '&', '*']);
+    let path = path.strip_prefix("mut ").unwrap_or(path);
+    let path = path.strip_prefix("dyn ").unwrap_or(path);
+    let (krate, _) = path.split_once("::")?;
+    let valid = !krate.is_empty() && krate.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then_some(krate)
+}
+
+/// Whether a section only holds debug info or symbols, which stripping removes.
+fn is_strippable(section: &str) -> bool {
+    section.starts_with(".debug")
+        || section.starts_with("__debug")
+        || matches!(section, ".symtab" | ".strtab" | "__DWARF")
+}
+
+/// Section sizes (largest first) and per-crate code sizes of an object file.
+pub fn analyze_binary(data: &[u8]) -> Result<(Vec<SectionSize>, Vec<CrateSize>)> {
+    let file = object::File::parse(data).context("Failed to parse binary")?;
+
+    let mut sections: Vec<SectionSize> = file
+        .sections()
+        .filter(|section| section.size() > 0)
+        .filter_map(|section| {
+            Some(SectionSize {
+                name: section.name().ok()?.to_string(),
+                size: section.size(),
+            })
+        })
+        .collect();
+    sections.sort_by_key(|section| std::cmp::Reverse(section.size));
+
+    let mut crates: HashMap<String, u64> = HashMap::new();
+    for symbol in file.symbols() {
+        if symbol.kind() != SymbolKind::Text || symbol.size() == 0 {
+            continue;
+        }
+        let Ok(name) = symbol.name() else {
+            continue;
+        };
+        let demangled = rustc_demangle::demangle(name).to_string();
+        let krate = crate_of_symbol(&demangled).unwrap_or("[other]").to_string();
+        *crates.entry(krate).or_default() += symbol.size();
+    }
+    let mut crates: Vec<CrateSize> = crates
+        .into_iter()
+        .map(|(name, size)| CrateSize { name, size })
+        .collect();
+    crates.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    Ok((sections, crates))
+}
+
+pub fn size_report(binary: &str, path: &Path) -> Result<SizeReport> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let (sections, crates) = analyze_binary(&data)?;
+    let strippable: u64 = sections
+        .iter()
+        .filter(|section| is_strippable(&section.name))
+        .map(|section| section.size)
+        .sum();
+    Ok(SizeReport {
+        binary: binary.to_string(),
+        path: path.to_path_buf(),
+        file_size: data.len() as u64,
+        stripped_size: (data.len() as u64).saturating_sub(strippable),
+        sections,
+        crates,
+    })
+}
+
+/// Builds the workspace in release mode and returns its binaries as `(name, path)`.
+fn build_release() -> Result<Vec<(String, PathBuf)>> {
+    output::status("🔨 Building release binaries...");
+    let output = Command::new(executor::cargo_bin())
+        .args([
+            "build",
+            "--release",
+            "--message-format=json-render-diagnostics",
+        ])
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo build --release"))?;
+    if !output.status.success() {
+        anyhow::bail!("Release build failed");
+    }
+    let metadata = metadata::cargo_metadata(Path::new("."))?;
+    Ok(
+        performance::parse_artifacts(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .filter(|artifact| metadata.workspace_members.contains(&artifact.package_id))
+            .filter_map(|artifact| Some((artifact.target.name, artifact.executable?)))
+            .collect(),
+    )
+}
+
+/// Existing release binaries, without building.
+pub fn existing_release() -> Result<Vec<(String, PathBuf)>> {
+    let metadata = metadata::cargo_metadata(Path::new("."))?;
+    Ok(performance::binary_paths(&metadata, "release")
+        .into_iter()
+        .filter(|path| path.is_file())
+        .map(|path| {
+            let name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            (name, path)
+        })
+        .collect())
+}
+
+/// Size records for `binaries`, taken now at the current commit.
+pub fn size_records(binaries: &[(String, PathBuf)]) -> Vec<SizeRecord> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let commit = history::git_commit();
+    binaries
+        .iter()
+        .filter_map(|(name, path)| {
+            Some(SizeRecord {
+                timestamp,
+                binary: name.clone(),
+                bytes: fs::metadata(path).ok()?.len(),
+                commit: commit.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Appends the sizes of freshly built release `binaries` to the history.
+pub fn record_release_sizes(history_path: &Path, binaries: &[(String, PathBuf)]) -> Result<()> {
+    let records = size_records(binaries);
+    if records.is_empty() {
+        return Ok(());
+    }
+    let mut history = history::load_history(history_path);
+    history.sizes.extend(records);
+    history::save_history(history_path, &history)
+}
+
+/// Limits checked by `size --max-size` and `--max-growth`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SizeLimits {
+    pub max_size: Option<u64>,
+    /// Growth over the binary's previous recorded size, in percent.
+    pub max_growth: Option<f64>,
+}
+
+/// A binary that exceeded a size limit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeViolation {
+    pub binary: String,
+    pub bytes: u64,
+    pub limit_bytes: u64,
+    /// Previous recorded size when the growth limit was exceeded.
+    pub previous_bytes: Option<u64>,
+}
+
+/// Checks `current` sizes against `limits`; growth is measured against the latest entry
+/// of the same binary in `previous`, so each binary target is judged on its own.
+pub fn check_size_limits(
+    previous: &[SizeRecord],
+    current: &[SizeRecord],
+    limits: SizeLimits,
+) -> Vec<SizeViolation> {
+    let mut violations = Vec::new();
+    for record in current {
+        if let Some(max) = limits.max_size.filter(|max| record.bytes > *max) {
+            violations.push(SizeViolation {
+                binary: record.binary.clone(),
+                bytes: record.bytes,
+                limit_bytes: max,
+                previous_bytes: None,
+            });
+        }
+        let last = previous
+            .iter()
+            .rev()
+            .find(|prev| prev.binary == record.binary)
+            .map(|prev| prev.bytes);
+        if let (Some(growth), Some(last)) = (limits.max_growth, last) {
+            let limit = (last as f64 * (1.0 + growth / 100.0)).round() as u64;
+            if record.bytes > limit {
+                violations.push(SizeViolation {
+                    binary: record.binary.clone(),
+                    bytes: record.bytes,
+                    limit_bytes: limit,
+                    previous_bytes: Some(last),
+                });
+            }
+        }
+    }
+    violations
+}
+
+fn report_size_violations(violations: &[SizeViolation]) {
+    for violation in violations {
+        let detail = match violation.previous_bytes {
+            Some(previous) => format!(
+                "grew from {} to {} (limit {})",
+                format_size(previous),
+                format_size(violation.bytes),
+                format_size(violation.limit_bytes)
+            ),
+            None => format!(
+                "is {}, over the {} limit",
+                format_size(violation.bytes),
+                format_size(violation.limit_bytes)
+            ),
+        };
+        output::warning(format!("❌ Binary {} {}", violation.binary, detail).red());
+    }
+}
+
+fn print_report(report: &SizeReport) {
+    output::line(format!(
+        "\n📦 {} ({})",
+        report.binary.bold().cyan(),
+        report.path.display()
+    ));
+    output::line(format!(
+        "   Total size:        {}",
+        format_size(report.file_size).bold()
+    ));
+    output::line(format!(
+        "   Stripped (approx): {}",
+        format_size(report.stripped_size)
+    ));
+
+    output::line(format!("\n   {:<28} {:>12} {:>7}", "Section", "Size", "%"));
+    for section in &report.sections {
+        output::line(format!(
+            "   {:<28} {:>12} {:>6.1}%",
+            section.name,
+            format_size(section.size),
+            section.size as f64 * 100.0 / report.file_size as f64
+        ));
+    }
+
+    if report.crates.is_empty() {
+        output::line(format!(
+            "\n   {}",
+            "No symbols found; the binary is stripped (set `strip = false` for a per-crate breakdown)."
+                .yellow()
+        ));
+        return;
+    }
+    let code: u64 = report.crates.iter().map(|krate| krate.size).sum();
+    output::line(format!("\n   {:<28} {:>12} {:>7}", "Crate", "Code", "%"));
+    for krate in report.crates.iter().take(TOP_CRATES) {
+        output::line(format!(
+            "   {:<28} {:>12} {:>6.1}%",
+            krate.name,
+            format_size(krate.size),
+            krate.size as f64 * 100.0 / code as f64
+        ));
+    }
+}
+
+/// Prints the breakdown and records the sizes. Returns `false` when a binary exceeded
+/// one of `limits`.
+pub fn analyze_size(
+    history_path: &Path,
+    build: bool,
+    json: bool,
+    limits: SizeLimits,
+) -> Result<bool> {
+    let binaries = if build {
+        build_release()?
+    } else {
+        existing_release()?
+    };
+    if binaries.is_empty() {
+        output::line("⚠️  No release binaries found.".yellow());
+        return Ok(true);
+    }
+    let reports = binaries
+        .iter()
+        .map(|(name, path)| size_report(name, path))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut history = history::load_history(history_path);
+    let records = size_records(&binaries);
+    let violations = check_size_limits(&history.sizes, &records, limits);
+    history.sizes.extend(records);
+    history::save_history(history_path, &history)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else {
+        reports.iter().for_each(print_report);
+    }
+    report_size_violations(&violations);
+    Ok(violations.is_empty())
+}
@@ -0,0 +1,193 @@
+//! Startup latency of the project's own binary (`run --measure-startup`).
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::executor;
+use crate::history::{self, StartupRecord};
+use crate::metadata;
+use crate::output;
+use crate::performance;
+use crate::stats;
+
+/// Launches measured when `--measure-startup` is given without a count.
+pub const DEFAULT_STARTUP_RUNS: usize = 10;
+
+/// Splits `run` arguments like `cargo run` does: cargo build arguments, then the
+/// binary's own arguments after `--`.
+pub fn split_run_args(args: &[String]) -> (&[String], &[String]) {
+    match args.iter().position(|arg| arg == "--") {
+        Some(index) => (&args[..index], &args[index + 1..]),
+        None => (args, &[]),
+    }
+}
+
+/// Minimum, median and 95th percentile of a set of launches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StartupSummary {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+/// Nearest-rank percentile of sorted `samples`.
+fn percentile(sorted: &[Duration], percent: f64) -> Duration {
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+pub fn summarize(samples: &[Duration]) -> Option<StartupSummary> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+    Some(StartupSummary {
+        min: sorted[0],
+        median: percentile(&sorted, 50.0),
+        p95: percentile(&sorted, 95.0),
+    })
+}
+
+/// Builds the project and returns its binary as `(name, path)`.
+fn build_binary(build_args: &[String]) -> Result<(String, PathBuf)> {
+    output::status("🔨 Building...");
+    let output = Command::new(executor::cargo_bin())
+        .args(["build", "--message-format=json-render-diagnostics"])
+        .args(build_args)
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo build"))?;
+    if !output.status.success() {
+        bail!("Build failed");
+    }
+    let metadata = metadata::cargo_metadata(Path::new("."))?;
+    let mut binaries: Vec<(String, PathBuf)> =
+        performance::parse_artifacts(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .filter(|artifact| metadata.workspace_members.contains(&artifact.package_id))
+            .filter(|artifact| artifact.target.kind.iter().any(|kind| kind == "bin"))
+            .filter_map(|artifact| Some((artifact.target.name, artifact.executable?)))
+            .collect();
+    match binaries.len() {
+        0 => bail!("The build produced no binary to launch"),
+        1 => Ok(binaries.remove(0)),
+        _ => {
+            let names: Vec<&str> = binaries.iter().map(|(name, _)| name.as_str()).collect();
+            bail!(
+                "Several binaries were built ({}); pick one with `--bin NAME`",
+                names.join(", ")
+            )
+        }
+    }
+}
+
+/// Launches `binary` once and returns the time until it exits, or until its first output
+/// byte with `first_output` (falling back to the exit for silent programs).
+fn launch(binary: &Path, args: &[String], first_output: bool) -> Result<Duration> {
+    let start = Instant::now();
+    let mut child = Command::new(binary)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(if first_output {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to launch {}", binary.display()))?;
+    let mut elapsed = None;
+    if let Some(mut stdout) = child.stdout.take() {
+        let mut byte = [0u8; 1];
+        if stdout.read(&mut byte).is_ok_and(|read| read > 0) {
+            elapsed = Some(start.elapsed());
+        }
+        // Keep draining so the program never blocks on a full pipe.
+        std::io::copy(&mut stdout, &mut std::io::sink()).ok();
+    }
+    child.wait().context("Failed to wait for the binary")?;
+    Ok(elapsed.unwrap_or_else(|| start.elapsed()))
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub fn measure_startup(
+    history_path: &Path,
+    stats_path: &Path,
+    args: &[String],
+    runs: usize,
+    first_output: bool,
+) -> Result<()> {
+    let (build_args, binary_args) = split_run_args(args);
+    let built = build_binary(build_args);
+    stats::track_command_result(stats_path, "run", built.is_ok())?;
+    let (name, binary) = built?;
+
+    let metric = if first_output { "first output" } else { "exit" };
+    output::status(format!(
+        "⏱️  Launching {} {} time(s), timing to {}...",
+        name, runs, metric
+    ));
+    let samples = (0..runs)
+        .map(|_| launch(&binary, binary_args, first_output))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(summary) = summarize(&samples) else {
+        bail!("No launches to measure; pass a count of at least 1");
+    };
+
+    let mut history = history::load_history(history_path);
+    let previous = history
+        .startups
+        .iter()
+        .rev()
+        .find(|record| record.binary == name && record.first_output == first_output)
+        .cloned();
+    let record = StartupRecord {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+        commit: history::git_commit(),
+        binary: name.clone(),
+        first_output,
+        runs,
+        min_ms: ms(summary.min),
+        median_ms: ms(summary.median),
+        p95_ms: ms(summary.p95),
+    };
+
+    output::line(format!(
+        "\n{}",
+        format!("🚀 Startup time of {} (to {}):", name, metric)
+            .bold()
+            .cyan()
+    ));
+    output::line(format!("   Min:    {:>9.2}ms", record.min_ms));
+    output::line(format!("   Median: {:>9.2}ms", record.median_ms));
+    output::line(format!("   P95:    {:>9.2}ms", record.p95_ms));
+    if let Some(previous) = previous.filter(|previous| previous.median_ms > 0.0) {
+        let change = (record.median_ms - previous.median_ms) / previous.median_ms * 100.0;
+        let text = format!(
+            "   Median was {:.2}ms at {} ({:+.1}%)",
+            previous.median_ms,
+            previous.commit.as_deref().unwrap_or("an unknown commit"),
+            change
+        );
+        if change > 0.0 {
+            output::line(text.red());
+        } else {
+            output::line(text.green());
+        }
+    }
+
+    history.startups.push(record);
+    history::save_history(history_path, &history)?;
+    Ok(())
+}
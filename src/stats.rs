@@ -0,0 +1,1005 @@
+//! Command usage statistics persisted as JSON.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::history::{self, BuildHistory};
+use crate::output;
+
+pub const STATS_FILE: &str = "command_stats.json";
+
+/// Schema version written to the stats file.
+pub const STATS_SCHEMA_VERSION: u32 = 2;
+
+/// Version of this cargo-sleek build, recorded in the stats file.
+pub const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Environment variable overriding the stats file location.
+pub const STATS_FILE_ENV: &str = "SLEEK_STATS_FILE";
+
+/// Environment variable that turns command tracking off, e.g. `SLEEK_NO_STATS=1`.
+pub const NO_STATS_ENV: &str = "SLEEK_NO_STATS";
+
+/// Set from `tracking = false` in the config file.
+static TRACKING_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Stats files read with malformed entries that were dropped; each is warned about once
+/// and backed up before the first save that would lose those entries.
+static MALFORMED_FILES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Failure rates (in percent) below this are shown in green.
+pub const FAILURE_RATE_WARNING: f64 = 10.0;
+/// Failure rates (in percent) above this are shown in red.
+pub const FAILURE_RATE_CRITICAL: f64 = 50.0;
+
+/// Number of days shown by `stats --graph`.
+pub const GRAPH_DAYS: u32 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct CommandStats {
+    pub usage_count: u32,
+    pub last_used: u64,
+    /// When the command was first tracked; files written before this field existed
+    /// fall back to `last_used` on load.
+    #[serde(default)]
+    pub first_used: u64,
+    #[serde(default)]
+    pub success_count: u32,
+    #[serde(default)]
+    pub failure_count: u32,
+    /// Time spent in the command across all timed runs.
+    #[serde(default)]
+    pub total_duration_ms: u64,
+    /// Invocations per UTC day, keyed by `YYYY-MM-DD`.
+    #[serde(default)]
+    pub daily: HashMap<String, u32>,
+    /// Exit code of the most recent run through the executor; `None` when it was killed
+    /// by a signal or no run recorded one yet.
+    #[serde(default)]
+    pub last_exit_code: Option<i32>,
+    /// Whether that most recent run was killed by a signal.
+    #[serde(default)]
+    pub last_exit_signal: bool,
+}
+
+impl CommandStats {
+    /// Percentage of recorded runs that failed, if any outcome was recorded.
+    pub fn failure_rate(&self) -> Option<f64> {
+        let runs = self.success_count + self.failure_count;
+        (runs > 0).then(|| self.failure_count as f64 * 100.0 / runs as f64)
+    }
+
+    /// The last exit code for display: the code, `signal`, or `None` when unknown.
+    pub fn last_exit(&self) -> Option<String> {
+        if self.last_exit_signal {
+            Some("signal".to_string())
+        } else {
+            self.last_exit_code.map(|code| code.to_string())
+        }
+    }
+
+    /// Adds the counts of `other`, e.g. the same command tracked on another machine.
+    /// The last exit code is taken from whichever side ran last.
+    pub fn merge(&mut self, other: &CommandStats) {
+        if other.last_used > self.last_used {
+            self.last_exit_code = other.last_exit_code;
+            self.last_exit_signal = other.last_exit_signal;
+        }
+        // Counters saturate instead of overflowing when two huge files are merged.
+        self.usage_count = self.usage_count.saturating_add(other.usage_count);
+        self.success_count = self.success_count.saturating_add(other.success_count);
+        self.failure_count = self.failure_count.saturating_add(other.failure_count);
+        self.total_duration_ms = self
+            .total_duration_ms
+            .saturating_add(other.total_duration_ms);
+        self.last_used = self.last_used.max(other.last_used);
+        self.first_used = match (self.first_used, other.first_used) {
+            (0, theirs) => theirs,
+            (ours, 0) => ours,
+            (ours, theirs) => ours.min(theirs),
+        };
+        for (day, count) in &other.daily {
+            let total = self.daily.entry(day.clone()).or_default();
+            *total = total.saturating_add(*count);
+        }
+    }
+}
+
+/// Adds every command of `other` into `stats`.
+pub fn merge_stats(
+    stats: &mut HashMap<String, CommandStats>,
+    other: &HashMap<String, CommandStats>,
+) {
+    for (command, data) in other {
+        stats.entry(command.clone()).or_default().merge(data);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+/// Classifies a failure rate (in percent) against the severity thresholds.
+pub fn failure_severity(rate: f64) -> Severity {
+    if rate < FAILURE_RATE_WARNING {
+        Severity::Low
+    } else if rate <= FAILURE_RATE_CRITICAL {
+        Severity::Medium
+    } else {
+        Severity::High
+    }
+}
+
+/// Widest command name shown in the stats table before it is cut with an ellipsis.
+pub const MAX_COMMAND_WIDTH: usize = 32;
+
+/// Terminal columns `text` occupies (wide characters count double).
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Cuts `text` to at most `max` columns, ending in `…` when anything was removed.
+pub fn truncate_to_width(text: &str, max: usize) -> String {
+    if display_width(text) <= max {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut width = 0;
+    for c in text.chars() {
+        let w = UnicodeWidthChar::width(c).unwrap_or(0);
+        if width + w + 1 > max {
+            break;
+        }
+        out.push(c);
+        width += w;
+    }
+    out.push('…');
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// Pads `text` to `width` display columns.
+pub fn pad(text: &str, width: usize, align: Align) -> String {
+    let fill = " ".repeat(width.saturating_sub(display_width(text)));
+    match align {
+        Align::Left => format!("{text}{fill}"),
+        Align::Right => format!("{fill}{text}"),
+    }
+}
+
+fn paint_failure_rate(cell: String, data: &CommandStats) -> ColoredString {
+    match data.failure_rate().map(failure_severity) {
+        None => cell.normal(),
+        Some(Severity::Low) => cell.green(),
+        Some(Severity::Medium) => cell.yellow(),
+        Some(Severity::High) => cell.red(),
+    }
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+/// Optional columns of the stats table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatsColumns {
+    /// "First Used", before "Last Used".
+    pub first_used: bool,
+    /// "Last Exit": the most recent exit code, or `signal`.
+    pub last_exit: bool,
+}
+
+/// Renders the stats table, header first, with column widths computed from the data.
+/// `color` adds ANSI styling to the command and failure-rate columns. The share column
+/// is each row's part of all invocations in `rows`. `columns` picks the optional ones.
+pub fn render_stats_table(
+    rows: &[(&String, &CommandStats)],
+    color: bool,
+    columns: StatsColumns,
+) -> Vec<String> {
+    let mut headers = vec!["#", "Command", "Count", "Share", "Fail %"];
+    let mut align = vec![
+        Align::Left,
+        Align::Left,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+    ];
+    if columns.first_used {
+        headers.push("First Used");
+        align.push(Align::Right);
+    }
+    headers.push("Last Used");
+    align.push(Align::Right);
+    if columns.last_exit {
+        headers.push("Last Exit");
+        align.push(Align::Right);
+    }
+
+    let total: u64 = rows
+        .iter()
+        .map(|(_, data)| u64::from(data.usage_count))
+        .sum();
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, (cmd, data))| {
+            let mut row = vec![
+                (i + 1).to_string(),
+                truncate_to_width(cmd, MAX_COMMAND_WIDTH),
+                data.usage_count.to_string(),
+                format!(
+                    "{:.1}%",
+                    data.usage_count as f64 * 100.0 / total.max(1) as f64
+                ),
+                data.failure_rate()
+                    .map(|rate| format!("{:.1}%", rate))
+                    .unwrap_or_else(|| "-".to_string()),
+            ];
+            if columns.first_used {
+                row.push(format_timestamp(data.first_used));
+            }
+            row.push(format_timestamp(data.last_used));
+            if columns.last_exit {
+                row.push(data.last_exit().unwrap_or_else(|| "-".to_string()));
+            }
+            row
+        })
+        .collect();
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|col| {
+            cells
+                .iter()
+                .map(|row| display_width(&row[col]))
+                .chain([display_width(headers[col])])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let header = (0..headers.len())
+        .map(|col| pad(headers[col], widths[col], align[col]))
+        .collect::<Vec<_>>()
+        .join("  ");
+    let mut lines = vec![header];
+    for (row, (_, data)) in cells.iter().zip(rows) {
+        let padded: Vec<String> = (0..headers.len())
+            .map(|col| pad(&row[col], widths[col], align[col]))
+            .collect();
+        let line = if color {
+            padded
+                .into_iter()
+                .enumerate()
+                .map(|(col, cell)| match col {
+                    1 => cell.green().bold().to_string(),
+                    4 => paint_failure_rate(cell, data).to_string(),
+                    _ => cell,
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+        } else {
+            padded.join("  ")
+        };
+        lines.push(line);
+    }
+    lines
+}
+
+/// Resolves the stats file location: CLI flag, then `SLEEK_STATS_FILE` (passed in as
+/// `env`), then the config file, then [`STATS_FILE`] in the working directory.
+pub fn resolve_stats_path(
+    cli: Option<&Path>,
+    env: Option<&std::ffi::OsStr>,
+    config: Option<&Path>,
+) -> PathBuf {
+    cli.map(Path::to_path_buf)
+        .or_else(|| env.filter(|v| !v.is_empty()).map(PathBuf::from))
+        .or_else(|| config.map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from(STATS_FILE))
+}
+
+/// The resolved stats path made absolute against the working directory (`stats --where`).
+pub fn absolute_stats_path(path: &Path) -> Result<PathBuf> {
+    std::path::absolute(path).with_context(|| format!("Failed to resolve {}", path.display()))
+}
+
+/// Opens the directory holding the stats file in the platform's file manager.
+pub fn open_stats_dir(path: &Path) -> Result<()> {
+    let path = absolute_stats_path(path)?;
+    let Some(dir) = path.parent().filter(|dir| dir.is_dir()) else {
+        output::line(
+            format!(
+                "⚠️  The directory of {} does not exist yet; it is created on the first tracked command.",
+                path.display()
+            )
+            .yellow(),
+        );
+        return Ok(());
+    };
+    output::status(format!("📂 Opening {}", dir.display()));
+    opener::open(dir).with_context(|| format!("Failed to open {}", dir.display()))
+}
+
+/// Layout of the stats file since schema version 2. Version 1 files hold the bare
+/// `commands` map.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct StatsFile {
+    pub schema_version: u32,
+    /// cargo-sleek version that last wrote the file.
+    #[serde(default)]
+    pub tool_version: Option<String>,
+    pub commands: HashMap<String, CommandStats>,
+}
+
+/// Entries recorded before `first_used` existed count from their `last_used`.
+fn backfill_first_used(commands: &mut HashMap<String, CommandStats>) {
+    for data in commands.values_mut().filter(|data| data.first_used == 0) {
+        data.first_used = data.last_used;
+    }
+}
+
+/// Deserializes each command separately so one malformed entry (e.g. a string where a
+/// count belongs) is skipped instead of losing the whole file. The skipped entries are
+/// warned about the first time `path` is read in a process.
+fn parse_commands(
+    path: &Path,
+    entries: serde_json::Map<String, serde_json::Value>,
+) -> HashMap<String, CommandStats> {
+    let mut malformed = Vec::new();
+    let commands = entries
+        .into_iter()
+        .filter_map(|(command, value)| {
+            // serde would also accept a positional array for the struct.
+            let parsed = if value.is_object() {
+                serde_json::from_value::<CommandStats>(value).map_err(|err| err.to_string())
+            } else {
+                Err(format!("expected an object, found {}", value))
+            };
+            match parsed {
+                Ok(data) => Some((command, data)),
+                Err(err) => {
+                    malformed.push((command, err));
+                    None
+                }
+            }
+        })
+        .collect();
+
+    if !malformed.is_empty() {
+        let mut files = MALFORMED_FILES.lock().unwrap_or_else(|e| e.into_inner());
+        if !files.iter().any(|file| file == path) {
+            files.push(path.to_path_buf());
+            for (command, err) in malformed {
+                output::warning(
+                    format!(
+                        "⚠️  Skipping malformed entry `{}` in {}: {}",
+                        command,
+                        path.display(),
+                        err
+                    )
+                    .yellow(),
+                );
+            }
+        }
+    }
+    commands
+}
+
+/// Whether `path` was read with malformed entries that no backup holds yet.
+fn has_unsaved_malformed_entries(path: &Path) -> bool {
+    MALFORMED_FILES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .any(|file| file == path)
+}
+
+/// Reads the stats file in either schema version, `None` if missing or not a JSON object.
+/// Malformed command entries are dropped individually.
+pub fn read_stats_file(path: &Path) -> Option<StatsFile> {
+    let file = fs::File::open(path).ok()?;
+    let serde_json::Value::Object(mut root) =
+        serde_json::from_reader(std::io::BufReader::new(file)).ok()?
+    else {
+        return None;
+    };
+    let versioned = root.get("schema_version").is_some_and(|v| v.is_u64())
+        && root.get("commands").is_some_and(|v| v.is_object());
+    let mut file = if versioned {
+        let Some(serde_json::Value::Object(commands)) = root.remove("commands") else {
+            return None;
+        };
+        StatsFile {
+            schema_version: root["schema_version"].as_u64()? as u32,
+            tool_version: root
+                .get("tool_version")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            commands: parse_commands(path, commands),
+        }
+    } else {
+        StatsFile {
+            schema_version: 1,
+            tool_version: None,
+            commands: parse_commands(path, root),
+        }
+    };
+    backfill_first_used(&mut file.commands);
+    Some(file)
+}
+
+/// Whether `written` is a newer release than `current`.
+pub fn is_newer_version(written: &str, current: &str) -> bool {
+    match (
+        semver::Version::parse(written),
+        semver::Version::parse(current),
+    ) {
+        (Ok(written), Ok(current)) => written > current,
+        _ => false,
+    }
+}
+
+pub fn load_stats(path: &Path) -> HashMap<String, CommandStats> {
+    let Some(file) = read_stats_file(path) else {
+        return HashMap::new();
+    };
+    if let Some(written) = file
+        .tool_version
+        .as_deref()
+        .filter(|written| is_newer_version(written, TOOL_VERSION))
+    {
+        output::warning(
+            format!(
+                "⚠️  {} was written by cargo-sleek {}, newer than this {}; \
+                 consider upgrading with `cargo install cargo-sleek`",
+                path.display(),
+                written,
+                TOOL_VERSION
+            )
+            .yellow(),
+        );
+    }
+    file.commands
+}
+
+pub fn save_stats(path: &Path, stats: &HashMap<String, CommandStats>) -> Result<()> {
+    save_stats_as(path, stats, TOOL_VERSION)
+}
+
+/// Writes `stats` as if by cargo-sleek `tool_version`.
+pub fn save_stats_as(
+    path: &Path,
+    stats: &HashMap<String, CommandStats>,
+    tool_version: &str,
+) -> Result<()> {
+    if has_unsaved_malformed_entries(path) {
+        if let Some(backup) = backup_stats(path)? {
+            output::warning(
+                format!(
+                    "💾 Backed up {} to {} before dropping its malformed entries",
+                    path.display(),
+                    backup.display()
+                )
+                .yellow(),
+            );
+        }
+    }
+    let file = StatsFile {
+        schema_version: STATS_SCHEMA_VERSION,
+        tool_version: Some(tool_version.to_string()),
+        commands: stats.clone(),
+    };
+    let json = serde_json::to_string_pretty(&file)?;
+    fs::write(path, json).context("Failed to write stats file")
+}
+
+/// Key a command is tracked under: the subcommand alone, or with `track_args` the
+/// subcommand plus its cargo arguments (anything after `--` is left out).
+pub fn tracking_key(command: &str, args: &[String], track_args: bool) -> String {
+    if !track_args {
+        return command.to_string();
+    }
+    std::iter::once(command)
+        .chain(
+            args.iter()
+                .map(|arg| arg.trim())
+                .take_while(|arg| *arg != "--")
+                .filter(|arg| !arg.is_empty()),
+        )
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether an `SLEEK_NO_STATS` value asks for tracking to be off: anything but empty,
+/// `0` or `false`.
+pub fn no_stats_requested(value: Option<&std::ffi::OsStr>) -> bool {
+    value.is_some_and(|value| !matches!(value.to_str(), Some("" | "0" | "false")))
+}
+
+/// Turns tracking off for the rest of the process.
+pub fn disable_tracking() {
+    TRACKING_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether commands are tracked at all; when not, the stats file is never written.
+pub fn tracking_enabled() -> bool {
+    !TRACKING_DISABLED.load(Ordering::Relaxed)
+        && !no_stats_requested(std::env::var_os(NO_STATS_ENV).as_deref())
+}
+
+pub fn show_tracking_disabled() {
+    output::line(
+        format!(
+            "📊 Command tracking is disabled (`{}` or `tracking = false` in {}).",
+            NO_STATS_ENV,
+            crate::config::CONFIG_FILE
+        )
+        .yellow(),
+    );
+}
+
+pub fn track_command(path: &Path, command: &str) -> Result<()> {
+    record(path, command, None, |_| {})
+}
+
+/// Tracks a command together with whether it succeeded.
+pub fn track_command_result(path: &Path, command: &str, success: bool) -> Result<()> {
+    record(path, command, Some(success), |_| {})
+}
+
+/// Tracks a command with its exit code (`None` when a signal killed it) and how long
+/// it ran.
+pub fn track_command_exit(
+    path: &Path,
+    command: &str,
+    exit_code: Option<i32>,
+    duration: std::time::Duration,
+) -> Result<()> {
+    record(path, command, Some(exit_code == Some(0)), |entry| {
+        entry.total_duration_ms += duration.as_millis() as u64;
+        entry.last_exit_code = exit_code;
+        entry.last_exit_signal = exit_code.is_none();
+    })
+}
+
+/// Counts one run of `command` with its outcome; `update` records anything else known.
+fn record(
+    path: &Path,
+    command: &str,
+    success: Option<bool>,
+    update: impl FnOnce(&mut CommandStats),
+) -> Result<()> {
+    if !tracking_enabled() {
+        return Ok(());
+    }
+    let mut stats = load_stats(path);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let entry = stats.entry(command.to_string()).or_default();
+    entry.usage_count += 1;
+    if entry.first_used == 0 {
+        entry.first_used = now;
+    }
+    entry.last_used = now;
+    *entry
+        .daily
+        .entry(Utc::now().format("%Y-%m-%d").to_string())
+        .or_default() += 1;
+    match success {
+        Some(true) => entry.success_count += 1,
+        Some(false) => entry.failure_count += 1,
+        None => {}
+    }
+    update(entry);
+
+    save_stats(path, &stats)?;
+    Ok(())
+}
+
+/// Parses a `--since` value into a Unix timestamp: an ISO date (`2024-06-01`),
+/// an RFC 3339 instant, or a relative duration before `now` (`12h`, `7d`, `2w`).
+pub fn parse_since(input: &str, now: DateTime<Utc>) -> Result<u64> {
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        return Ok(midnight.timestamp().max(0) as u64);
+    }
+    if let Ok(instant) = DateTime::parse_from_rfc3339(input) {
+        return Ok(instant.timestamp().max(0) as u64);
+    }
+
+    let Some((split, unit)) = input.char_indices().next_back() else {
+        bail!("Invalid --since value: expected a date like 2024-06-01 or a duration like 7d");
+    };
+    let Ok(amount) = input[..split].parse::<i64>() else {
+        bail!("Invalid --since value `{input}`: expected a date like 2024-06-01 or a duration like 7d");
+    };
+    if amount <= 0 {
+        bail!("Invalid --since value `{input}`: the duration must be positive");
+    }
+    let duration = match unit {
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => bail!("Invalid --since unit in `{input}`: use h, d or w"),
+    };
+    let since = duration
+        .and_then(|duration| now.checked_sub_signed(duration))
+        .with_context(|| format!("Invalid --since value `{input}`: the duration is too long"))?;
+    Ok(since.timestamp().max(0) as u64)
+}
+
+/// Keeps only the commands last used at or after `since`.
+pub fn filter_since(
+    stats: HashMap<String, CommandStats>,
+    since: u64,
+) -> HashMap<String, CommandStats> {
+    stats
+        .into_iter()
+        .filter(|(_, data)| data.last_used >= since)
+        .collect()
+}
+
+/// Invocations of all commands per day for the `days` days ending at `today`, oldest
+/// first. Days without activity are included with a zero count.
+pub fn daily_totals(
+    stats: &HashMap<String, CommandStats>,
+    today: NaiveDate,
+    days: u32,
+) -> Vec<(NaiveDate, u32)> {
+    (0..days as i64)
+        .rev()
+        .map(|offset| {
+            let day = today - Duration::days(offset);
+            let key = day.format("%Y-%m-%d").to_string();
+            let total = stats.values().filter_map(|s| s.daily.get(&key)).sum();
+            (day, total)
+        })
+        .collect()
+}
+
+/// Bar length for each count, scaled so the largest fills `width`. Non-zero counts
+/// always get at least one cell.
+pub fn bar_lengths(counts: &[u32], width: usize) -> Vec<usize> {
+    let max = counts.iter().copied().max().unwrap_or(0);
+    counts
+        .iter()
+        .map(|&count| {
+            if count == 0 || max == 0 {
+                0
+            } else {
+                ((count as f64 / max as f64 * width as f64).round() as usize).max(1)
+            }
+        })
+        .collect()
+}
+
+fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(w), _)| w as usize)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(80)
+}
+
+/// Prints a bar chart of invocations per day over the last [`GRAPH_DAYS`] days.
+pub fn show_graph(path: &Path) -> Result<()> {
+    let stats = load_stats(path);
+    let totals = daily_totals(&stats, Utc::now().date_naive(), GRAPH_DAYS);
+    if totals.iter().all(|(_, count)| *count == 0) {
+        output::line(format!("📊 No activity recorded in the last {} days.", GRAPH_DAYS).yellow());
+        return Ok(());
+    }
+
+    let counts: Vec<u32> = totals.iter().map(|(_, count)| *count).collect();
+    let label_width = counts.iter().max().unwrap_or(&0).to_string().len();
+    // "MM-DD │ " prefix plus the count after the bar.
+    let width = terminal_width()
+        .saturating_sub(10 + label_width + 1)
+        .max(10);
+
+    output::line(
+        format!("📊 Daily Activity (last {} days):", GRAPH_DAYS)
+            .bold()
+            .cyan(),
+    );
+    for ((day, count), len) in totals.iter().zip(bar_lengths(&counts, width)) {
+        let label = if *count == 0 {
+            String::new()
+        } else {
+            format!(" {}", count)
+        };
+        output::line(format!(
+            "{} │ {}{}",
+            day.format("%m-%d"),
+            "█".repeat(len).green(),
+            label
+        ));
+    }
+    Ok(())
+}
+
+fn title(text: &str, color: bool) -> String {
+    if color {
+        text.bold().cyan().to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn notice(text: &str, color: bool) -> String {
+    if color {
+        text.yellow().to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Prints `lines`, or writes them without color to `output` (creating parent directories).
+fn emit(lines: &[String], output: Option<&Path>) -> Result<()> {
+    let Some(output) = output else {
+        lines.iter().for_each(output::line);
+        return Ok(());
+    };
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    fs::write(output, content).with_context(|| format!("Failed to write {}", output.display()))?;
+    output::line(format!("💾 Report written to {}", output.display()));
+    Ok(())
+}
+
+/// The `stats` report: title and table, or a notice when nothing was recorded.
+pub fn stats_report(
+    stats: &HashMap<String, CommandStats>,
+    color: bool,
+    columns: StatsColumns,
+) -> Vec<String> {
+    if stats.is_empty() {
+        return vec![notice("📊 No command usage data available.", color)];
+    }
+    let mut sorted: Vec<_> = stats.iter().collect();
+    // Ties go alphabetically so the order does not depend on HashMap iteration.
+    sorted.sort_by(|(a_cmd, a), (b_cmd, b)| {
+        b.usage_count
+            .cmp(&a.usage_count)
+            .then_with(|| a_cmd.cmp(b_cmd))
+    });
+
+    let mut lines = vec![title("📊 Most Used Cargo Commands:", color)];
+    lines.extend(render_stats_table(&sorted, color, columns));
+    lines
+}
+
+/// Serializes `stats` as TOML, one table per command in name order. Command names with
+/// spaces or other special characters become quoted keys.
+pub fn stats_to_toml(stats: &HashMap<String, CommandStats>) -> Result<String> {
+    let sorted: BTreeMap<&String, &CommandStats> = stats.iter().collect();
+    toml::to_string_pretty(&sorted).context("Failed to serialize stats as TOML")
+}
+
+/// Parses stats written by [`stats_to_toml`].
+pub fn stats_from_toml(text: &str) -> Result<HashMap<String, CommandStats>> {
+    let mut stats = toml::from_str(text).context("Failed to parse stats TOML")?;
+    backfill_first_used(&mut stats);
+    Ok(stats)
+}
+
+/// Shows the stats table (or TOML with `as_toml`), or writes it to `output` without color.
+/// `columns` picks the table's optional columns.
+pub fn show_stats(
+    path: &Path,
+    since: Option<u64>,
+    output: Option<&Path>,
+    as_toml: bool,
+    columns: StatsColumns,
+) -> Result<()> {
+    let mut stats = load_stats(path);
+    if let Some(since) = since {
+        stats = filter_since(stats, since);
+    }
+    if as_toml {
+        let text = stats_to_toml(&stats)?;
+        let lines: Vec<String> = text.lines().map(String::from).collect();
+        return emit(&lines, output);
+    }
+    emit(&stats_report(&stats, output.is_none(), columns), output)
+}
+
+/// A short overview of command usage and build times.
+pub fn summary_report(
+    stats: &HashMap<String, CommandStats>,
+    builds: &BuildHistory,
+    color: bool,
+) -> Vec<String> {
+    let mut lines = vec![title("📋 cargo-sleek summary", color)];
+    let total: u32 = stats.values().map(|s| s.usage_count).sum();
+    lines.push(format!(
+        "   Commands run:     {} ({} distinct)",
+        total,
+        stats.len()
+    ));
+    if let Some((cmd, data)) = stats
+        .iter()
+        .max_by(|a, b| a.1.usage_count.cmp(&b.1.usage_count).then(b.0.cmp(a.0)))
+    {
+        lines.push(format!(
+            "   Most used:        {} ({}×)",
+            cmd, data.usage_count
+        ));
+    }
+    let failures: u32 = stats.values().map(|s| s.failure_count).sum();
+    let outcomes: u32 = failures + stats.values().map(|s| s.success_count).sum::<u32>();
+    if outcomes > 0 {
+        lines.push(format!(
+            "   Failure rate:     {:.1}%",
+            failures as f64 * 100.0 / outcomes as f64
+        ));
+    }
+    match builds.runs.last() {
+        Some(last) => {
+            lines.push(format!("   Builds measured:  {}", builds.runs.len()));
+            lines.push(format!(
+                "   Last build:       {:.2}s (rolling average {:.2}s)",
+                last.duration_ms as f64 / 1000.0,
+                builds.rolling_average_ms as f64 / 1000.0
+            ));
+        }
+        None => lines.push(notice(
+            "   No builds measured yet (run `cargo sleek build-time`).",
+            color,
+        )),
+    }
+    lines
+}
+
+pub fn show_summary(stats_path: &Path, history_path: &Path, output: Option<&Path>) -> Result<()> {
+    let stats = load_stats(stats_path);
+    let builds = history::load_history(history_path);
+    emit(&summary_report(&stats, &builds, output.is_none()), output)
+}
+
+/// Number of reset backups kept next to the stats file.
+pub const MAX_BACKUPS: usize = 5;
+
+const BACKUP_SUFFIX: &str = ".bak.json";
+
+fn backup_prefix(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "command_stats".to_string());
+    format!("{stem}.")
+}
+
+/// Lists the backups of the stats file at `path`, oldest first.
+pub fn list_backups(path: &Path) -> Vec<PathBuf> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = backup_prefix(path);
+    let mut backups: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|p| {
+                    let name = p.file_name().unwrap_or_default().to_string_lossy();
+                    name.starts_with(&prefix) && name.ends_with(BACKUP_SUFFIX)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    backups.sort();
+    backups
+}
+
+/// Copies the stats file to a timestamped backup, pruning all but the newest [`MAX_BACKUPS`].
+pub fn backup_stats(path: &Path) -> Result<Option<PathBuf>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let stamp = Utc::now().format("%Y%m%d%H%M%S%3f");
+    let backup = path.with_file_name(format!("{}{stamp}{BACKUP_SUFFIX}", backup_prefix(path)));
+    fs::copy(path, &backup).context("Failed to back up stats file")?;
+    MALFORMED_FILES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|file| file != path);
+
+    let backups = list_backups(path);
+    for old in &backups[..backups.len().saturating_sub(MAX_BACKUPS)] {
+        let _ = fs::remove_file(old);
+    }
+    Ok(Some(backup))
+}
+
+/// Restores the most recent backup over the stats file, returning the backup used.
+pub fn restore_latest_backup(path: &Path) -> Result<PathBuf> {
+    let Some(latest) = list_backups(path).pop() else {
+        bail!("No stats backups found to restore");
+    };
+    fs::copy(&latest, path).context("Failed to restore stats backup")?;
+    Ok(latest)
+}
+
+/// Merges the stats file at `other` into the one at `path`. Legacy files are migrated
+/// on read; files from a newer schema are refused rather than half-understood.
+pub fn merge_stats_file(path: &Path, other: &Path) -> Result<()> {
+    let Some(incoming) = read_stats_file(other) else {
+        bail!("Could not read stats from {}", other.display());
+    };
+    if incoming.schema_version > STATS_SCHEMA_VERSION {
+        bail!(
+            "{} uses stats schema version {}, newer than the supported {}; \
+             upgrade cargo-sleek to merge it",
+            other.display(),
+            incoming.schema_version,
+            STATS_SCHEMA_VERSION
+        );
+    }
+    let mut stats = load_stats(path);
+    merge_stats(&mut stats, &incoming.commands);
+    save_stats(path, &stats)?;
+    output::line(format!(
+        "🔀 Merged {} command(s) from {} into {}",
+        incoming.commands.len(),
+        other.display(),
+        path.display()
+    ));
+    Ok(())
+}
+
+pub fn restore_stats(path: &Path) -> Result<()> {
+    let backup = restore_latest_backup(path)?;
+    output::line(format!(
+        "♻️  Restored command stats from {}",
+        backup.display()
+    ));
+    Ok(())
+}
+
+/// Empties the stats file after backing it up. With tracking off the file is left as it
+/// is, since nothing would be recorded into it afterwards.
+pub fn reset_stats(path: &Path, force: bool) -> Result<()> {
+    if !tracking_enabled() {
+        show_tracking_disabled();
+        output::line("❌ Nothing was reset.");
+        return Ok(());
+    }
+    if force {
+        if let Some(backup) = backup_stats(path)? {
+            output::line(format!("💾 Backup saved to {}", backup.display()));
+        }
+        fs::write(path, "{}")?;
+        output::line("✅ Command stats have been reset!");
+    } else {
+        output::line("⚠️ Run with `cargo sleek reset --force` to confirm.");
+    }
+    Ok(())
+}
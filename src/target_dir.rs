@@ -0,0 +1,268 @@
+//! Size breakdown of the target directory and age-based cleanup (`target-size`, `clean-smart`).
+
+use anyhow::{Context, Result};
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::output;
+use crate::performance::{dir_size, format_size};
+use crate::prompt;
+
+/// Default age, as a `--since`-style duration, past which `clean-smart` deletes artifacts.
+pub const DEFAULT_OLDER_THAN: &str = "30d";
+
+/// Subdirectories of a profile directory whose entries are individually cleanable.
+const CLEANABLE_AREAS: &[&str] = &["deps", "incremental", "build", ".fingerprint"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AreaSize {
+    pub name: String,
+    pub bytes: u64,
+}
+
+/// A profile output directory such as `debug` or `x86_64-unknown-linux-gnu/release`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileSize {
+    pub name: String,
+    pub bytes: u64,
+    pub areas: Vec<AreaSize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TargetReport {
+    pub total: u64,
+    pub profiles: Vec<ProfileSize>,
+    /// Top-level areas outside profiles, e.g. `doc` and `cargo-timings`.
+    pub other: Vec<AreaSize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaleEntry {
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Newest modification time within the entry, as a Unix timestamp.
+    pub modified: u64,
+}
+
+fn is_profile_dir(path: &Path) -> bool {
+    path.join("deps").is_dir() || path.join(".fingerprint").is_dir()
+}
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn profile_size(name: String, dir: &Path) -> ProfileSize {
+    let mut areas = Vec::new();
+    let mut loose = 0;
+    for entry in sorted_entries(dir) {
+        if entry.is_dir() {
+            areas.push(AreaSize {
+                name: file_name(&entry),
+                bytes: dir_size(&entry),
+            });
+        } else {
+            loose += dir_size(&entry);
+        }
+    }
+    if loose > 0 {
+        areas.push(AreaSize {
+            name: "(outputs)".to_string(),
+            bytes: loose,
+        });
+    }
+    areas.sort_by_key(|area| std::cmp::Reverse(area.bytes));
+    ProfileSize {
+        name,
+        bytes: areas.iter().map(|area| area.bytes).sum(),
+        areas,
+    }
+}
+
+/// Sizes per profile (including cross-compile `<triple>/<profile>` directories) and per area.
+pub fn scan_target(target_dir: &Path) -> TargetReport {
+    let mut report = TargetReport {
+        total: dir_size(target_dir),
+        ..Default::default()
+    };
+    for entry in sorted_entries(target_dir)
+        .into_iter()
+        .filter(|e| e.is_dir())
+    {
+        let name = file_name(&entry);
+        if is_profile_dir(&entry) {
+            report.profiles.push(profile_size(name, &entry));
+            continue;
+        }
+        let nested: Vec<PathBuf> = sorted_entries(&entry)
+            .into_iter()
+            .filter(|e| e.is_dir() && is_profile_dir(e))
+            .collect();
+        if nested.is_empty() {
+            report.other.push(AreaSize {
+                bytes: dir_size(&entry),
+                name,
+            });
+        } else {
+            for profile in nested {
+                let label = format!("{}/{}", name, file_name(&profile));
+                report.profiles.push(profile_size(label, &profile));
+            }
+        }
+    }
+    report.profiles.sort_by_key(|p| std::cmp::Reverse(p.bytes));
+    report.other.sort_by_key(|a| std::cmp::Reverse(a.bytes));
+    report
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Newest modification time of the files at or below `path`. Directories only count
+/// when empty, since their own mtime changes whenever an entry is added.
+fn newest_mtime(path: &Path) -> u64 {
+    if path.is_dir() {
+        if let Some(newest) = sorted_entries(path).iter().map(|e| newest_mtime(e)).max() {
+            return newest;
+        }
+    }
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(unix_secs)
+        .unwrap_or(0)
+}
+
+/// Entries of the deps/incremental/build/fingerprint areas not modified since `cutoff`.
+pub fn find_stale(target_dir: &Path, cutoff: u64) -> Vec<StaleEntry> {
+    let mut profiles: Vec<PathBuf> = Vec::new();
+    for entry in sorted_entries(target_dir)
+        .into_iter()
+        .filter(|e| e.is_dir())
+    {
+        if is_profile_dir(&entry) {
+            profiles.push(entry);
+        } else {
+            profiles.extend(
+                sorted_entries(&entry)
+                    .into_iter()
+                    .filter(|e| e.is_dir() && is_profile_dir(e)),
+            );
+        }
+    }
+
+    profiles
+        .iter()
+        .flat_map(|profile| CLEANABLE_AREAS.iter().map(move |area| profile.join(area)))
+        .flat_map(|area| sorted_entries(&area))
+        .filter_map(|path| {
+            let modified = newest_mtime(&path);
+            (modified < cutoff).then(|| StaleEntry {
+                bytes: dir_size(&path),
+                path,
+                modified,
+            })
+        })
+        .collect()
+}
+
+fn print_report(target_dir: &Path, report: &TargetReport) {
+    output::line(
+        format!(
+            "🗄️  {} — {}",
+            target_dir.display(),
+            format_size(report.total)
+        )
+        .bold()
+        .cyan(),
+    );
+    for profile in &report.profiles {
+        output::line(format!(
+            "\n   {:<36} {:>12}",
+            profile.name.bold(),
+            format_size(profile.bytes)
+        ));
+        for area in &profile.areas {
+            output::line(format!(
+                "     {:<34} {:>12}",
+                area.name,
+                format_size(area.bytes)
+            ));
+        }
+    }
+    if !report.other.is_empty() {
+        output::line("");
+        for area in &report.other {
+            output::line(format!(
+                "   {:<36} {:>12}",
+                area.name,
+                format_size(area.bytes)
+            ));
+        }
+    }
+}
+
+pub fn show_target_size(target_dir: &Path) -> Result<()> {
+    if !target_dir.is_dir() {
+        output::line("🗄️  No target directory yet.".yellow());
+        return Ok(());
+    }
+    print_report(target_dir, &scan_target(target_dir));
+    Ok(())
+}
+
+/// Deletes artifacts not touched since `cutoff`, after confirmation unless `yes` is set.
+pub fn clean_smart(target_dir: &Path, cutoff: u64, dry_run: bool, yes: bool) -> Result<()> {
+    show_target_size(target_dir)?;
+    let stale = find_stale(target_dir, cutoff);
+    let total: u64 = stale.iter().map(|entry| entry.bytes).sum();
+    if stale.is_empty() {
+        output::line(format!("\n{}", "✅ No stale artifacts found.".green()));
+        return Ok(());
+    }
+    output::line(format!(
+        "\n🧹 {} stale artifact(s) totalling {}",
+        stale.len(),
+        format_size(total).bold()
+    ));
+    if dry_run {
+        for entry in &stale {
+            output::line(format!(
+                "   • {} ({})",
+                entry.path.display(),
+                format_size(entry.bytes)
+            ));
+        }
+        output::line("🔍 Dry run: nothing was deleted.".yellow());
+        return Ok(());
+    }
+    if !prompt::confirm("Delete them?", yes)? {
+        output::line("❌ Nothing deleted.".yellow());
+        return Ok(());
+    }
+    for entry in &stale {
+        let removed = if entry.path.is_dir() {
+            fs::remove_dir_all(&entry.path)
+        } else {
+            fs::remove_file(&entry.path)
+        };
+        removed.with_context(|| format!("Failed to delete {}", entry.path.display()))?;
+    }
+    output::line(format!("✅ Freed {}", format_size(total)).green());
+    Ok(())
+}
@@ -0,0 +1,181 @@
+//! Test durations captured from `cargo test` output (`test-times`).
+
+use anyhow::Result;
+use chrono::DateTime;
+use colored::*;
+use serde::Deserialize;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::history::{self, TestRunRecord, TestTime};
+use crate::output;
+
+/// Number of slowest tests kept per run.
+pub const KEPT_TESTS: usize = 50;
+
+/// Size of the "slowest tests" list that new entries are flagged against.
+pub const TOP_TESTS: usize = 10;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParsedTests {
+    /// Per-test times, when libtest reported them (`--report-time` or JSON format).
+    pub tests: Vec<TestTime>,
+    /// Time per test binary from the `finished in` summary lines.
+    pub suites: Vec<TestTime>,
+}
+
+#[derive(Deserialize)]
+struct JsonTestEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    name: String,
+    exec_time: Option<f64>,
+}
+
+fn parse_seconds(text: &str) -> Option<f64> {
+    text.trim().strip_suffix('s')?.parse().ok()
+}
+
+/// Extracts test and suite durations from `cargo test` output.
+///
+/// Understands libtest's JSON events (`--format json --report-time`), the plain
+/// `test name ... ok <0.012s>` lines of `--report-time`, and the per-binary
+/// `test result: ... finished in 0.45s` summaries printed by stable toolchains.
+pub fn parse_test_output(output: &str) -> ParsedTests {
+    let mut parsed = ParsedTests::default();
+    let mut suite = String::from("tests");
+    for line in output.lines().map(str::trim) {
+        if line.starts_with('{') {
+            if let Ok(event) = serde_json::from_str::<JsonTestEvent>(line) {
+                if let (true, Some(seconds)) = (event.kind == "test", event.exec_time) {
+                    parsed.tests.push(TestTime {
+                        name: event.name,
+                        seconds,
+                    });
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("Running ") {
+            suite = rest.split(" (").next().unwrap_or(rest).to_string();
+        } else if let Some(rest) = line.strip_prefix("Doc-tests ") {
+            suite = format!("doc-tests {}", rest);
+        } else if let Some(rest) = line.strip_prefix("test result:") {
+            if let Some(seconds) = rest.rsplit("finished in ").next().and_then(parse_seconds) {
+                parsed.suites.push(TestTime {
+                    name: suite.clone(),
+                    seconds,
+                });
+            }
+        } else if let Some(rest) = line.strip_prefix("test ") {
+            let (Some((name, outcome)), true) = (rest.split_once(" ... "), rest.ends_with("s>"))
+            else {
+                continue;
+            };
+            let time = outcome.rsplit('<').next().and_then(|t| t.strip_suffix('>'));
+            if let Some(seconds) = time.and_then(parse_seconds) {
+                parsed.tests.push(TestTime {
+                    name: name.to_string(),
+                    seconds,
+                });
+            }
+        }
+    }
+    parsed
+}
+
+/// Builds the history record for one `cargo test` run.
+pub fn test_run_record(output: &str, duration: Duration, timestamp: u64) -> TestRunRecord {
+    let parsed = parse_test_output(output);
+    let suite_secs: f64 = parsed.suites.iter().map(|s| s.seconds).sum();
+    let mut slowest = if parsed.tests.is_empty() {
+        parsed.suites
+    } else {
+        parsed.tests
+    };
+    slowest.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+    slowest.truncate(KEPT_TESTS);
+    TestRunRecord {
+        timestamp,
+        duration_ms: duration.as_millis() as u64,
+        suite_ms: (suite_secs * 1000.0).round() as u64,
+        slowest,
+    }
+}
+
+pub fn record_test_run(history_path: &Path, output: &str, duration: Duration) -> Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let mut history = history::load_history(history_path);
+    history
+        .tests
+        .push(test_run_record(output, duration, timestamp));
+    history::save_history(history_path, &history)
+}
+
+/// Names in the top [`TOP_TESTS`] of `current` that were not in the top of `previous`.
+pub fn new_in_top<'a>(current: &'a TestRunRecord, previous: &TestRunRecord) -> Vec<&'a str> {
+    let before: Vec<&str> = previous
+        .slowest
+        .iter()
+        .take(TOP_TESTS)
+        .map(|t| t.name.as_str())
+        .collect();
+    current
+        .slowest
+        .iter()
+        .take(TOP_TESTS)
+        .map(|t| t.name.as_str())
+        .filter(|name| !before.contains(name))
+        .collect()
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+pub fn show_test_times(history_path: &Path, top: usize) -> Result<()> {
+    let history = history::load_history(history_path);
+    let Some(latest) = history.tests.last() else {
+        output::line(
+            "🧪 No test runs recorded yet. Run `cargo sleek --record-test-times test` or set \
+             `record_test_times = true` in .sleek.toml first."
+                .yellow(),
+        );
+        return Ok(());
+    };
+    let previous = history
+        .tests
+        .len()
+        .checked_sub(2)
+        .map(|i| &history.tests[i]);
+    let fresh = previous.map(|p| new_in_top(latest, p)).unwrap_or_default();
+
+    output::line(
+        format!("🐢 Slowest tests ({}):", format_timestamp(latest.timestamp))
+            .bold()
+            .cyan(),
+    );
+    for (i, test) in latest.slowest.iter().take(top).enumerate() {
+        let row = format!("{:<4} {:<60} {:>9.3}s", i + 1, test.name, test.seconds);
+        if fresh.contains(&test.name.as_str()) {
+            output::line(format!("{} {}", row.yellow(), "🆕 new in top 10".yellow()));
+        } else {
+            output::line(row);
+        }
+    }
+
+    output::line(format!("\n{}", "📈 Suite time trend:".bold().cyan()));
+    let start = history.tests.len().saturating_sub(history::ROLLING_WINDOW);
+    for run in &history.tests[start..] {
+        output::line(format!(
+            "   {:<20} {:>9.2}s run, {:>9.2}s total",
+            format_timestamp(run.timestamp),
+            run.suite_ms as f64 / 1000.0,
+            run.duration_ms as f64 / 1000.0
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,296 @@
+//! Per-crate compile times from cargo's `--timings` data.
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::output;
+
+/// Report written by `cargo build --timings`, relative to the target directory.
+pub const TIMING_REPORT: &str = "cargo-timings/cargo-timing.html";
+
+/// Number of crates shown when neither `--top` nor `--all` is given.
+pub const DEFAULT_TOP: usize = 10;
+
+/// One compilation unit, as recorded in the `UNIT_DATA` table of the timing report.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UnitTiming {
+    #[serde(default)]
+    pub i: usize,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub target: String,
+    #[serde(default)]
+    pub start: f64,
+    pub duration: f64,
+    /// Seconds until the crate's metadata was ready, i.e. the frontend share.
+    #[serde(default)]
+    pub rmeta_time: Option<f64>,
+    #[serde(default, alias = "unlocked_units")]
+    pub unblocked_units: Vec<usize>,
+    #[serde(default, alias = "unlocked_rmeta_units")]
+    pub unblocked_rmeta_units: Vec<usize>,
+    /// Compiler phases (`frontend`, `codegen`, `link`, ...), recorded by nightly cargo
+    /// with `-Zsection-timings`.
+    #[serde(default)]
+    pub sections: Option<Vec<(String, SectionData)>>,
+}
+
+/// Start and end of one compiler phase, in seconds relative to the unit's start.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct SectionData {
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Compile time of a crate, summed over its units (lib, build script, ...).
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CrateTiming {
+    pub name: String,
+    pub version: String,
+    pub seconds: f64,
+    pub frontend: Option<f64>,
+    pub codegen: Option<f64>,
+    /// Whether one of the crate's units lies on the build's critical path.
+    pub critical: bool,
+}
+
+#[derive(Deserialize)]
+struct TimingTarget {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct TimingMessage {
+    target: TimingTarget,
+    duration: f64,
+    #[serde(default)]
+    rmeta_time: Option<f64>,
+    #[serde(default)]
+    sections: Option<Vec<(String, SectionData)>>,
+}
+
+/// Extracts `timing-info` messages emitted by `--timings=json` (nightly cargo only).
+///
+/// The stream carries no dependency edges, so the result has no critical path.
+pub fn parse_timing_messages(output: &str) -> Vec<UnitTiming> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|msg| msg["reason"] == "timing-info")
+        .filter_map(|msg| serde_json::from_value::<TimingMessage>(msg).ok())
+        .enumerate()
+        .map(|(i, msg)| UnitTiming {
+            i,
+            name: msg.target.name,
+            duration: msg.duration,
+            rmeta_time: msg.rmeta_time,
+            sections: msg.sections,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Extracts the `UNIT_DATA` table embedded in `cargo-timing.html`.
+pub fn parse_timing_html(html: &str) -> Option<Vec<UnitTiming>> {
+    let start = html.find("const UNIT_DATA =")? + "const UNIT_DATA =".len();
+    serde_json::Deserializer::from_str(&html[start..])
+        .into_iter::<Vec<UnitTiming>>()
+        .next()?
+        .ok()
+}
+
+/// Indices of the units on the critical path: starting from the unit that finished last,
+/// repeatedly step back to the latest-finishing unit that unblocked it. Empty when the
+/// units carry no dependency edges.
+pub fn critical_path(units: &[UnitTiming]) -> HashSet<usize> {
+    let mut path = HashSet::new();
+    let has_edges = units
+        .iter()
+        .any(|u| !u.unblocked_units.is_empty() || !u.unblocked_rmeta_units.is_empty());
+    if !has_edges {
+        return path;
+    }
+    let finish = |u: &UnitTiming| u.start + u.duration;
+    let mut current = units.iter().max_by(|a, b| finish(a).total_cmp(&finish(b)));
+    while let Some(unit) = current {
+        if !path.insert(unit.i) {
+            break;
+        }
+        current = units
+            .iter()
+            .filter_map(|u| {
+                if u.unblocked_units.contains(&unit.i) {
+                    Some((u, finish(u)))
+                } else if u.unblocked_rmeta_units.contains(&unit.i) {
+                    Some((u, u.start + u.rmeta_time.unwrap_or(u.duration)))
+                } else {
+                    None
+                }
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(u, _)| u);
+    }
+    path
+}
+
+/// Sums units per crate and sorts by compile time, slowest first. Fresh units, which
+/// cargo lists with a zero duration, are skipped.
+pub fn crate_timings(units: &[UnitTiming]) -> Vec<CrateTiming> {
+    let critical = critical_path(units);
+    let mut crates: BTreeMap<(&str, &str), CrateTiming> = BTreeMap::new();
+    for unit in units.iter().filter(|u| u.duration > 0.0) {
+        let entry = crates
+            .entry((&unit.name, &unit.version))
+            .or_insert_with(|| CrateTiming {
+                name: unit.name.clone(),
+                version: unit.version.clone(),
+                seconds: 0.0,
+                frontend: None,
+                codegen: None,
+                critical: false,
+            });
+        entry.seconds += unit.duration;
+        if let Some(rmeta) = unit.rmeta_time {
+            *entry.frontend.get_or_insert(0.0) += rmeta;
+            *entry.codegen.get_or_insert(0.0) += (unit.duration - rmeta).max(0.0);
+        }
+        entry.critical |= critical.contains(&unit.i);
+    }
+    let mut crates: Vec<_> = crates.into_values().collect();
+    crates.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+    crates
+}
+
+/// Total seconds spent linking, summed over the `link` sections of all units. `None`
+/// when the data has no section timings (stable cargo).
+pub fn link_seconds(units: &[UnitTiming]) -> Option<f64> {
+    let mut sections = units.iter().filter_map(|u| u.sections.as_ref()).peekable();
+    sections.peek()?;
+    Some(
+        sections
+            .flatten()
+            .filter(|(name, _)| name == "link")
+            .map(|(_, data)| (data.end - data.start).max(0.0))
+            .sum(),
+    )
+}
+
+/// The crates among `direct` dependencies, keeping the slowest-first order of `crates`.
+pub fn heaviest_direct_deps<'a>(
+    crates: &'a [CrateTiming],
+    direct: &[String],
+) -> Vec<&'a CrateTiming> {
+    crates
+        .iter()
+        .filter(|krate| direct.contains(&krate.name))
+        .collect()
+}
+
+/// Loads unit timings from the last `--timings` report in `target_dir`.
+pub fn load_timing_report(target_dir: &Path) -> Option<Vec<UnitTiming>> {
+    let html = fs::read_to_string(target_dir.join(TIMING_REPORT)).ok()?;
+    parse_timing_html(&html)
+}
+
+/// Newest `cargo-timing-*.html` report in `dir` (usually `target/cargo-timings`) by
+/// modification time.
+pub fn newest_timing_report(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("cargo-timing-") && name.ends_with(".html")
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Opens the newest timings report under `target_dir` in the default browser.
+pub fn open_timing_report(target_dir: &Path) -> Result<()> {
+    let dir = target_dir.join("cargo-timings");
+    let Some(report) = newest_timing_report(&dir) else {
+        output::line(format!("⚠️  No timings report found in {}", dir.display()).yellow());
+        return Ok(());
+    };
+    output::line(format!("🌐 Opening {}", report.display()));
+    opener::open(&report).with_context(|| format!("Failed to open {}", report.display()))
+}
+
+fn format_secs(secs: Option<f64>) -> String {
+    secs.map(|s| format!("{:.2}s", s))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Prints the `top` slowest crates (all when `None`).
+pub fn show_crate_timings(crates: &[CrateTiming], top: Option<usize>) -> Result<()> {
+    if crates.is_empty() {
+        output::line("🕓 No per-crate timings available (everything was fresh?)".yellow());
+        return Ok(());
+    }
+    let shown = top.unwrap_or(crates.len()).min(crates.len());
+    output::line(
+        format!("🐢 Slowest crates ({} of {}):", shown, crates.len())
+            .bold()
+            .cyan(),
+    );
+    output::line(format!(
+        "{:<4} {:<30} {:>10} {:>10} {:>10}",
+        "#", "Crate", "Total", "Frontend", "Codegen"
+    ));
+    for (i, krate) in crates.iter().take(shown).enumerate() {
+        let name = if krate.version.is_empty() {
+            krate.name.clone()
+        } else {
+            format!("{} v{}", krate.name, krate.version)
+        };
+        let row = format!(
+            "{:<4} {:<30} {:>10} {:>10} {:>10}",
+            i + 1,
+            name,
+            format_secs(Some(krate.seconds)),
+            format_secs(krate.frontend),
+            format_secs(krate.codegen)
+        );
+        if krate.critical {
+            output::line(format!("{} {}", row.red(), "◀ critical path".red()));
+        } else {
+            output::line(row);
+        }
+    }
+    Ok(())
+}
+
+/// Prints the `top` direct dependencies that took longest to compile.
+pub fn show_heaviest_deps(crates: &[CrateTiming], direct: &[String], top: Option<usize>) {
+    let heaviest = heaviest_direct_deps(crates, direct);
+    if heaviest.is_empty() {
+        return;
+    }
+    let total: f64 = heaviest.iter().map(|krate| krate.seconds).sum();
+    output::line("\n📦 Your heaviest direct dependencies:".bold().cyan());
+    for (i, krate) in heaviest
+        .iter()
+        .take(top.unwrap_or(heaviest.len()))
+        .enumerate()
+    {
+        output::line(format!(
+            "{:<4} {:<30} {:>10} {:>6.1}%",
+            i + 1,
+            krate.name,
+            format_secs(Some(krate.seconds)),
+            krate.seconds * 100.0 / total
+        ));
+    }
+}
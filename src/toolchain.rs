@@ -0,0 +1,70 @@
+//! Rustup toolchains used to compare builds across compiler releases.
+
+use anyhow::{bail, Result};
+use colored::*;
+use std::process::{Command, Stdio};
+
+use crate::executor;
+use crate::output;
+use crate::prompt;
+
+/// Toolchain names from `rustup toolchain list`, without the `(default)` markers.
+pub fn parse_toolchain_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(String::from)
+        .collect()
+}
+
+/// Whether `requested` (e.g. `stable` or `1.74.0`) is among `installed`, which carry the
+/// host triple as in `stable-x86_64-unknown-linux-gnu`.
+pub fn is_installed(requested: &str, installed: &[String]) -> bool {
+    installed.iter().any(|name| {
+        name == requested
+            || name
+                .strip_prefix(requested)
+                .is_some_and(|rest| rest.starts_with('-'))
+    })
+}
+
+fn installed_toolchains() -> Result<Vec<String>> {
+    let output = Command::new("rustup")
+        .args(["toolchain", "list"])
+        .output()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute rustup"))?;
+    Ok(parse_toolchain_list(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Checks that each of `toolchains` is installed, offering `rustup install` for missing
+/// ones. Returns the toolchains that are available afterwards.
+pub fn ensure_installed(toolchains: &[String], assume_yes: bool) -> Result<Vec<String>> {
+    let installed = installed_toolchains()?;
+    let mut available = Vec::new();
+    for toolchain in toolchains {
+        if is_installed(toolchain, &installed) {
+            available.push(toolchain.clone());
+            continue;
+        }
+        let question = format!(
+            "📥 Toolchain `{}` is not installed. Install it with `rustup install {}`?",
+            toolchain, toolchain
+        );
+        if !prompt::confirm(&question, assume_yes)? {
+            output::line(format!("⏭️  Skipping `{}`", toolchain).yellow());
+            continue;
+        }
+        let status = Command::new("rustup")
+            .args(["install", "--profile", "minimal", toolchain])
+            .stdout(Stdio::null())
+            .status()
+            .map_err(|err| executor::spawn_error(err, "Failed to execute rustup install"))?;
+        if !status.success() {
+            bail!("rustup install {} failed", toolchain);
+        }
+        available.push(toolchain.clone());
+    }
+    Ok(available)
+}
@@ -0,0 +1,241 @@
+//! Finds the fastest `--jobs` value by timing builds (`tune-jobs`).
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use toml_edit::{value, DocumentMut, Item, Table};
+
+use crate::executor;
+use crate::history::{self, BuildSlice};
+use crate::metadata;
+use crate::output;
+use crate::performance;
+use crate::prompt;
+
+/// Cargo configuration file `--apply` writes to, relative to the project.
+pub const CARGO_CONFIG: &str = ".cargo/config.toml";
+
+#[derive(Debug, Clone)]
+pub struct TuneOptions {
+    pub jobs: Vec<usize>,
+    pub samples: usize,
+    /// Measure clean builds instead of incremental rebuilds.
+    pub clean: bool,
+    pub apply: bool,
+    pub yes: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobsResult {
+    pub jobs: usize,
+    pub samples: Vec<Duration>,
+}
+
+impl JobsResult {
+    pub fn median(&self) -> Duration {
+        median(&self.samples)
+    }
+}
+
+/// Job counts to try for a machine with `cpus` logical CPUs: a spread from half the
+/// CPUs up to slight oversubscription.
+pub fn default_job_counts(cpus: usize) -> Vec<usize> {
+    let cpus = cpus.max(1);
+    let mut counts: Vec<usize> = [
+        cpus / 2,
+        cpus * 3 / 4,
+        cpus.saturating_sub(1),
+        cpus,
+        cpus + 2,
+    ]
+    .into_iter()
+    .filter(|&n| n > 0)
+    .collect();
+    counts.sort_unstable();
+    counts.dedup();
+    counts
+}
+
+pub fn median(samples: &[Duration]) -> Duration {
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    match sorted.len() {
+        0 => Duration::ZERO,
+        n if n % 2 == 1 => sorted[n / 2],
+        n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+    }
+}
+
+/// Job count with the lowest median; ties go to fewer jobs.
+pub fn recommend(results: &[JobsResult]) -> Option<usize> {
+    results
+        .iter()
+        .filter(|r| !r.samples.is_empty())
+        .min_by_key(|r| (r.median(), r.jobs))
+        .map(|r| r.jobs)
+}
+
+/// Sets `build.jobs` in a cargo config document, keeping the rest of it intact.
+pub fn apply_jobs(config: &str, jobs: usize) -> Result<String> {
+    let mut doc: DocumentMut = config.parse().context("Failed to parse cargo config")?;
+    let build = doc
+        .entry("build")
+        .or_insert_with(|| Item::Table(Table::new()))
+        .as_table_like_mut()
+        .context("`build` in cargo config is not a table")?;
+    build.insert("jobs", value(jobs as i64));
+    Ok(doc.to_string())
+}
+
+fn timed_build(jobs: usize) -> Result<Option<Duration>> {
+    let start = Instant::now();
+    let output = Command::new(executor::cargo_bin())
+        .args(["build", "--quiet", "--jobs", &jobs.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|err| executor::spawn_error(err, "Failed to execute cargo build"))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(start.elapsed()))
+}
+
+fn print_results(results: &[JobsResult], best: Option<usize>) {
+    output::line(format!("\n{:<6} {:>8} {:>12}", "Jobs", "Samples", "Median"));
+    for result in results {
+        let row = format!(
+            "{:<6} {:>8} {:>12.2?}",
+            result.jobs,
+            result.samples.len(),
+            result.median()
+        );
+        if Some(result.jobs) == best {
+            output::line(format!("{} {}", row.green().bold(), "◀ fastest".green()));
+        } else {
+            output::line(row);
+        }
+    }
+}
+
+pub fn tune_jobs(project_dir: &Path, history_path: &Path, options: &TuneOptions) -> Result<()> {
+    if options.jobs.is_empty() || options.samples == 0 {
+        bail!("Nothing to measure: need at least one job count and one sample");
+    }
+    let builds = options.jobs.len() * options.samples;
+    let kind = if options.clean { "cold" } else { "warm" };
+    let estimate = history::load_history(history_path)
+        .median_ms(&BuildSlice {
+            kind: Some(kind.to_string()),
+            ..Default::default()
+        })
+        .map(|ms| Duration::from_millis(ms * builds as u64));
+    output::status(
+        format!(
+            "⏱️  Running {} {} builds ({} job counts × {} samples){}.",
+            builds,
+            if options.clean {
+                "clean"
+            } else {
+                "incremental"
+            },
+            options.jobs.len(),
+            options.samples,
+            estimate
+                .map(|d| format!(", roughly {:.0?} based on history", d))
+                .unwrap_or_default()
+        )
+        .yellow(),
+    );
+    output::status("   Press Ctrl-C to stop early; finished measurements are kept.");
+
+    if options.clean
+        && !prompt::confirm(
+            "🧹 Clean builds delete the target directory before every sample. Continue?",
+            options.yes,
+        )?
+    {
+        output::line("❌ Cancelled.".yellow());
+        return Ok(());
+    }
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&interrupted);
+    ctrlc::set_handler(move || flag.store(true, Ordering::SeqCst))
+        .context("Failed to install Ctrl-C handler")?;
+
+    let metadata = metadata::cargo_metadata(project_dir)?;
+    if !options.clean {
+        output::status("🔨 Warm-up build...");
+        if timed_build(options.jobs[0])?.is_none() {
+            bail!("Warm-up build failed; fix the build before tuning");
+        }
+    }
+
+    let mut results: Vec<JobsResult> = Vec::new();
+    'outer: for &jobs in &options.jobs {
+        let mut result = JobsResult {
+            jobs,
+            samples: Vec::new(),
+        };
+        for sample in 1..=options.samples {
+            if options.clean {
+                performance::cargo_clean()?;
+            } else {
+                performance::touch_entry_point(&metadata)?;
+            }
+            print!(
+                "   -j{:<3} sample {}/{} ... ",
+                jobs, sample, options.samples
+            );
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            let duration = timed_build(jobs)?;
+            if interrupted.load(Ordering::SeqCst) {
+                println!("{}", "interrupted".yellow());
+                if !result.samples.is_empty() {
+                    results.push(result);
+                }
+                break 'outer;
+            }
+            match duration {
+                Some(duration) => {
+                    println!("{:.2?}", duration);
+                    result.samples.push(duration);
+                }
+                None => println!("{}", "build failed".red()),
+            }
+        }
+        results.push(result);
+    }
+
+    let best = recommend(&results);
+    print_results(&results, best);
+    let Some(best) = best else {
+        output::line("⚠️  No successful builds to compare.".yellow());
+        return Ok(());
+    };
+    if interrupted.load(Ordering::SeqCst) {
+        output::line("⚠️  Stopped early; results are partial.".yellow());
+    }
+    output::line(format!(
+        "\n💡 Recommended: {}",
+        format!("--jobs {}", best).bold()
+    ));
+
+    if options.apply {
+        let path = project_dir.join(CARGO_CONFIG);
+        let current = fs::read_to_string(&path).unwrap_or_default();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, apply_jobs(&current, best)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        output::line(format!("✏️  Set build.jobs = {} in {}", best, CARGO_CONFIG).green());
+    }
+    Ok(())
+}
@@ -0,0 +1,254 @@
+//! Source scanning to find where each dependency is referenced.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directories scanned for Rust sources, relative to the project root.
+const SOURCE_DIRS: &[&str] = &["src", "tests", "benches", "examples"];
+
+/// Build script scanned for `[build-dependencies]` usage.
+const BUILD_SCRIPT: &str = "build.rs";
+
+/// Derive macros whose name doesn't match the crate providing them.
+const DERIVE_MACROS: &[(&str, &str)] = &[
+    ("Serialize", "serde"),
+    ("Deserialize", "serde"),
+    ("Error", "thiserror"),
+    ("Parser", "clap"),
+    ("Subcommand", "clap"),
+    ("Args", "clap"),
+    ("ValueEnum", "clap"),
+    ("Builder", "derive_builder"),
+    ("EnumIter", "strum"),
+    ("Display", "derive_more"),
+    ("Zeroize", "zeroize"),
+    ("Pod", "bytemuck"),
+    ("Zeroable", "bytemuck"),
+];
+
+/// Attribute macros (and helper attributes) used without a crate path.
+const ATTRIBUTE_MACROS: &[(&str, &str)] = &[
+    ("serde", "serde"),
+    ("async_trait", "async_trait"),
+    ("instrument", "tracing"),
+    ("error", "thiserror"),
+    ("command", "clap"),
+    ("arg", "clap"),
+    ("pin_project", "pin_project"),
+    ("rstest", "rstest"),
+];
+
+/// Where a reference was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefKind {
+    /// Library or binary code that ends up in the final artifact.
+    Main,
+    /// `tests/`, `benches/`, `examples/` or a `#[cfg(test)]` module.
+    Test,
+    /// The `build.rs` build script.
+    Build,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub file: PathBuf,
+    pub line: usize,
+    pub kind: RefKind,
+}
+
+/// Converts a package name to the identifier used in Rust paths.
+pub fn crate_ident(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Scans the project sources for references to each of `deps`.
+///
+/// Every dependency gets an entry in the returned map, empty when no reference is found.
+pub fn scan_usage(project_dir: &Path, deps: &[String]) -> Result<HashMap<String, Vec<Reference>>> {
+    let mut usage: HashMap<String, Vec<Reference>> =
+        deps.iter().map(|dep| (dep.clone(), Vec::new())).collect();
+    let idents: Vec<(String, String)> = deps
+        .iter()
+        .map(|dep| (dep.clone(), crate_ident(dep)))
+        .collect();
+
+    for dir in SOURCE_DIRS {
+        let mut files = Vec::new();
+        collect_rs_files(&project_dir.join(dir), &mut files);
+        files.sort();
+        let kind = if *dir == "src" {
+            RefKind::Main
+        } else {
+            RefKind::Test
+        };
+        for file in files {
+            scan_file(project_dir, &file, kind, &idents, &mut usage);
+        }
+    }
+    let build_script = project_dir.join(BUILD_SCRIPT);
+    if build_script.is_file() {
+        scan_file(
+            project_dir,
+            &build_script,
+            RefKind::Build,
+            &idents,
+            &mut usage,
+        );
+    }
+
+    Ok(usage)
+}
+
+fn scan_file(
+    project_dir: &Path,
+    file: &Path,
+    kind: RefKind,
+    idents: &[(String, String)],
+    usage: &mut HashMap<String, Vec<Reference>>,
+) {
+    let Ok(content) = fs::read_to_string(file) else {
+        return;
+    };
+    let relative = file.strip_prefix(project_dir).unwrap_or(file).to_path_buf();
+    let test_lines = cfg_test_lines(&content);
+
+    for (idx, line) in content.lines().enumerate() {
+        let code = line.trim_start();
+        if code.starts_with("//") {
+            continue;
+        }
+        let macro_crates = attribute_crates(code);
+        for (dep, ident) in idents {
+            if references_crate(code, ident) || macro_crates.iter().any(|c| c == ident) {
+                let kind = if kind == RefKind::Main && test_lines[idx] {
+                    RefKind::Test
+                } else {
+                    kind
+                };
+                usage.get_mut(dep).unwrap().push(Reference {
+                    file: relative.clone(),
+                    line: idx + 1,
+                    kind,
+                });
+            }
+        }
+    }
+}
+
+/// Returns the crate identifiers implied by derive and attribute macros on `line`,
+/// e.g. `#[derive(Serialize)]` → `serde`, `#[serde(rename = "x")]` → `serde`.
+pub fn attribute_crates(line: &str) -> Vec<String> {
+    let mut crates = Vec::new();
+    let mut rest = line;
+    while let Some(pos) = rest.find("#[") {
+        rest = &rest[pos + 2..];
+        let attr = rest.trim_start_matches('!').trim_start();
+        let name_end = attr
+            .find(|c: char| c == '(' || c == ']' || c == '=' || c.is_whitespace())
+            .unwrap_or(attr.len());
+        let name = &attr[..name_end];
+
+        if name == "derive" {
+            let inner = attr[name_end..]
+                .trim_start_matches('(')
+                .split(')')
+                .next()
+                .unwrap_or_default();
+            for derive in inner.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+                let last = derive.rsplit("::").next().unwrap_or(derive);
+                if let Some((_, krate)) = DERIVE_MACROS.iter().find(|(m, _)| *m == last) {
+                    crates.push(krate.to_string());
+                }
+            }
+        } else if !name.contains("::") {
+            match ATTRIBUTE_MACROS.iter().find(|(m, _)| *m == name) {
+                Some((_, krate)) => crates.push(krate.to_string()),
+                // Bare attribute macros are usually named after their crate.
+                None => crates.push(name.to_string()),
+            }
+        }
+    }
+    crates
+}
+
+fn collect_rs_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_rs_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+/// Returns whether `line` refers to the crate `ident` through a path, `use` or `extern crate`.
+pub fn references_crate(line: &str, ident: &str) -> bool {
+    let bytes = line.as_bytes();
+    let mut start = 0;
+    while let Some(pos) = line[start..].find(ident) {
+        let begin = start + pos;
+        let end = begin + ident.len();
+        start = end;
+
+        let before = line[..begin].chars().next_back();
+        if before.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == ':' || c == '.') {
+            continue;
+        }
+        let after = bytes.get(end).copied();
+        if after.is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_') {
+            continue;
+        }
+        if line[end..].starts_with("::") {
+            return true;
+        }
+        let prefix = line[..begin].trim_end();
+        if prefix.ends_with("use") || prefix.ends_with("extern crate") {
+            return true;
+        }
+    }
+    false
+}
+
+/// Marks which lines of `content` sit inside a `#[cfg(test)]` item, using brace depth.
+fn cfg_test_lines(content: &str) -> Vec<bool> {
+    let mut marks = Vec::new();
+    let mut pending = false;
+    let mut depth: i32 = 0;
+    let mut test_depth: Option<i32> = None;
+
+    for line in content.lines() {
+        let code = line.trim();
+        if test_depth.is_none() && code.starts_with("#[cfg(test)]") {
+            pending = true;
+        }
+        let in_test = test_depth.is_some() || pending;
+        marks.push(in_test);
+
+        for c in code.chars() {
+            match c {
+                '{' => {
+                    if pending {
+                        test_depth = Some(depth);
+                        pending = false;
+                    }
+                    depth += 1;
+                }
+                '}' => {
+                    depth -= 1;
+                    if test_depth == Some(depth) {
+                        test_depth = None;
+                    }
+                }
+                ';' if pending => pending = false,
+                _ => {}
+            }
+        }
+    }
+    marks
+}
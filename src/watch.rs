@@ -0,0 +1,371 @@
+//! Watch mode (`watch`): re-runs a cargo command through the executor when files
+//! change, which paths trigger a run, and the JSON Lines encoding of run events.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::executor::{self, ExecOptions, RunningChild};
+use crate::output;
+
+/// Patterns watched when no `--watch-glob` is given.
+pub const DEFAULT_WATCH_GLOBS: &[&str] = &["**/*.rs", "**/Cargo.toml", "**/Cargo.lock"];
+
+/// Paths watched in addition to `--path` and `[watch] paths`.
+pub const DEFAULT_WATCH_PATHS: &[&str] = &["src", "Cargo.toml"];
+
+/// Paths that never trigger a rebuild: build output and hidden directories.
+pub const EXCLUDED_GLOBS: &[&str] = &["**/target/**", "**/.*/**"];
+
+fn glob_set(patterns: &[&str]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        // `*` stays within one path component, so `Cargo.toml` only matches the root one.
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .with_context(|| format!("Invalid watch glob `{}`", pattern))?;
+        builder.add(glob);
+    }
+    builder.build().context("Failed to compile watch globs")
+}
+
+/// Decides which changed paths, relative to the project root, trigger a rebuild.
+#[derive(Debug, Clone)]
+pub struct WatchFilter {
+    include: GlobSet,
+    exclude: GlobSet,
+}
+
+impl WatchFilter {
+    /// Compiles `patterns`, falling back to [`DEFAULT_WATCH_GLOBS`] when empty.
+    pub fn new(patterns: &[String]) -> Result<WatchFilter> {
+        let patterns: Vec<&str> = if patterns.is_empty() {
+            DEFAULT_WATCH_GLOBS.to_vec()
+        } else {
+            patterns.iter().map(String::as_str).collect()
+        };
+        Ok(WatchFilter {
+            include: glob_set(&patterns)?,
+            exclude: glob_set(EXCLUDED_GLOBS)?,
+        })
+    }
+
+    /// Also skips paths matching `patterns` (`--ignore`).
+    pub fn ignoring(self, patterns: &[String]) -> Result<WatchFilter> {
+        let mut excluded = EXCLUDED_GLOBS.to_vec();
+        excluded.extend(patterns.iter().map(String::as_str));
+        Ok(WatchFilter {
+            exclude: glob_set(&excluded)?,
+            ..self
+        })
+    }
+
+    pub fn triggers_rebuild(&self, relative: &Path) -> bool {
+        self.include.is_match(relative) && !self.exclude.is_match(relative)
+    }
+}
+
+/// One watch-mode activity record, emitted as a single JSON line with `--json`: a
+/// `start` event when a run begins and a `rebuild` event when it ends.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct WatchEvent {
+    pub event: String,
+    /// The changed path that caused the run, or `start` for the first one.
+    pub trigger: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// The run was stopped by another change before it finished.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub restarted: bool,
+}
+
+impl WatchEvent {
+    pub fn start(trigger: &str) -> Self {
+        WatchEvent {
+            event: "start".to_string(),
+            trigger: trigger.to_string(),
+            success: None,
+            duration_ms: None,
+            restarted: false,
+        }
+    }
+
+    pub fn rebuild(trigger: &str, success: bool, duration: Duration) -> Self {
+        WatchEvent {
+            event: "rebuild".to_string(),
+            trigger: trigger.to_string(),
+            success: Some(success),
+            duration_ms: Some(duration.as_millis() as u64),
+            restarted: false,
+        }
+    }
+
+    /// The end of a run that a change stopped early.
+    pub fn restarted(trigger: &str, duration: Duration) -> Self {
+        WatchEvent {
+            restarted: true,
+            ..WatchEvent::rebuild(trigger, false, duration)
+        }
+    }
+}
+
+/// Writes `event` as one JSON line and flushes so consumers see it immediately.
+pub fn emit_json_line<W: Write>(out: &mut W, event: &WatchEvent) -> Result<()> {
+    serde_json::to_writer(&mut *out, event)?;
+    writeln!(out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Globs for `WatchFilter::new`: `explicit` ones (`--watch-glob`) as given; otherwise
+/// [`DEFAULT_WATCH_GLOBS`] plus everything under each extra watched path.
+pub fn watch_globs(explicit: &[String], extra_paths: &[PathBuf]) -> Vec<String> {
+    if !explicit.is_empty() {
+        return explicit.to_vec();
+    }
+    let mut globs: Vec<String> = DEFAULT_WATCH_GLOBS.iter().map(|g| g.to_string()).collect();
+    for path in extra_paths {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+        globs.push(path.to_string());
+        globs.push(format!("{}/**", path));
+    }
+    globs
+}
+
+/// `path` relative to `root` when it lies inside it, as notify reports absolute paths
+/// on some platforms.
+pub fn relative_to(root: &Path, path: &Path) -> PathBuf {
+    let path = path.strip_prefix(root).unwrap_or(path);
+    path.strip_prefix(".").unwrap_or(path).to_path_buf()
+}
+
+/// What `watch` runs and which changes re-run it.
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub command: String,
+    pub args: Vec<String>,
+    /// Files and directories to watch, relative to the working directory.
+    pub paths: Vec<PathBuf>,
+    pub filter: WatchFilter,
+    pub debounce: Duration,
+    /// Clear the screen before each run.
+    pub clear: bool,
+    /// Print [`WatchEvent`] JSON lines to stdout instead of the status line; everything
+    /// else, cargo's output included, goes to stderr.
+    pub json: bool,
+}
+
+/// The first path of `event` that triggers a run, relative to `root`.
+fn trigger_of(event: &Event, root: &Path, filter: &WatchFilter) -> Option<String> {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return None;
+    }
+    event
+        .paths
+        .iter()
+        .map(|path| relative_to(root, path))
+        .find(|relative| filter.triggers_rebuild(relative))
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Waits up to `timeout` for a change that triggers a run.
+fn next_trigger(
+    events: &Receiver<notify::Result<Event>>,
+    root: &Path,
+    filter: &WatchFilter,
+    timeout: Duration,
+) -> Result<Option<String>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        match events.recv_timeout(left) {
+            Ok(Ok(event)) => {
+                if let Some(trigger) = trigger_of(&event, root, filter) {
+                    return Ok(Some(trigger));
+                }
+            }
+            Ok(Err(err)) => output::warning(format!("⚠️  Watch error: {}", err).yellow()),
+            Err(RecvTimeoutError::Timeout) => return Ok(None),
+            Err(RecvTimeoutError::Disconnected) => bail!("The file watcher stopped"),
+        }
+    }
+}
+
+/// Swallows the burst of events that usually follows a save, until `quiet` passes
+/// without any.
+fn debounce(events: &Receiver<notify::Result<Event>>, quiet: Duration) {
+    while events.recv_timeout(quiet).is_ok() {}
+}
+
+fn clear_screen() {
+    print!("\x1B[2J\x1B[1;1H");
+    let _ = std::io::stdout().flush();
+}
+
+/// Outcome of one run: finished with a status, or stopped because `trigger` changed.
+enum RunEnd {
+    Finished(Result<std::process::ExitStatus>),
+    Restarted(String),
+}
+
+/// Runs the command on a worker thread, stopping it early when a watched file changes.
+fn run_once(
+    stats_path: &Path,
+    options: &WatchOptions,
+    exec: &ExecOptions,
+    running: &RunningChild,
+    events: &Receiver<notify::Result<Event>>,
+    root: &Path,
+) -> Result<RunEnd> {
+    let (done_tx, done_rx) = mpsc::channel();
+    let (stats_path, command, args, exec) = (
+        stats_path.to_path_buf(),
+        options.command.clone(),
+        options.args.clone(),
+        exec.clone(),
+    );
+    thread::spawn(move || {
+        let _ = done_tx.send(executor::execute_cargo_command(
+            &stats_path,
+            &command,
+            &args,
+            &exec,
+        ));
+    });
+    loop {
+        if let Ok(result) = done_rx.try_recv() {
+            return Ok(RunEnd::Finished(result));
+        }
+        if let Some(trigger) =
+            next_trigger(events, root, &options.filter, Duration::from_millis(50))?
+        {
+            executor::stop_child(running);
+            // The stopped run is still tracked, as killed by a signal.
+            let _ = done_rx.recv();
+            return Ok(RunEnd::Restarted(trigger));
+        }
+    }
+}
+
+/// Runs `cargo <command>` now and again whenever a watched file changes, until Ctrl-C.
+/// Every run goes through the executor, so it is tracked with its duration and outcome.
+pub fn watch(stats_path: &Path, options: &WatchOptions, exec: &ExecOptions) -> Result<()> {
+    if exec.dry_run {
+        executor::execute_cargo_command(stats_path, &options.command, &options.args, exec)?;
+        return Ok(());
+    }
+    let root = std::env::current_dir()
+        .and_then(|dir| dir.canonicalize())
+        .context("Failed to resolve the working directory")?;
+    let (tx, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })
+    .context("Failed to start the file watcher")?;
+    let mut watched = Vec::new();
+    for path in &options.paths {
+        if !path.exists() {
+            output::warning(
+                format!("⚠️  Not watching {}: it does not exist", path.display()).yellow(),
+            );
+            continue;
+        }
+        let mode = if path.is_dir() {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher
+            .watch(path, mode)
+            .with_context(|| format!("Failed to watch {}", path.display()))?;
+        watched.push(path.display().to_string());
+    }
+    if watched.is_empty() {
+        bail!("Nothing to watch: none of the watch paths exist");
+    }
+
+    if options.json {
+        output::use_stderr();
+    }
+    let running = RunningChild::default();
+    let exec = ExecOptions {
+        running: Some(running.clone()),
+        stdout_to_stderr: options.json,
+        ..exec.clone()
+    };
+    // The child has its own process group, out of reach of the terminal's Ctrl-C.
+    let stop = running.clone();
+    ctrlc::set_handler(move || {
+        executor::stop_child(&stop);
+        std::process::exit(130);
+    })
+    .context("Failed to install Ctrl-C handler")?;
+
+    let mut trigger = "start".to_string();
+    let mut runs = 0;
+    loop {
+        if options.clear && !options.json {
+            clear_screen();
+        }
+        runs += 1;
+        if options.json {
+            emit_json_line(&mut std::io::stdout(), &WatchEvent::start(&trigger))?;
+        }
+        let started = Instant::now();
+        match run_once(stats_path, options, &exec, &running, &events, &root)? {
+            RunEnd::Finished(result) => {
+                let status = result?;
+                let duration = started.elapsed();
+                if options.json {
+                    emit_json_line(
+                        &mut std::io::stdout(),
+                        &WatchEvent::rebuild(&trigger, status.success(), duration),
+                    )?;
+                } else {
+                    let outcome = if status.success() {
+                        "ok".green()
+                    } else {
+                        format!("failed with exit code {}", executor::exit_code(status)).red()
+                    };
+                    output::line(format!(
+                        "\n👀 Run #{}: {} after {:.2}s ({}). Watching {}; Ctrl-C to stop.",
+                        runs,
+                        outcome,
+                        duration.as_secs_f64(),
+                        trigger,
+                        watched.join(", ")
+                    ));
+                }
+                trigger = loop {
+                    if let Some(trigger) =
+                        next_trigger(&events, &root, &options.filter, Duration::from_secs(3600))?
+                    {
+                        break trigger;
+                    }
+                };
+            }
+            RunEnd::Restarted(changed) => {
+                if options.json {
+                    emit_json_line(
+                        &mut std::io::stdout(),
+                        &WatchEvent::restarted(&trigger, started.elapsed()),
+                    )?;
+                }
+                output::status(format!("🔁 {} changed; restarting", changed).yellow());
+                trigger = changed;
+            }
+        }
+        debounce(&events, options.debounce);
+    }
+}
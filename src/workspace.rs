@@ -0,0 +1,189 @@
+//! Consistency checks for `[workspace.dependencies]` inheritance.
+
+use anyhow::{Context, Result};
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, InlineTable, Item, Value};
+
+use crate::dependencies;
+use crate::output;
+
+/// Dependency tables a member may inherit workspace dependencies into.
+const DEPENDENCY_SECTIONS: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Keys that may accompany `workspace = true` in a member's dependency entry.
+const INHERITABLE_KEYS: &[&str] = &["version", "features", "optional"];
+
+/// A member dependency that is declared centrally but pinned locally.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InheritanceFinding {
+    pub member: String,
+    pub manifest: PathBuf,
+    pub section: String,
+    pub name: String,
+    pub local_version: Option<String>,
+    pub central_version: Option<String>,
+    /// Whether the entry can be rewritten to `workspace = true` without losing settings.
+    pub eligible: bool,
+}
+
+impl InheritanceFinding {
+    pub fn differs(&self) -> bool {
+        self.local_version != self.central_version
+    }
+}
+
+fn read_document(path: &Path) -> Result<DocumentMut> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    content
+        .parse()
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn version_of(item: &Item) -> Option<String> {
+    match item {
+        Item::Value(Value::String(version)) => Some(version.value().clone()),
+        _ => item
+            .as_table_like()
+            .and_then(|table| table.get("version"))
+            .and_then(Item::as_str)
+            .map(str::to_string),
+    }
+}
+
+fn inherits(item: &Item) -> bool {
+    item.as_table_like()
+        .and_then(|table| table.get("workspace"))
+        .and_then(Item::as_bool)
+        .unwrap_or(false)
+}
+
+fn is_eligible(item: &Item) -> bool {
+    match item.as_table_like() {
+        Some(table) => table.iter().all(|(key, _)| INHERITABLE_KEYS.contains(&key)),
+        None => item.is_str(),
+    }
+}
+
+/// Compares every member manifest against the root `[workspace.dependencies]`.
+pub fn find_inheritance_issues(root: &Path) -> Result<Vec<InheritanceFinding>> {
+    let root_manifest = read_document(&root.join("Cargo.toml"))?;
+    let Some(central) = root_manifest
+        .get("workspace")
+        .and_then(|ws| ws.get("dependencies"))
+        .and_then(Item::as_table_like)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut findings = Vec::new();
+    for member in dependencies::workspace_members(root)? {
+        let manifest_path = member.dir.join("Cargo.toml");
+        let manifest = read_document(&manifest_path)?;
+        for section in DEPENDENCY_SECTIONS {
+            let Some(deps) = manifest.get(section).and_then(Item::as_table_like) else {
+                continue;
+            };
+            for (name, item) in deps.iter() {
+                let Some(central_item) = central.get(name) else {
+                    continue;
+                };
+                if inherits(item) {
+                    continue;
+                }
+                findings.push(InheritanceFinding {
+                    member: member.name.clone(),
+                    manifest: manifest_path.clone(),
+                    section: section.to_string(),
+                    name: name.to_string(),
+                    local_version: version_of(item),
+                    central_version: version_of(central_item),
+                    eligible: is_eligible(item),
+                });
+            }
+        }
+    }
+    Ok(findings)
+}
+
+/// Rewrites eligible findings to `{ workspace = true }`, keeping `features` and `optional`.
+pub fn apply_inheritance(findings: &[InheritanceFinding]) -> Result<usize> {
+    let mut fixed = 0;
+    let mut manifests: Vec<&PathBuf> = findings.iter().map(|f| &f.manifest).collect();
+    manifests.dedup();
+
+    for path in manifests {
+        let mut manifest = read_document(path)?;
+        for finding in findings
+            .iter()
+            .filter(|f| f.eligible && &f.manifest == path)
+        {
+            let Some(deps) = manifest
+                .get_mut(&finding.section)
+                .and_then(Item::as_table_like_mut)
+            else {
+                continue;
+            };
+            let Some(item) = deps.get_mut(&finding.name) else {
+                continue;
+            };
+
+            let mut inherited = InlineTable::new();
+            inherited.insert("workspace", true.into());
+            if let Some(table) = item.as_table_like() {
+                for key in ["features", "optional"] {
+                    if let Some(value) = table.get(key).and_then(Item::as_value) {
+                        inherited.insert(key, value.clone());
+                    }
+                }
+            }
+            *item = Item::Value(Value::InlineTable(inherited));
+            fixed += 1;
+        }
+        fs::write(path, manifest.to_string())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(fixed)
+}
+
+pub fn check_workspace_deps(root: &Path, fix: bool) -> Result<()> {
+    output::status("🔍 Checking [workspace.dependencies] inheritance...");
+    let findings = find_inheritance_issues(root)?;
+    if findings.is_empty() {
+        output::line("✅ All members inherit workspace dependencies.".green());
+        return Ok(());
+    }
+
+    let conflicts: Vec<_> = findings.iter().filter(|f| f.differs()).collect();
+    if !conflicts.is_empty() {
+        output::line("🚨 Declared centrally and locally with different specs:".red());
+        for f in conflicts {
+            output::line(format!(
+                "   • {} [{}] {}: local {} vs workspace {}",
+                f.member.bold(),
+                f.section,
+                f.name,
+                f.local_version.as_deref().unwrap_or("?"),
+                f.central_version.as_deref().unwrap_or("?")
+            ));
+        }
+    }
+
+    output::line("💡 Could switch to `workspace = true`:".yellow());
+    for f in findings.iter().filter(|f| f.eligible) {
+        output::line(format!(
+            "   • {} [{}] {}",
+            f.member.bold(),
+            f.section,
+            f.name
+        ));
+    }
+
+    if fix {
+        let fixed = apply_inheritance(&findings)?;
+        output::line(format!("✏️  Rewrote {fixed} entries to inherit from the workspace").green());
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! Detection of yanked versions pinned in `Cargo.lock`.
+
+use anyhow::Result;
+use colored::*;
+use semver::{Version, VersionReq};
+use std::path::Path;
+
+use crate::cache;
+use crate::index::{self, IndexEntry};
+use crate::lockfile;
+use crate::output;
+
+/// A locked package whose pinned version has been yanked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YankedPackage {
+    pub name: String,
+    pub version: String,
+    /// Closest non-yanked semver-compatible version, if any.
+    pub replacement: Option<String>,
+}
+
+/// Picks the closest non-yanked version compatible with `pinned`, preferring
+/// the smallest upgrade over a downgrade.
+pub fn nearest_compatible(pinned: &str, entries: &[IndexEntry]) -> Option<String> {
+    let pinned = Version::parse(pinned).ok()?;
+    let base = match (pinned.major, pinned.minor) {
+        (0, 0) => format!("=0.0.{}", pinned.patch),
+        (0, minor) => format!("^0.{minor}"),
+        (major, _) => format!("^{major}"),
+    };
+    let req = VersionReq::parse(&base).ok()?;
+    let mut candidates: Vec<Version> = entries
+        .iter()
+        .filter(|entry| !entry.yanked)
+        .filter_map(|entry| Version::parse(&entry.vers).ok())
+        .filter(|version| *version != pinned && req.matches(version))
+        .collect();
+    candidates.sort();
+
+    let upgrade = candidates.iter().find(|version| **version > pinned);
+    let downgrade = candidates.iter().rev().find(|version| **version < pinned);
+    upgrade.or(downgrade).map(Version::to_string)
+}
+
+/// Checks every crates.io package in the lockfile against the index.
+pub fn check_yanked(project_dir: &Path, offline: bool) -> Result<()> {
+    output::status("🔍 Checking Cargo.lock for yanked versions...");
+    let lock = lockfile::load_lockfile(&project_dir.join("Cargo.lock"))?;
+    let packages: Vec<_> = lock.package.iter().filter(|p| p.is_crates_io()).collect();
+    let fetched = cache::map_concurrent(&packages, |package| {
+        index::fetch_versions(&package.name, offline)
+    });
+
+    let mut yanked = Vec::new();
+    let mut failures = 0;
+    let mut first_error = None;
+    for (package, entries) in packages.iter().zip(fetched) {
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(err) => {
+                failures += 1;
+                first_error.get_or_insert(err);
+                continue;
+            }
+        };
+        let is_yanked = entries
+            .iter()
+            .any(|entry| entry.vers == package.version && entry.yanked);
+        if is_yanked {
+            yanked.push(YankedPackage {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                replacement: nearest_compatible(&package.version, &entries),
+            });
+        }
+    }
+
+    if yanked.is_empty() && failures == 0 {
+        output::line("✅ No yanked versions found!".green());
+    } else if yanked.is_empty() {
+        output::line("✅ No yanked versions among the packages checked.".green());
+    } else {
+        output::line("🚨 Yanked versions pinned in Cargo.lock:".red());
+        for package in &yanked {
+            match &package.replacement {
+                Some(replacement) => output::line(format!(
+                    "   • {} {} → {}",
+                    package.name,
+                    package.version,
+                    replacement.green()
+                )),
+                None => output::line(format!(
+                    "   • {} {} (no compatible replacement)",
+                    package.name, package.version
+                )),
+            }
+        }
+    }
+    if let Some(err) = first_error {
+        output::warning(format!("⚠️ {failures} package(s) could not be checked: {err:#}").yellow());
+    }
+    Ok(())
+}
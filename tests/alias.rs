@@ -0,0 +1,47 @@
+use cargo_sleek::alias;
+use std::collections::BTreeMap;
+
+fn aliases(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, expansion)| (name.to_string(), expansion.to_string()))
+        .collect()
+}
+
+#[test]
+fn aliases_expand_to_command_and_args() {
+    let table = aliases(&[
+        ("t", "test --workspace --all-features"),
+        ("br", "build --release"),
+        ("brv", "br --verbose"),
+    ]);
+    assert_eq!(
+        alias::resolve_alias("t", &table).unwrap().unwrap(),
+        ["test", "--workspace", "--all-features"]
+    );
+    assert_eq!(
+        alias::resolve_alias("brv", &table).unwrap().unwrap(),
+        ["build", "--release", "--verbose"]
+    );
+    assert!(alias::resolve_alias("nextest", &table).unwrap().is_none());
+}
+
+#[test]
+fn recursive_aliases_are_rejected() {
+    let table = aliases(&[("loop", "loop --again"), ("a", "b"), ("b", "a x")]);
+    let err = alias::resolve_alias("loop", &table).unwrap_err();
+    assert!(err.to_string().contains("loop → loop"), "{err}");
+    let err = alias::resolve_alias("a", &table).unwrap_err();
+    assert!(err.to_string().contains("a → b → a"), "{err}");
+}
+
+#[test]
+fn help_section_lists_aliases_only_when_configured() {
+    let table = aliases(&[("t", "test --workspace"), ("br", "build --release")]);
+    let help = alias::aliases_help(&table).unwrap();
+    assert_eq!(
+        help,
+        "Aliases (from .sleek.toml):\n  br  cargo build --release\n  t   cargo test --workspace"
+    );
+    assert!(alias::aliases_help(&BTreeMap::new()).is_none());
+}
@@ -0,0 +1,93 @@
+use cargo_sleek::bench::{self, BenchDelta};
+use cargo_sleek::history::BenchRecord;
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+const LIBTEST: &str = "running 3 tests
+test parse_small ... bench:         412 ns/iter (+/- 9)
+test parse_large ... bench:   1,234,567 ns/iter (+/- 8,901)
+test render      ... bench:       1,020.50 ns/iter (+/- 3.10)
+test helpers::works ... ok
+
+test result: ok. 0 passed; 0 failed; 0 ignored; 3 measured; 0 filtered out
+";
+
+#[test]
+fn parses_libtest_bench_lines() {
+    let results = bench::parse_libtest_benches(LIBTEST);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results["parse_small"], 412.0);
+    assert_eq!(results["parse_large"], 1_234_567.0);
+    assert_eq!(results["render"], 1_020.5);
+}
+
+#[test]
+fn loads_fresh_criterion_medians() {
+    let dir = tempfile::tempdir().unwrap();
+    let new = dir.path().join("parsing/small input/new");
+    fs::create_dir_all(&new).unwrap();
+    fs::write(
+        new.join("estimates.json"),
+        r#"{"mean":{"point_estimate":1510.2},"median":{"point_estimate":1498.7}}"#,
+    )
+    .unwrap();
+    fs::write(
+        new.join("benchmark.json"),
+        r#"{"group_id":"parsing","function_id":"small input","full_id":"parsing/small input"}"#,
+    )
+    .unwrap();
+    let stale = dir.path().join("old/new");
+    fs::create_dir_all(&stale).unwrap();
+    fs::write(
+        stale.join("estimates.json"),
+        r#"{"median":{"point_estimate":5.0}}"#,
+    )
+    .unwrap();
+    let old = SystemTime::now() - Duration::from_secs(3600);
+    fs::File::options()
+        .append(true)
+        .open(stale.join("estimates.json"))
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+
+    let since = SystemTime::now() - Duration::from_secs(60);
+    let results = bench::load_criterion_results(dir.path(), since);
+    assert_eq!(
+        results.into_iter().collect::<Vec<_>>(),
+        [("parsing/small input".to_string(), 1498.7)]
+    );
+}
+
+#[test]
+fn compares_with_the_previous_run() {
+    let record = |pairs: &[(&str, f64)]| BenchRecord {
+        results: pairs
+            .iter()
+            .map(|(name, ns)| (name.to_string(), *ns))
+            .collect::<BTreeMap<_, _>>(),
+        ..Default::default()
+    };
+    let previous = record(&[("a", 100.0), ("b", 200.0)]);
+    let current = record(&[("a", 112.0), ("b", 190.0), ("c", 50.0)]);
+
+    let deltas = bench::compare_benches(Some(&previous), &current);
+    assert_eq!(
+        deltas[2],
+        BenchDelta {
+            name: "c".into(),
+            previous_ns: None,
+            current_ns: 50.0
+        }
+    );
+    assert!((deltas[0].percent().unwrap() - 12.0).abs() < 1e-9);
+    assert!(deltas[0].regressed(10.0));
+    assert!(!deltas[0].regressed(15.0));
+    assert!(!deltas[1].regressed(0.0));
+    assert!(!deltas[2].regressed(0.0));
+
+    assert_eq!(bench::format_ns(412.0), "412.0 ns");
+    assert_eq!(bench::format_ns(1_498.7), "1.50 µs");
+    assert_eq!(bench::format_ns(2_500_000.0), "2.50 ms");
+}
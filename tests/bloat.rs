@@ -0,0 +1,60 @@
+use cargo_sleek::bloat::{self, BloatEntry};
+
+const FUNCTIONS: &str = "    Finished `release` profile [optimized] target(s) in 0.11s
+    Analyzing target/release/app
+
+File  .text     Size        Crate Name
+0.5%   3.3%  13.4KiB          std std::rt::lang_start_internal
+0.4%   2.6%  10.6KiB        regex regex::exec::ExecBuilder::build
+0.1%   0.5%   2.0KiB          app app::main
+9.3%  62.3% 253.0KiB              And 1234 smaller methods. Use -n N to show more.
+14.9% 100.0% 406.0KiB              .text section size, the file size is 2.7MiB
+";
+
+const CRATES: &str = "File  .text     Size Crate
+5.0%  33.8%  138.4KiB std
+2.2%  14.8%   60.7KiB regex
+0.1%   0.9%     512B app
+14.9% 100.0% 406.0KiB .text section size, the file size is 2.7MiB
+
+Note: numbers above are a result of guesswork. They are not 100% correct and never will be.
+";
+
+#[test]
+fn parses_function_output() {
+    let summary = bloat::parse_bloat_output(FUNCTIONS);
+    assert_eq!(summary.entries.len(), 3);
+    assert_eq!(
+        summary.entries[0],
+        BloatEntry {
+            crate_name: "std".into(),
+            name: Some("std::rt::lang_start_internal".into()),
+            bytes: 13_721,
+            file_percent: 0.5,
+            text_percent: 3.3,
+        }
+    );
+    assert_eq!(summary.entries[2].name.as_deref(), Some("app::main"));
+    assert_eq!(summary.text_bytes, Some(415_744));
+    assert_eq!(summary.file_bytes, Some(2_831_155));
+}
+
+#[test]
+fn parses_crate_output() {
+    let summary = bloat::parse_bloat_output(CRATES);
+    let crates: Vec<_> = summary
+        .entries
+        .iter()
+        .map(|e| (e.crate_name.as_str(), e.bytes))
+        .collect();
+    assert_eq!(crates, [("std", 141_721), ("regex", 62_156), ("app", 512)]);
+    assert!(summary.entries.iter().all(|e| e.name.is_none()));
+}
+
+#[test]
+fn parses_bloat_sizes() {
+    assert_eq!(bloat::parse_bloat_size("512B"), Some(512));
+    assert_eq!(bloat::parse_bloat_size("1.5KiB"), Some(1536));
+    assert_eq!(bloat::parse_bloat_size("2MiB"), Some(2 << 20));
+    assert_eq!(bloat::parse_bloat_size("Size"), None);
+}
@@ -0,0 +1,75 @@
+use cargo_sleek::build_cache::{self, SccacheStats};
+use std::time::Duration;
+
+const BEFORE: &str = "Compile requests                    120
+Compile requests executed            90
+Cache hits                           60
+Cache hits (Rust)                    60
+Cache misses                         30
+Cache misses (Rust)                  30
+Cache timeouts                        0
+Non-cacheable compilations            0
+Cache location                  Local disk: \"/home/dev/.cache/sccache\"
+Cache size                          512 MiB
+Max cache size                       10 GiB
+";
+
+const AFTER: &str = "Compile requests                    220
+Compile requests executed           170
+Cache hits                          140
+Cache hits (Rust)                   140
+Cache misses                         40
+Cache misses (Rust)                  40
+Cache size                            1 GiB
+Max cache size                       10 GiB
+";
+
+#[test]
+fn parses_sccache_stats() {
+    assert_eq!(
+        build_cache::parse_sccache_stats(BEFORE),
+        SccacheStats {
+            compile_requests: 120,
+            cache_hits: 60,
+            cache_misses: 30,
+            cache_size_bytes: Some(512 << 20),
+        }
+    );
+}
+
+#[test]
+fn reports_stats_of_one_build() {
+    let before = build_cache::parse_sccache_stats(BEFORE);
+    let build = build_cache::parse_sccache_stats(AFTER).since(&before);
+    assert_eq!(build.compile_requests, 100);
+    assert_eq!(build.cache_hits, 80);
+    assert_eq!(build.cache_misses, 10);
+    assert_eq!(build.cache_size_bytes, Some(1 << 30));
+    assert!((build.hit_rate().unwrap() - 88.9).abs() < 0.1);
+    assert_eq!(
+        build_cache::estimate_time_saved(Duration::from_secs(10), &build),
+        Some(Duration::from_secs(80))
+    );
+    assert_eq!(
+        build_cache::estimate_time_saved(Duration::from_secs(10), &SccacheStats::default()),
+        None
+    );
+}
+
+#[test]
+fn detects_sccache_wrapper() {
+    let config = "[build]\nrustc-wrapper = \"/usr/local/bin/sccache\"".to_string();
+    let wrapper = build_cache::rustc_wrapper(std::slice::from_ref(&config), None).unwrap();
+    assert!(build_cache::is_sccache(&wrapper));
+    // The environment overrides config files, and an empty value disables the wrapper.
+    assert_eq!(
+        build_cache::rustc_wrapper(std::slice::from_ref(&config), Some("")),
+        None
+    );
+    assert_eq!(
+        build_cache::rustc_wrapper(&[config], Some("cachepot")).as_deref(),
+        Some("cachepot")
+    );
+    assert!(!build_cache::is_sccache("cachepot"));
+    assert_eq!(build_cache::rustc_wrapper(&[], None), None);
+}
@@ -0,0 +1,23 @@
+use cargo_sleek::cache;
+
+#[test]
+fn cache_keys_are_filesystem_safe() {
+    assert_eq!(
+        cache::cache_key("https://index.crates.io/se/rd/serde"),
+        "index.crates.io_se_rd_serde"
+    );
+}
+
+#[test]
+fn offline_fetch_of_uncached_url_fails_without_network() {
+    let err =
+        cache::fetch("https://index.crates.io/no/ts/not-a-cached-crate-xyz", true).unwrap_err();
+    assert!(err.to_string().contains("offline"));
+}
+
+#[test]
+fn map_concurrent_preserves_input_order() {
+    let items: Vec<u64> = (0..50).collect();
+    let doubled = cache::map_concurrent(&items, |n| n * 2);
+    assert_eq!(doubled, (0..50).map(|n| n * 2).collect::<Vec<_>>());
+}
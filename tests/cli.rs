@@ -0,0 +1,871 @@
+use std::fs;
+use std::process::Command;
+
+fn sleek() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_cargo-sleek"));
+    // `true` stands in for cargo so executor-backed commands succeed instantly.
+    cmd.env("CARGO", "true")
+        .env_remove("SLEEK_STATS_FILE")
+        .env_remove("SLEEK_NO_STATS");
+    cmd
+}
+
+#[test]
+fn stats_file_flag_isolates_tracking() {
+    let dir = tempfile::tempdir().unwrap();
+    let custom = dir.path().join("custom-stats.json");
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+
+    let status = sleek()
+        .current_dir(dir.path())
+        .arg("--stats-file")
+        .arg(&custom)
+        .arg("build")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let content = fs::read_to_string(&custom).unwrap();
+    assert!(content.contains("\"build\""));
+    assert!(!dir.path().join("command_stats.json").exists());
+}
+
+#[test]
+fn no_stats_env_disables_tracking() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+
+    let status = sleek()
+        .current_dir(dir.path())
+        .env("SLEEK_NO_STATS", "1")
+        .arg("build")
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(!dir.path().join("command_stats.json").exists());
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .env("SLEEK_NO_STATS", "1")
+        .arg("stats")
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("tracking is disabled"));
+
+    let stats = dir.path().join("command_stats.json");
+    fs::write(&stats, r#"{"build":3}"#).unwrap();
+    let output = sleek()
+        .current_dir(dir.path())
+        .env("SLEEK_NO_STATS", "1")
+        .args(["reset", "--force"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("tracking is disabled"), "{stdout}");
+    assert_eq!(fs::read_to_string(&stats).unwrap(), r#"{"build":3}"#);
+}
+
+#[test]
+fn build_outside_a_crate_fails_with_guidance() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .arg("build")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No Cargo.toml found in the current directory"));
+    assert!(stderr.contains("--no-project-check"));
+    assert!(!dir.path().join("command_stats.json").exists());
+
+    let status = sleek()
+        .current_dir(dir.path())
+        .args(["--no-project-check", "build"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn missing_cargo_binary_gets_a_friendly_error() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", "cargo-sleek-definitely-missing")
+        .arg("build")
+        .output()
+        .unwrap();
+    assert_eq!(
+        output.status.code(),
+        Some(cargo_sleek::executor::EXIT_CARGO_NOT_FOUND)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("cargo not found on PATH; is Rust installed?"));
+    assert!(stderr.contains("cargo-sleek-definitely-missing"));
+}
+
+#[test]
+fn report_json_describes_each_run() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    let report = dir.path().join("report.json");
+
+    for _ in 0..2 {
+        let status = sleek()
+            .current_dir(dir.path())
+            .arg("--report-json")
+            .arg(&report)
+            .args(["--report-append", "build"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+    let content = fs::read_to_string(&report).unwrap();
+    assert_eq!(content.lines().count(), 2);
+
+    let status = sleek()
+        .current_dir(dir.path())
+        .arg("--report-json")
+        .arg(&report)
+        .arg("clean")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let content = fs::read_to_string(&report).unwrap();
+    assert_eq!(content.lines().count(), 1);
+    let run: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(run["command"], "clean");
+    assert_eq!(run["args"], serde_json::json!([]));
+    assert_eq!(run["exit_code"], 0);
+    assert_eq!(run["success"], true);
+    assert!(run["duration_ms"].is_u64());
+    assert!(run["timestamp"].as_u64().unwrap() > 1_600_000_000);
+
+    // A report that can't be written is warned about; the run still counts.
+    fs::remove_file(&report).unwrap();
+    fs::create_dir(&report).unwrap();
+    let output = sleek()
+        .current_dir(dir.path())
+        .arg("--report-json")
+        .arg(&report)
+        .arg("build")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Could not write the run report"),
+        "{stderr}"
+    );
+    let stats = fs::read_to_string(dir.path().join("command_stats.json")).unwrap();
+    let stats: serde_json::Value = serde_json::from_str(&stats).unwrap();
+    assert_eq!(stats["commands"]["build"]["usage_count"], 3);
+}
+
+#[test]
+fn check_deps_porcelain_lists_unused_and_sets_exit_code() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"demo\"\n\n[dependencies]\nleftpad = \"1\"\n\n\
+         [build-dependencies]\ncc = \"1\"\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("Cargo.lock"), "").unwrap();
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .args(["check-deps", "--porcelain"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "unused\tleftpad\tdependencies\nunused\tcc\tbuild-dependencies\n"
+    );
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .args(["check-deps", "--porcelain", "--ignore", "leftpad,cc"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn check_deps_count_only_prints_the_number_of_unused() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"demo\"\n\n[dependencies]\nleftpad = \"1\"\n\
+         rand = \"0.8\"\nserde = \"1\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Cargo.lock"),
+        "[[package]]\nname = \"serde\"\nversion = \"1.0.200\"\n",
+    )
+    .unwrap();
+
+    let count = |args: &[&str]| {
+        let output = sleek()
+            .current_dir(dir.path())
+            .arg("check-deps")
+            .args(args)
+            .output()
+            .unwrap();
+        (
+            String::from_utf8_lossy(&output.stdout).into_owned(),
+            output.status.code(),
+        )
+    };
+    assert_eq!(count(&["--count-only"]), ("2\n".to_string(), Some(1)));
+    assert_eq!(
+        count(&["--count-only", "--ignore", "leftpad,rand"]),
+        ("0\n".to_string(), Some(0))
+    );
+}
+
+/// A stand-in cargo that appends the arguments of each call, one call per line, to `out`.
+#[cfg(unix)]
+fn recording_cargo(dir: &std::path::Path, out: &std::path::Path) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+    let script = dir.join("fake-cargo");
+    fs::write(
+        &script,
+        format!("#!/bin/sh\necho \"$*\" >> '{}'\n", out.display()),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+#[cfg(unix)]
+#[test]
+fn passthrough_arguments_reach_cargo_intact() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    let out = dir.path().join("args.txt");
+    let cargo = recording_cargo(dir.path(), &out);
+    // Skip the `cargo metadata` lookups made around the command itself.
+    let received = |args: &[&str]| {
+        fs::remove_file(&out).ok();
+        let status = sleek()
+            .current_dir(dir.path())
+            .env("CARGO", &cargo)
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let calls = fs::read_to_string(&out).unwrap();
+        calls
+            .lines()
+            .find(|call| !call.starts_with("metadata"))
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(received(&["build", "--release"]), "build --release");
+    assert_eq!(
+        received(&["run", "--release", "--", "--port", "8080"]),
+        "run --release -- --port 8080"
+    );
+    assert_eq!(
+        received(&["run", "--", "--port", "8080"]),
+        "run -- --port 8080"
+    );
+    assert_eq!(
+        received(&["test", "--", "--nocapture"]),
+        "test -- --nocapture"
+    );
+    assert_eq!(received(&["clean", "-y", "--doc"]), "clean --doc");
+    assert_eq!(
+        received(&["clippy", "--", "-D", "warnings"]),
+        "clippy -- -D warnings"
+    );
+    assert_eq!(received(&["fmt", "--check"]), "fmt --check");
+    assert_eq!(received(&["test", "-q"]), "test -q");
+
+    let stats = fs::read_to_string(dir.path().join("command_stats.json")).unwrap();
+    let stats: serde_json::Value = serde_json::from_str(&stats).unwrap();
+    assert_eq!(stats["commands"]["clippy"]["success_count"], 1);
+    assert_eq!(stats["commands"]["fmt"]["success_count"], 1);
+
+    fs::write(dir.path().join(".sleek-args"), "--features full\n").unwrap();
+    assert_eq!(
+        received(&["build", "--release"]),
+        "build --features full --release"
+    );
+    assert_eq!(received(&["fmt", "--check"]), "fmt --check");
+
+    // A release profile from .sleek-args still has its binary sizes looked up, and a
+    // failed lookup leaves the build successful.
+    fs::write(dir.path().join(".sleek-args"), "--profile release\n").unwrap();
+    assert_eq!(received(&["build"]), "build --profile release");
+    let calls = fs::read_to_string(&out).unwrap();
+    let after_build = calls.split("build --profile release").nth(1).unwrap();
+    assert!(after_build.contains("metadata"), "{calls}");
+}
+
+#[cfg(unix)]
+#[test]
+fn unknown_subcommands_are_passed_through_to_cargo() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    let out = dir.path().join("args.txt");
+    let cargo = recording_cargo(dir.path(), &out);
+
+    let status = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        .args(["nextest", "run", "--", "--nocapture"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let calls = fs::read_to_string(&out).unwrap();
+    assert_eq!(calls.lines().next(), Some("nextest run -- --nocapture"));
+    let stats = fs::read_to_string(dir.path().join("command_stats.json")).unwrap();
+    assert!(stats.contains("\"nextest\""));
+
+    let help = sleek().arg("help").output().unwrap();
+    assert!(String::from_utf8_lossy(&help.stdout).contains("passed through to cargo"));
+}
+
+#[test]
+fn leading_sleek_from_cargo_is_dropped() {
+    let dir = tempfile::tempdir().unwrap();
+    // Cargo runs `cargo sleek build --help` as `cargo-sleek sleek build --help`.
+    let help = sleek()
+        .current_dir(dir.path())
+        .args(["sleek", "build", "--help"])
+        .output()
+        .unwrap();
+    assert!(help.status.success());
+    assert!(String::from_utf8_lossy(&help.stdout).contains("Usage: cargo-sleek build"));
+
+    // A second `sleek` is never handed back to cargo, which would run sleek again.
+    let nested = sleek()
+        .current_dir(dir.path())
+        .args(["sleek", "sleek", "build"])
+        .output()
+        .unwrap();
+    assert!(!nested.status.success());
+    assert!(String::from_utf8_lossy(&nested.stderr).contains("can't be passed through"));
+    assert!(!dir.path().join("command_stats.json").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn failing_cargo_exit_code_is_propagated() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    let cargo = dir.path().join("failing-cargo");
+    fs::write(&cargo, "#!/bin/sh\nexit 3\n").unwrap();
+    fs::set_permissions(&cargo, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let status = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        .arg("build")
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(3));
+
+    fs::write(
+        dir.path().join(".sleek.toml"),
+        "[pipeline]\nsteps = [\"fmt\", \"build\"]\n",
+    )
+    .unwrap();
+    let status = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        .args(["pipeline", "--keep-going"])
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(3));
+}
+
+#[cfg(unix)]
+#[test]
+fn build_time_fails_when_the_measured_build_fails() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    let cargo = dir.path().join("failing-cargo");
+    fs::write(
+        &cargo,
+        "#!/bin/sh\n\
+         if [ \"$1\" = metadata ]; then\n  \
+         echo '{\"packages\":[],\"workspace_members\":[],\"target_directory\":\"target\"}'\n  \
+         exit 0\nfi\nexit 101\n",
+    )
+    .unwrap();
+    fs::set_permissions(&cargo, fs::Permissions::from_mode(0o755)).unwrap();
+
+    for args in [&["build-time"][..], &["build-time", "--check-delta"]] {
+        let output = sleek()
+            .current_dir(dir.path())
+            .env("CARGO", &cargo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(101), "{:?}", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("failed"));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn failing_pre_hook_skips_the_command() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    let out = dir.path().join("args.txt");
+    let cargo = recording_cargo(dir.path(), &out);
+    let config = dir.path().join(".sleek.toml");
+    let build = |args: &[&str]| {
+        fs::remove_file(&out).ok();
+        let output = sleek()
+            .current_dir(dir.path())
+            .env("CARGO", &cargo)
+            .args(args)
+            .output()
+            .unwrap();
+        let ran = fs::read_to_string(&out)
+            .unwrap_or_default()
+            .lines()
+            .any(|call| call.starts_with("build"));
+        (output, ran)
+    };
+
+    fs::write(
+        &config,
+        "[hooks.build]\npre = [\"exit 4\"]\npost = [\"touch post-ran\"]\n",
+    )
+    .unwrap();
+    let (output, ran) = build(&["build"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(!ran);
+    assert!(!dir.path().join("post-ran").exists());
+
+    let (output, ran) = build(&["build", "--no-hooks"]);
+    assert!(output.status.success());
+    assert!(ran);
+
+    fs::write(
+        &config,
+        "[hooks.build]\npre = [\"exit 4\"]\npost = [\"touch post-ran\"]\nallow_failure = true\n",
+    )
+    .unwrap();
+    let (output, ran) = build(&["build", "--verbose"]);
+    assert!(output.status.success());
+    assert!(ran);
+    assert!(dir.path().join("post-ran").exists());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Running pre hook: exit 4"));
+}
+
+#[test]
+fn stats_where_prints_the_configured_path() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(".sleek.toml"),
+        "stats_file = \"data/usage.json\"\n",
+    )
+    .unwrap();
+    let output = sleek()
+        .current_dir(dir.path())
+        .args(["stats", "--where"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let printed = String::from_utf8_lossy(&output.stdout);
+    let expected = dir.path().canonicalize().unwrap().join("data/usage.json");
+    assert_eq!(printed.trim_end(), expected.to_str().unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn aliases_run_the_expanded_command_and_track_both() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    fs::write(
+        dir.path().join(".sleek.toml"),
+        "[aliases]\nbr = \"build --release\"\n",
+    )
+    .unwrap();
+    let out = dir.path().join("args.txt");
+    let cargo = recording_cargo(dir.path(), &out);
+
+    let status = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        .args(["br", "--locked"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let calls = fs::read_to_string(&out).unwrap();
+    assert!(calls.lines().any(|call| call == "build --release --locked"));
+    let stats = fs::read_to_string(dir.path().join("command_stats.json")).unwrap();
+    let stats: serde_json::Value = serde_json::from_str(&stats).unwrap();
+    assert_eq!(stats["commands"]["br"]["usage_count"], 1);
+    assert_eq!(stats["commands"]["build"]["usage_count"], 1);
+
+    let listed = sleek()
+        .current_dir(dir.path())
+        .arg("aliases")
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&listed.stdout).contains("cargo build --release"));
+}
+
+#[test]
+fn help_lists_configured_aliases() {
+    let dir = tempfile::tempdir().unwrap();
+    let help = |dir: &std::path::Path| {
+        let output = sleek().current_dir(dir).arg("--help").output().unwrap();
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    assert!(!help(dir.path()).contains("Aliases (from .sleek.toml)"));
+
+    fs::write(
+        dir.path().join(".sleek.toml"),
+        "[aliases]\nt = \"test --workspace --all-features\"\nbr = \"build --release\"\n",
+    )
+    .unwrap();
+    let text = help(dir.path());
+    assert!(text.contains("Aliases (from .sleek.toml)"), "{text}");
+    assert!(text.contains("br  cargo build --release"));
+    assert!(text.contains("t   cargo test --workspace --all-features"));
+}
+
+#[test]
+fn malformed_config_does_not_break_help_or_version() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join(".sleek.toml"), "tracking = [\n").unwrap();
+    for flag in ["--help", "--version"] {
+        let output = sleek().current_dir(dir.path()).arg(flag).output().unwrap();
+        assert!(output.status.success(), "{flag} failed");
+    }
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .arg("stats")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(".sleek.toml"));
+}
+
+#[cfg(unix)]
+#[test]
+fn dry_run_prints_the_plan_without_running_anything() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    fs::write(
+        dir.path().join(".sleek.toml"),
+        "[hooks.build]\npre = [\"touch pre-ran\"]\n",
+    )
+    .unwrap();
+    let out = dir.path().join("args.txt");
+    let cargo = recording_cargo(dir.path(), &out);
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        .args(["chain", "build --release", "test", "--dry-run"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let plan: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.starts_with("cd "))
+        .collect();
+    assert_eq!(plan.len(), 3, "{stdout}");
+    assert!(plan[0].ends_with("sh -c 'touch pre-ran'"));
+    assert!(plan[1].ends_with("fake-cargo build --release"));
+    assert!(plan[2].ends_with("fake-cargo test"));
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        .args(["build", "--release", "--dry-run"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("fake-cargo build --release\n"));
+    assert!(!out.exists());
+    assert!(!dir.path().join("pre-ran").exists());
+    assert!(!dir.path().join("command_stats.json").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn quiet_drops_banners_and_plain_output_has_no_emoji() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    let run = |args: &[&str]| {
+        let output = sleek()
+            .current_dir(dir.path())
+            .env("CARGO", "false")
+            .args(args)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    // stdout is a pipe here, so plain output is the default.
+    let plain = run(&["build"]);
+    assert!(
+        plain.contains("info: Running Cargo command: build"),
+        "{plain}"
+    );
+    assert!(plain.contains("error: Command failed with exit code: 1"));
+    assert!(!plain.contains('🚀') && !plain.contains('\x1b'));
+
+    let quiet = run(&["build", "--quiet"]);
+    assert!(!quiet.contains("Running Cargo command"), "{quiet}");
+    assert!(quiet.contains("error: Command failed with exit code: 1"));
+
+    let aliases = run(&["aliases"]);
+    assert!(
+        aliases.starts_with("info: No aliases configured"),
+        "{aliases}"
+    );
+    let history = run(&["build-time", "--history"]);
+    assert_eq!(history, "info: No build history recorded yet.\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn retries_rerun_a_failing_command_and_record_only_the_outcome() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    // Fails twice, then succeeds.
+    let cargo = dir.path().join("flaky-cargo");
+    fs::write(
+        &cargo,
+        "#!/bin/sh\necho x >> attempts\n[ \"$(wc -l < attempts)\" -ge 3 ]\n",
+    )
+    .unwrap();
+    fs::set_permissions(&cargo, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        // Arguments after a passed-through subcommand belong to cargo.
+        .args(["--retries", "3", "--retry-delay", "0", "fetch"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Attempt 2/4 failed"), "{stdout}");
+    assert!(stdout.contains("Succeeded after 3 attempts"), "{stdout}");
+    let stats = fs::read_to_string(dir.path().join("command_stats.json")).unwrap();
+    let stats: serde_json::Value = serde_json::from_str(&stats).unwrap();
+    assert_eq!(stats["commands"]["fetch"]["usage_count"], 1);
+    assert_eq!(stats["commands"]["fetch"]["success_count"], 1);
+    assert_eq!(stats["commands"]["fetch"]["failure_count"], 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn log_dir_tees_output_into_a_log_that_logs_show_prints() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    let cargo = dir.path().join("noisy-cargo");
+    fs::write(
+        &cargo,
+        "#!/bin/sh\necho \"compiling $*\"\necho \"warning: unused\" >&2\nexit 2\n",
+    )
+    .unwrap();
+    fs::set_permissions(&cargo, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        .args(["--log-dir", "logs", "build", "--release"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    // Still streamed to the terminal unchanged.
+    assert!(String::from_utf8_lossy(&output.stdout).contains("compiling build --release"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("warning: unused"));
+
+    let logs: Vec<_> = fs::read_dir(dir.path().join("logs"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(logs.len(), 1);
+    assert!(logs[0].starts_with("build-") && logs[0].ends_with(".log"));
+
+    let shown = sleek()
+        .current_dir(dir.path())
+        .args(["logs", "--log-dir", "logs", "show", "1"])
+        .output()
+        .unwrap();
+    let log = String::from_utf8_lossy(&shown.stdout);
+    assert!(
+        log.starts_with("# command: cargo build --release\n"),
+        "{log}"
+    );
+    assert!(log.contains("compiling build --release"));
+    assert!(log.contains("warning: unused"));
+    assert!(log.trim_end().ends_with("# exit code: 2"), "{log}");
+
+    let listed = sleek()
+        .current_dir(dir.path())
+        .args(["--log-dir", "logs", "logs"])
+        .output()
+        .unwrap();
+    let listed = String::from_utf8_lossy(&listed.stdout);
+    assert!(
+        listed.contains(&logs[0]) && listed.contains("exit 2"),
+        "{listed}"
+    );
+}
+
+#[test]
+fn watch_dry_run_prints_the_command_once() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+
+    let output = sleek()
+        .current_dir(dir.path())
+        .args(["--dry-run", "watch", "--clear", "test", "--workspace"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("true test --workspace"), "{stdout}");
+    assert!(!dir.path().join("command_stats.json").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_json_stdout_holds_only_events() {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::fs::PermissionsExt;
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    fs::write(dir.path().join("src/main.rs"), "").unwrap();
+    // The first run hangs until a change restarts it; later ones finish at once.
+    fs::write(dir.path().join("hang"), "").unwrap();
+    let cargo = dir.path().join("noisy-cargo");
+    fs::write(
+        &cargo,
+        "#!/bin/sh\necho \"cargo output for $*\"\n[ -e hang ] && sleep 30\nexit 0\n",
+    )
+    .unwrap();
+    fs::set_permissions(&cargo, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut child = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        .args(["--verbose", "watch", "--json", "--debounce", "50", "test"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let (tx, lines) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let _ = tx.send(line.unwrap());
+        }
+    });
+    let next = || lines.recv_timeout(Duration::from_secs(20)).unwrap();
+
+    let mut events = vec![next()];
+    // Let the first run reach its `sleep` before changing a watched file.
+    std::thread::sleep(Duration::from_millis(500));
+    fs::remove_file(dir.path().join("hang")).unwrap();
+    fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+    events.extend((0..3).map(|_| next()));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let events: Vec<serde_json::Value> = events
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|_| panic!("not JSON: {line}")))
+        .collect();
+    let kinds: Vec<&str> = events
+        .iter()
+        .map(|e| e["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["start", "rebuild", "start", "rebuild"]);
+    assert_eq!(events[0]["trigger"], "start");
+    assert_eq!(events[1]["restarted"], true);
+    assert_eq!(events[2]["trigger"], "src/main.rs");
+    assert_eq!(events[3]["success"], true);
+    assert!(events[3].get("restarted").is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_times_are_recorded_only_when_asked_for() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+    let cargo = dir.path().join("test-cargo");
+    fs::write(
+        &cargo,
+        "#!/bin/sh\necho 'test slow_one ... ok <1.500s>'\n\
+         echo 'test result: ok. 1 passed; finished in 1.50s'\n",
+    )
+    .unwrap();
+    fs::set_permissions(&cargo, fs::Permissions::from_mode(0o755)).unwrap();
+    let recorded_runs = |args: &[&str]| {
+        let output = sleek()
+            .current_dir(dir.path())
+            .env("CARGO", &cargo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        assert!(stdout.contains("test slow_one ... ok"), "{stdout}");
+        fs::read_to_string(dir.path().join("build_history.json"))
+            .ok()
+            .map(|history| serde_json::from_str::<serde_json::Value>(&history).unwrap())
+            .map_or(0, |history| history["tests"].as_array().unwrap().len())
+    };
+
+    assert_eq!(recorded_runs(&["test"]), 0);
+    assert_eq!(recorded_runs(&["--record-test-times", "test"]), 1);
+    fs::write(dir.path().join(".sleek.toml"), "record_test_times = true\n").unwrap();
+    assert_eq!(recorded_runs(&["test"]), 2);
+
+    // An unwritable history costs only the test times, not the run or its stats.
+    let history = dir.path().join("build_history.json");
+    fs::remove_file(&history).unwrap();
+    fs::create_dir(&history).unwrap();
+    let output = sleek()
+        .current_dir(dir.path())
+        .env("CARGO", &cargo)
+        .arg("test")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Could not record test times"), "{stderr}");
+    let stats = fs::read_to_string(dir.path().join("command_stats.json")).unwrap();
+    let stats: serde_json::Value = serde_json::from_str(&stats).unwrap();
+    assert_eq!(stats["commands"]["test"]["usage_count"], 4);
+}
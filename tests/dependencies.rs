@@ -0,0 +1,202 @@
+use cargo_sleek::dependencies;
+use std::fs;
+use std::path::Path;
+
+fn write(root: &Path, relative: &str, content: &str) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+#[test]
+fn dev_only_dependencies_are_detected_and_moved() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        r#"[package]
+name = "demo"
+
+[dependencies]
+serde = "1"
+proptest = { version = "1.4", features = ["std"] } # property tests
+tempfile = "3"
+"#,
+    );
+    write(
+        root,
+        "src/lib.rs",
+        "use serde::Serialize;\n\n#[cfg(test)]\nmod tests {\n    use tempfile::tempdir;\n}\n",
+    );
+    write(root, "tests/props.rs", "use proptest::prelude::*;\n");
+
+    let dev_only = dependencies::find_dev_only_deps(root, &[]).unwrap();
+    assert_eq!(
+        dev_only,
+        vec!["proptest".to_string(), "tempfile".to_string()]
+    );
+
+    dependencies::move_to_dev_dependencies(root, &dev_only).unwrap();
+    assert_eq!(
+        dependencies::manifest_dependencies(root, "dependencies").unwrap(),
+        vec!["serde".to_string()]
+    );
+    let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    assert!(manifest.contains("[dev-dependencies]"));
+    assert!(manifest.contains(r#"proptest = { version = "1.4", features = ["std"] }"#));
+}
+
+#[test]
+fn workspace_members_are_checked_individually() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"alpha\", \"beta\"]\nresolver = \"2\"\n",
+    );
+    write(
+        root,
+        "alpha/Cargo.toml",
+        "[package]\nname = \"alpha\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nserde = \"1\"\n",
+    );
+    write(root, "alpha/src/lib.rs", "");
+    write(
+        root,
+        "beta/Cargo.toml",
+        "[package]\nname = \"beta\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nserde = \"1\"\nrand = \"0.8\"\n",
+    );
+    write(root, "beta/src/lib.rs", "");
+    write(
+        root,
+        "Cargo.lock",
+        "[[package]]\nname = \"alpha\"\n\n[[package]]\nname = \"beta\"\n\n[[package]]\nname = \"serde\"\n",
+    );
+
+    assert!(dependencies::is_workspace(root).unwrap());
+    let members = dependencies::workspace_members(root).unwrap();
+    let names: Vec<_> = members.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["alpha", "beta"]);
+
+    let lock = root.join("Cargo.lock");
+    let alpha = dependencies::find_unused_deps_with_lock(&members[0].dir, &lock, &[]).unwrap();
+    let beta = dependencies::find_unused_deps_with_lock(&members[1].dir, &lock, &[]).unwrap();
+    assert!(alpha.unused.is_empty());
+    assert_eq!(beta.unused, vec!["rand".to_string()]);
+}
+
+#[test]
+fn dependency_features_are_listed() {
+    let manifest = r#"
+[dependencies]
+anyhow = "1"
+serde = { version = "1.0", features = ["derive", "rc"] }
+tokio = { version = "1", default-features = false, features = ["rt"] }
+ring = { version = "0.17", default-features = false }
+
+[dev-dependencies]
+criterion = { version = "0.5", features = ["html_reports"] }
+"#;
+    let deps = dependencies::parse_dependency_features(manifest).unwrap();
+    let shown: Vec<String> = deps.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        shown,
+        [
+            "anyhow",
+            "serde [derive, rc]",
+            "tokio (no default features) [rt]",
+            "ring (no default features)",
+            "criterion [html_reports]",
+        ]
+    );
+    assert_eq!(deps[4].section, "dev-dependencies");
+    assert_eq!(deps[1].version.as_deref(), Some("1.0"));
+    let broken: Vec<_> = deps
+        .iter()
+        .filter(|d| d.possibly_broken())
+        .map(|d| &d.name)
+        .collect();
+    assert_eq!(broken, ["ring"]);
+}
+
+#[test]
+fn path_and_git_dependencies_are_not_flagged() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        r#"[package]
+name = "demo"
+
+[dependencies]
+# helpers shared with the CLI
+helpers = { path = "../helpers" }
+patched = { git = "https://example.com/patched.git", branch = "main" }
+serde = "1"
+rand = "0.8"
+"#,
+    );
+    write(
+        root,
+        "Cargo.lock",
+        "[[package]]\nname = \"demo\"\n\n[[package]]\nname = \"serde\"\n",
+    );
+
+    let report = dependencies::find_unused_deps(root, &[]).unwrap();
+    assert_eq!(report.unused, ["rand"]);
+    assert_eq!(report.local, ["helpers", "patched"]);
+}
+
+#[test]
+fn parallel_member_checks_are_complete_and_ordered() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let names = [
+        "zeta", "alpha", "mu", "delta", "omega", "beta", "kappa", "eta",
+    ];
+    let members: Vec<String> = names.iter().map(|n| format!("\"{n}\"")).collect();
+    write(
+        root,
+        "Cargo.toml",
+        &format!(
+            "[workspace]\nmembers = [{}]\nresolver = \"2\"\n",
+            members.join(", ")
+        ),
+    );
+    for name in names {
+        write(
+            root,
+            &format!("{name}/Cargo.toml"),
+            &format!(
+                "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+                 [dependencies]\nunused-{name} = \"1\"\n"
+            ),
+        );
+        write(root, &format!("{name}/src/lib.rs"), "");
+    }
+    write(root, "Cargo.lock", "");
+
+    let members = dependencies::workspace_members(root).unwrap();
+    let mut sorted = names.to_vec();
+    sorted.sort();
+    let expected: Vec<String> = sorted
+        .iter()
+        .map(|name| format!("unused\tunused-{name}\tdependencies"))
+        .collect();
+    for _ in 0..5 {
+        let findings =
+            dependencies::analyze_members(&members, &root.join("Cargo.lock"), &[]).unwrap();
+        let unused: Vec<String> = findings
+            .iter()
+            .flat_map(|f| f.report.unused.clone())
+            .collect();
+        let wanted: Vec<String> = sorted.iter().map(|name| format!("unused-{name}")).collect();
+        assert_eq!(unused, wanted);
+        assert_eq!(
+            dependencies::unused_porcelain_lines(root, &[]).unwrap(),
+            expected
+        );
+    }
+}
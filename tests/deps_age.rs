@@ -0,0 +1,28 @@
+use cargo_sleek::deps_age;
+use cargo_sleek::index::PublishedVersion;
+use chrono::{TimeZone, Utc};
+
+fn version(num: &str, created_at: &str, yanked: bool) -> PublishedVersion {
+    PublishedVersion {
+        num: num.to_string(),
+        created_at: created_at.to_string(),
+        yanked,
+    }
+}
+
+#[test]
+fn age_row_counts_months_and_newer_releases() {
+    let versions = vec![
+        version("1.0.0", "2023-01-01T00:00:00+00:00", false),
+        version("1.1.0", "2023-06-01T00:00:00+00:00", false),
+        version("1.2.0", "2023-09-01T00:00:00+00:00", true),
+        version("2.0.0-beta.1", "2023-10-01T00:00:00+00:00", false),
+        version("1.3.0", "2024-01-01T00:00:00+00:00", false),
+    ];
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    let row = deps_age::age_row("demo", "1.0.0", &versions, now).unwrap();
+    assert_eq!(row.age_months, 11);
+    assert_eq!(row.latest, "1.3.0");
+    assert_eq!(row.versions_behind, 2);
+}
@@ -0,0 +1,46 @@
+use cargo_sleek::{deps_count, metadata};
+
+fn package(id: &str) -> String {
+    format!(r#"{{ "id": "{id}", "name": "{id}", "manifest_path": "/x/{id}/Cargo.toml" }}"#)
+}
+
+fn node(id: &str, deps: &[&str]) -> String {
+    let deps: Vec<String> = deps.iter().map(|d| format!("\"{d}\"")).collect();
+    format!(
+        r#"{{ "id": "{id}", "dependencies": [{}] }}"#,
+        deps.join(",")
+    )
+}
+
+#[test]
+fn unique_contributions_exclude_shared_packages() {
+    // app -> heavy -> {a, b, shared}; app -> light -> shared
+    let ids = ["app", "heavy", "light", "a", "b", "shared"];
+    let packages: Vec<String> = ids.iter().map(|id| package(id)).collect();
+    let nodes = [
+        node("app", &["heavy", "light"]),
+        node("heavy", &["a", "b", "shared"]),
+        node("light", &["shared"]),
+        node("a", &[]),
+        node("b", &[]),
+        node("shared", &[]),
+    ];
+    let json = format!(
+        r#"{{ "packages": [{}], "workspace_members": ["app"], "target_directory": "/x/target",
+              "resolve": {{ "nodes": [{}] }} }}"#,
+        packages.join(","),
+        nodes.join(",")
+    );
+
+    let metadata = metadata::parse_metadata(&json).unwrap();
+    let count = deps_count::count_dependencies(&metadata).unwrap();
+
+    assert_eq!(count.total, 5);
+    assert_eq!(count.direct, 2);
+    assert_eq!(count.transitive, 3);
+    assert_eq!(count.contributors[0].name, "heavy");
+    assert_eq!(count.contributors[0].unique_transitive, 2);
+    assert_eq!(count.contributors[1].name, "light");
+    assert_eq!(count.contributors[1].unique_transitive, 0);
+    assert_eq!(metadata.direct_dependency_names(), ["heavy", "light"]);
+}
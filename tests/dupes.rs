@@ -0,0 +1,96 @@
+use cargo_sleek::dupes::{self, Duplicate};
+use cargo_sleek::lockfile;
+
+const LOCK: &str = r#"
+version = 3
+
+[[package]]
+name = "demo"
+version = "0.1.0"
+
+[[package]]
+name = "bitflags"
+version = "1.3.2"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "bitflags"
+version = "2.4.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "rand"
+version = "0.8.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.10"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.48"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+#[test]
+fn duplicates_are_unified_within_semver_compatible_versions() {
+    let lock = lockfile::parse_lockfile(LOCK).unwrap();
+    let duplicates = dupes::find_duplicates(&lock);
+    assert_eq!(
+        duplicates,
+        vec![
+            Duplicate {
+                name: "bitflags".into(),
+                versions: vec!["1.3.2".into(), "2.4.0".into()],
+            },
+            Duplicate {
+                name: "rand".into(),
+                versions: vec!["0.8.4".into(), "0.8.5".into()],
+            },
+            Duplicate {
+                name: "syn".into(),
+                versions: vec!["1.0.109".into(), "2.0.10".into(), "2.0.48".into()],
+            },
+        ]
+    );
+
+    let plan = dupes::plan_dedupe(&duplicates);
+    let commands: Vec<String> = plan.updates.iter().map(|u| u.display()).collect();
+    assert_eq!(
+        commands,
+        vec![
+            "cargo update -p rand@0.8.4 --precise 0.8.5",
+            "cargo update -p syn@2.0.10 --precise 2.0.48",
+        ]
+    );
+    let skipped: Vec<(&str, Vec<String>)> = plan
+        .skipped
+        .iter()
+        .map(|s| (s.name.as_str(), s.versions.clone()))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            ("bitflags", vec!["1.3.2".to_string(), "2.4.0".to_string()]),
+            ("syn", vec!["1.0.109".to_string(), "2.0.48".to_string()]),
+        ]
+    );
+    assert!(plan.skipped[0].reason.contains("semver-incompatible"));
+}
@@ -0,0 +1,211 @@
+use cargo_sleek::executor;
+
+#[test]
+fn cargo_bin_prefers_cargo_env_var() {
+    std::env::set_var("CARGO", "/opt/toolchains/custom/bin/cargo");
+    assert_eq!(executor::cargo_bin(), "/opt/toolchains/custom/bin/cargo");
+
+    std::env::remove_var("CARGO");
+    assert_eq!(executor::cargo_bin(), "cargo");
+}
+
+#[test]
+fn truncated_output_keeps_the_tail() {
+    let mut output = "warning: noise\n".repeat(1_000).into_bytes();
+    output.extend_from_slice(b"error[E0425]: cannot find value `x`\n");
+
+    let kept = executor::truncate_output(&output, 64);
+    let text = String::from_utf8(kept).unwrap();
+    assert!(text.starts_with(executor::TRUNCATED_MARKER));
+    assert!(text.ends_with("error[E0425]: cannot find value `x`\n"));
+    assert!(text.len() < 64 + 40);
+
+    assert_eq!(executor::truncate_output(b"short", 64), b"short");
+}
+
+#[test]
+fn truncation_does_not_split_characters() {
+    let kept = executor::truncate_output("ééé".as_bytes(), 3);
+    let text = String::from_utf8(kept).unwrap();
+    assert!(text.ends_with("\né"));
+}
+
+#[test]
+fn env_pairs_are_applied_to_the_command() {
+    assert_eq!(
+        executor::parse_env_pair("RUST_LOG=debug,hyper=info"),
+        Ok(("RUST_LOG".to_string(), "debug,hyper=info".to_string()))
+    );
+    assert_eq!(
+        executor::parse_env_pair("EMPTY="),
+        Ok(("EMPTY".to_string(), String::new()))
+    );
+    assert!(executor::parse_env_pair("RUST_LOG")
+        .unwrap_err()
+        .contains("missing `=`"));
+    assert!(executor::parse_env_pair("=debug").is_err());
+
+    let options = executor::ExecOptions {
+        env: vec![("RUST_LOG".to_string(), "debug".to_string())],
+        ..Default::default()
+    };
+    let cmd = executor::cargo_command("run", &["--release".to_string()], &options);
+    let envs: Vec<_> = cmd.get_envs().collect();
+    assert_eq!(
+        envs,
+        [(
+            std::ffi::OsStr::new("RUST_LOG"),
+            Some(std::ffi::OsStr::new("debug"))
+        )]
+    );
+    let args: Vec<_> = cmd.get_args().collect();
+    assert_eq!(args, ["run", "--release"]);
+}
+
+#[test]
+fn freed_space_message_reports_the_difference() {
+    assert_eq!(
+        executor::freed_space_message(1_288_490_189, 0),
+        "🧹 Freed 1.20 GB"
+    );
+    assert_eq!(
+        executor::freed_space_message(3 * 1024 * 1024, 1024 * 1024),
+        "🧹 Freed 2.00 MB"
+    );
+    // A directory that grew (e.g. another build running) frees nothing.
+    assert_eq!(executor::freed_space_message(10, 20), "🧹 Freed 0 B");
+}
+
+#[test]
+fn clean_target_dir_honours_target_dir_argument() {
+    let args = ["--target-dir".to_string(), "/tmp/custom-target".to_string()];
+    assert_eq!(
+        executor::clean_target_dir(&args),
+        std::path::PathBuf::from("/tmp/custom-target")
+    );
+    assert_eq!(
+        executor::clean_target_dir(&["--target-dir=out".to_string()]),
+        std::path::PathBuf::from("out")
+    );
+}
+
+#[test]
+fn release_builds_are_recognised_by_flag_or_profile() {
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    assert!(executor::builds_release(&args(&["--release"])));
+    assert!(executor::builds_release(&args(&["-r", "--locked"])));
+    assert!(executor::builds_release(&args(&["--profile", "release"])));
+    assert!(executor::builds_release(&args(&["--profile=release"])));
+    assert!(!executor::builds_release(&args(&["--profile", "dev"])));
+    assert!(!executor::builds_release(&args(&[
+        "--release",
+        "--profile=dev"
+    ])));
+    assert!(!executor::builds_release(&args(&["--", "--release"])));
+}
+
+#[test]
+fn declined_confirmation_is_a_cancelled_error() {
+    let options = executor::ExecOptions {
+        confirm: vec!["clean".to_string()],
+        ..Default::default()
+    };
+    let mut shown = Vec::new();
+    // Closed stdin at the prompt reads as no.
+    let err =
+        executor::confirm_or_cancel("clean", &options, true, &b""[..], &mut shown).unwrap_err();
+    let cancelled = err.downcast_ref::<executor::Cancelled>().unwrap();
+    assert_eq!(cancelled.to_string(), "Cancelled `cargo clean`.");
+
+    let confirmed = executor::confirm_or_cancel("clean", &options, true, &b"y\n"[..], &mut shown);
+    assert!(confirmed.is_ok());
+    assert!(executor::confirm_or_cancel("clean", &options, false, &b""[..], &mut shown).is_ok());
+}
+
+#[test]
+fn project_args_file_precedes_command_line_args() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(executor::load_project_args(dir.path()).unwrap().is_empty());
+
+    std::fs::write(
+        dir.path().join(executor::ARGS_FILE),
+        "--features full\n--locked\n",
+    )
+    .unwrap();
+    let options = executor::ExecOptions {
+        default_args: executor::load_project_args(dir.path()).unwrap(),
+        ..Default::default()
+    };
+    let cmd = executor::cargo_command("build", &["--release".to_string()], &options);
+    let args: Vec<_> = cmd.get_args().collect();
+    assert_eq!(
+        args,
+        ["build", "--features", "full", "--locked", "--release"]
+    );
+
+    assert_eq!(
+        executor::project_args_for("clippy", dir.path()).unwrap(),
+        ["--features", "full", "--locked"]
+    );
+    for command in ["clean", "fmt", "update"] {
+        assert!(executor::project_args_for(command, dir.path())
+            .unwrap()
+            .is_empty());
+    }
+}
+
+#[test]
+fn spinner_only_appears_on_a_terminal() {
+    assert!(executor::spinner("cargo test", true, || false).is_none());
+    assert!(executor::spinner("cargo test", false, || true).is_none());
+    let spinner = executor::spinner("cargo test", true, || true).unwrap();
+    assert_eq!(spinner.message(), "cargo test");
+    spinner.finish_and_clear();
+}
+
+#[cfg(unix)]
+#[test]
+fn exit_code_mirrors_the_child_status() {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+    // Raw wait statuses: exit code in the high byte, terminating signal in the low bits.
+    assert_eq!(executor::exit_code(ExitStatus::from_raw(3 << 8)), 3);
+    assert_eq!(executor::exit_code(ExitStatus::from_raw(0)), 0);
+    assert_eq!(executor::exit_code(ExitStatus::from_raw(9)), 137);
+}
+
+#[test]
+fn dry_run_lines_are_shell_quoted() {
+    assert_eq!(executor::shell_quote("--release"), "--release");
+    assert_eq!(executor::shell_quote(""), "''");
+    assert_eq!(executor::shell_quote("a b"), "'a b'");
+    assert_eq!(executor::shell_quote("it's"), r"'it'\''s'");
+
+    let mut cmd = std::process::Command::new("cargo");
+    cmd.args(["test", "--", "name with space"])
+        .env("RUSTFLAGS", "-C target-cpu=native")
+        .current_dir("/work/my crate");
+    assert_eq!(
+        executor::render_command(&cmd),
+        "cd '/work/my crate' && RUSTFLAGS='-C target-cpu=native' cargo test -- 'name with space'"
+    );
+}
+
+#[test]
+fn retry_delay_doubles_per_attempt() {
+    use std::time::Duration;
+    let base = Duration::from_millis(500);
+    assert_eq!(executor::retry_delay(base, 1), base);
+    assert_eq!(executor::retry_delay(base, 2), Duration::from_secs(1));
+    assert_eq!(executor::retry_delay(base, 4), Duration::from_secs(4));
+    assert_eq!(executor::retry_delay(Duration::ZERO, 5), Duration::ZERO);
+}
+
+#[cfg(unix)]
+#[test]
+fn runs_killed_by_a_signal_are_not_retried() {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+    assert!(executor::killed_by_signal(ExitStatus::from_raw(2)));
+    assert!(!executor::killed_by_signal(ExitStatus::from_raw(101 << 8)));
+}
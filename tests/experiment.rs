@@ -0,0 +1,58 @@
+use cargo_sleek::experiment::{self, ExperimentResult, Knobs};
+use std::time::Duration;
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn matrix_covers_every_combination() {
+    let matrix = experiment::experiment_matrix(&[1, 16], &strings(&["off", "fat"]), &[]);
+    let labels: Vec<(u32, &str)> = matrix
+        .iter()
+        .map(|k| (k.codegen_units, k.lto.as_str()))
+        .collect();
+    assert_eq!(labels, [(1, "off"), (1, "fat"), (16, "off"), (16, "fat")]);
+    assert!(matrix.iter().all(|k| k.opt_level.is_none()));
+
+    let matrix = experiment::experiment_matrix(&[1], &strings(&["thin"]), &strings(&["3", "s"]));
+    assert_eq!(matrix.len(), 2);
+    assert_eq!(
+        matrix[1].env_vars(),
+        [
+            (
+                "CARGO_PROFILE_RELEASE_CODEGEN_UNITS".to_string(),
+                "1".to_string()
+            ),
+            ("CARGO_PROFILE_RELEASE_LTO".to_string(), "thin".to_string()),
+            (
+                "CARGO_PROFILE_RELEASE_OPT_LEVEL".to_string(),
+                "s".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn pareto_front_drops_dominated_settings() {
+    let result = |lto: &str, secs, bytes| ExperimentResult {
+        knobs: Knobs {
+            codegen_units: 16,
+            lto: lto.into(),
+            opt_level: None,
+        },
+        duration: Duration::from_secs(secs),
+        binary_bytes: bytes,
+    };
+    let results = [
+        result("off", 10, 900),
+        result("thin", 14, 700),
+        result("fat", 30, 650),
+        // Slower and bigger than thin.
+        result("thin", 20, 800),
+    ];
+    assert_eq!(
+        experiment::pareto_front(&results),
+        [true, true, true, false]
+    );
+}
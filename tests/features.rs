@@ -0,0 +1,44 @@
+use cargo_sleek::features;
+
+#[test]
+fn orphaned_optionals_and_bad_feature_targets_are_reported() {
+    let manifest = r#"
+[package]
+name = "demo"
+
+[dependencies]
+serde = { version = "1", optional = true }
+tokio = { version = "1", optional = true }
+rayon = { version = "1", optional = true }
+log = "0.4"
+
+[target.'cfg(unix)'.dependencies]
+nix = { version = "0.27", optional = true }
+
+[features]
+default = ["json"]
+json = ["dep:serde", "serde?/std"]
+parallel = ["rayon"]
+unix = ["dep:nix"]
+logging = ["dep:log"]
+broken = ["missing/feature", "log?/std", "ghost"]
+"#;
+
+    let report = features::analyze_features(manifest).unwrap();
+    assert_eq!(report.orphaned_optional, vec!["tokio".to_string()]);
+
+    let issues: Vec<_> = report
+        .issues
+        .iter()
+        .map(|i| (i.feature.as_str(), i.value.as_str()))
+        .collect();
+    assert_eq!(
+        issues,
+        [
+            ("broken", "missing/feature"),
+            ("broken", "log?/std"),
+            ("broken", "ghost"),
+            ("logging", "dep:log"),
+        ]
+    );
+}
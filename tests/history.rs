@@ -0,0 +1,150 @@
+use cargo_sleek::history::{self, BuildHistory, BuildRecord, BuildSlice};
+
+fn run(timestamp: u64, duration_ms: u64) -> BuildRecord {
+    BuildRecord {
+        timestamp,
+        duration_ms,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn worst_build_is_the_longest_recorded() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.json");
+
+    let mut seeded = BuildHistory::default();
+    for (timestamp, duration_ms) in [(1, 4_000), (2, 95_000), (3, 12_000), (4, 7_500)] {
+        seeded.record(run(timestamp, duration_ms));
+    }
+    history::save_history(&path, &seeded).unwrap();
+
+    let loaded = history::load_history(&path);
+    assert_eq!(loaded.worst, Some(run(2, 95_000)));
+    assert_eq!(
+        loaded.rolling_average_ms,
+        (4_000 + 95_000 + 12_000 + 7_500) / 4
+    );
+}
+
+#[test]
+fn worst_and_average_cover_the_latest_runs_profile_and_kind() {
+    let mut seeded = BuildHistory::default();
+    for (timestamp, duration_ms) in [(1, 10_000), (2, 20_000)] {
+        seeded.record(run(timestamp, duration_ms));
+    }
+    let cold = BuildRecord {
+        kind: Some("cold".into()),
+        ..run(3, 300_000)
+    };
+    seeded.record(cold.clone());
+    assert_eq!(seeded.worst, Some(cold));
+    assert_eq!(seeded.rolling_average_ms, 300_000);
+
+    seeded.record(BuildRecord {
+        kind: Some("incremental-check".into()),
+        ..run(4, 300)
+    });
+    seeded.record(run(5, 12_000));
+    assert_eq!(seeded.worst, Some(run(2, 20_000)));
+    assert_eq!(seeded.rolling_average_ms, (10_000 + 20_000 + 12_000) / 3);
+}
+
+#[test]
+fn median_ignores_other_profiles_and_detects_slowdown() {
+    let mut seeded = BuildHistory::default();
+    for (timestamp, duration_ms) in [(1, 10_000), (2, 12_000), (3, 11_000), (4, 30_000)] {
+        seeded.record(run(timestamp, duration_ms));
+    }
+    seeded.record(BuildRecord {
+        profile: Some("dist".into()),
+        ..run(5, 90_000)
+    });
+
+    seeded.record(BuildRecord {
+        kind: Some("cold".into()),
+        ..run(6, 120_000)
+    });
+
+    let slice = |profile: Option<&str>, kind: Option<&str>| BuildSlice {
+        profile: profile.map(String::from),
+        kind: kind.map(String::from),
+        ..Default::default()
+    };
+    assert_eq!(seeded.median_ms(&slice(None, None)), Some(11_500));
+    assert_eq!(seeded.median_ms(&slice(Some("dist"), None)), Some(90_000));
+    assert_eq!(seeded.median_ms(&slice(Some("release"), None)), None);
+    assert_eq!(seeded.median_ms(&slice(None, Some("cold"))), Some(120_000));
+
+    let percent = history::slowdown_percent(14_145, 11_500);
+    assert!((percent - 23.0).abs() < 0.01);
+    assert!(percent > history::REGRESSION_WARNING);
+}
+
+#[test]
+fn median_keeps_feature_sets_and_targets_apart() {
+    let mut seeded = BuildHistory::default();
+    seeded.record(run(1, 10_000));
+    seeded.record(BuildRecord {
+        features: Some("full".into()),
+        ..run(2, 40_000)
+    });
+    seeded.record(BuildRecord {
+        target: Some("wasm32-unknown-unknown".into()),
+        ..run(3, 25_000)
+    });
+
+    assert_eq!(seeded.median_ms(&BuildSlice::default()), Some(10_000));
+    let full = BuildSlice {
+        features: Some("full".into()),
+        ..Default::default()
+    };
+    assert_eq!(seeded.median_ms(&full), Some(40_000));
+    let wasm = BuildSlice::of(seeded.runs.last().unwrap());
+    assert_eq!(seeded.median_ms(&wasm), Some(25_000));
+    assert_eq!(wasm.label(), "dev (wasm32-unknown-unknown)");
+    assert_eq!(seeded.rolling_average_ms, 25_000);
+}
+
+#[test]
+fn baselines_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.json");
+
+    let mut seeded = BuildHistory::default();
+    let reference = BuildRecord {
+        commit: Some("abc1234".into()),
+        crate_count: 42,
+        ..run(1, 20_000)
+    };
+    seeded
+        .baselines
+        .insert("before-syn2".into(), reference.clone());
+    history::save_history(&path, &seeded).unwrap();
+
+    let loaded = history::load_history(&path);
+    assert_eq!(loaded.baselines.get("before-syn2"), Some(&reference));
+    assert!(history::compare_with_baseline(&loaded, "missing", &run(2, 1)).is_err());
+}
+
+#[test]
+fn unparsable_history_is_moved_aside_before_it_can_be_overwritten() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("build_history.json");
+    std::fs::write(&path, r#"{"runs": [{"timestamp": "hand-edited"}]}"#).unwrap();
+
+    assert!(history::load_history(&path).runs.is_empty());
+    assert!(!path.exists());
+    let backups: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(backups.len(), 1);
+    assert!(backups[0].starts_with("build_history.") && backups[0].ends_with(".bak.json"));
+
+    history::save_history(&path, &BuildHistory::default()).unwrap();
+    let backup = dir.path().join(&backups[0]);
+    assert!(std::fs::read_to_string(backup)
+        .unwrap()
+        .contains("hand-edited"));
+}
@@ -0,0 +1,36 @@
+use cargo_sleek::{dependencies, stats};
+use std::fs;
+
+#[test]
+fn track_command_accumulates_usage() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.json");
+
+    stats::track_command(&path, "build").unwrap();
+    stats::track_command(&path, "build").unwrap();
+    stats::track_command(&path, "test").unwrap();
+
+    let loaded = stats::load_stats(&path);
+    assert_eq!(loaded["build"].usage_count, 2);
+    assert_eq!(loaded["test"].usage_count, 1);
+    assert!(loaded["build"].last_used > 0);
+}
+
+#[test]
+fn find_unused_deps_respects_ignore_list() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"demo\"\n\n[dependencies]\nserde = \"1\"\nopenssl-sys = \"0.9\"\nrand = \"0.8\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Cargo.lock"),
+        "[[package]]\nname = \"serde\"\n",
+    )
+    .unwrap();
+
+    let report = dependencies::find_unused_deps(dir.path(), &["openssl-sys".to_string()]).unwrap();
+    assert_eq!(report.unused, vec!["rand".to_string()]);
+    assert_eq!(report.ignored, vec!["openssl-sys".to_string()]);
+}
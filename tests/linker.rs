@@ -0,0 +1,64 @@
+use cargo_sleek::linker::{self, Linker};
+use semver::Version;
+
+const TRIPLE: &str = "x86_64-unknown-linux-gnu";
+
+#[test]
+fn detects_linker_from_config_and_rustflags() {
+    let mold = r#"
+[target.x86_64-unknown-linux-gnu]
+linker = "clang"
+rustflags = ["-C", "link-arg=-fuse-ld=mold"]
+"#
+    .to_string();
+    assert_eq!(
+        linker::detect_linker(std::slice::from_ref(&mold), None, TRIPLE),
+        Linker::Mold
+    );
+    // Rustflags from the environment replace configured ones.
+    assert_eq!(
+        linker::detect_linker(&[mold], Some("-C target-cpu=native"), TRIPLE),
+        Linker::Custom("clang".to_string())
+    );
+
+    let build_flags = "[build]\nrustflags = \"-C link-arg=-fuse-ld=lld\"".to_string();
+    assert_eq!(
+        linker::detect_linker(&[build_flags], None, TRIPLE),
+        Linker::Lld
+    );
+
+    let other_target = "[target.aarch64-apple-darwin]\nlinker = \"ld64.lld\"".to_string();
+    assert_eq!(
+        linker::detect_linker(&[other_target], None, TRIPLE),
+        Linker::Default
+    );
+    assert_eq!(
+        linker::detect_linker(&[], Some("-Clinker=/usr/bin/ld.mold"), TRIPLE),
+        Linker::Mold
+    );
+}
+
+#[test]
+fn knows_bundled_lld_default() {
+    assert!(linker::uses_rust_lld_by_default(
+        TRIPLE,
+        &Version::new(1, 90, 0)
+    ));
+    assert!(!linker::uses_rust_lld_by_default(
+        TRIPLE,
+        &Version::new(1, 89, 0)
+    ));
+    assert!(!linker::uses_rust_lld_by_default(
+        "aarch64-unknown-linux-gnu",
+        &Version::new(1, 95, 0)
+    ));
+}
+
+#[test]
+fn suggests_snippet_for_platform() {
+    let linux = linker::suggestion(TRIPLE);
+    assert!(linux.contains("[target.x86_64-unknown-linux-gnu]"));
+    assert!(linux.contains("-fuse-ld=mold"));
+    assert!(linker::suggestion("x86_64-pc-windows-msvc").contains("rust-lld.exe"));
+    assert!(linker::suggestion("aarch64-apple-darwin").contains("-fuse-ld=lld"));
+}
@@ -0,0 +1,51 @@
+use cargo_sleek::lint_versions::{self, LintKind, VersionStyle};
+use cargo_sleek::lockfile;
+
+const MANIFEST: &str = r#"[package]
+name = "demo"
+
+[dependencies]
+anyhow = "1" # error handling
+rand = "=0.8.5"
+serde = { version = "^1.0.3", features = ["derive"] }
+glob = "*"
+shared = { workspace = true }
+log = "0.4.20"
+"#;
+
+const LOCK: &str = r#"
+[[package]]
+name = "anyhow"
+version = "1.0.86"
+
+[[package]]
+name = "glob"
+version = "0.3.1"
+"#;
+
+#[test]
+fn lints_and_fixes_requirement_styles() {
+    let lock = lockfile::parse_lockfile(LOCK).unwrap();
+    let lints = lint_versions::lint_manifest(MANIFEST, &lock, VersionStyle::Full).unwrap();
+
+    let summary: Vec<_> = lints
+        .iter()
+        .map(|l| (l.name.as_str(), l.kind, l.suggested.as_deref()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("anyhow", LintKind::TooBroad, Some("1.0.86")),
+            ("rand", LintKind::ExactPin, Some("0.8.5")),
+            ("serde", LintKind::CaretPrefix, Some("1.0.3")),
+            ("glob", LintKind::Wildcard, Some("0.3.1")),
+        ]
+    );
+
+    let fixed = lint_versions::apply_fixes(MANIFEST, &lints).unwrap();
+    assert!(fixed.contains(r#"anyhow = "1.0.86" # error handling"#));
+    assert!(fixed.contains(r#"rand = "0.8.5""#));
+    assert!(fixed.contains(r#"serde = { version = "1.0.3", features = ["derive"] }"#));
+    assert!(fixed.contains(r#"glob = "0.3.1""#));
+    assert!(fixed.contains("shared = { workspace = true }"));
+}
@@ -0,0 +1,61 @@
+use cargo_sleek::{lock_diff, lockfile};
+
+#[test]
+fn lock_diff_classifies_changes() {
+    let old = lockfile::parse_lockfile(
+        r#"
+[[package]]
+name = "serde"
+version = "1.0.190"
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+
+[[package]]
+name = "log"
+version = "0.4.20"
+
+[[package]]
+name = "gone"
+version = "1.0.0"
+"#,
+    )
+    .unwrap();
+    let new = lockfile::parse_lockfile(
+        r#"
+[[package]]
+name = "serde"
+version = "1.0.200"
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+
+[[package]]
+name = "log"
+version = "0.4.19"
+
+[[package]]
+name = "fresh"
+version = "2.0.0"
+
+[[package]]
+name = "fresh-dep"
+version = "0.1.0"
+"#,
+    )
+    .unwrap();
+
+    let diff = lock_diff::diff_lockfiles(&old, &new);
+    let added: Vec<_> = diff.added.iter().map(|p| p.name.as_str()).collect();
+    let removed: Vec<_> = diff.removed.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(added, ["fresh", "fresh-dep"]);
+    assert_eq!(removed, ["gone"]);
+    assert_eq!(diff.upgraded.len(), 1);
+    assert_eq!(diff.upgraded[0].name, "serde");
+    assert_eq!(diff.upgraded[0].to, "1.0.200");
+    assert_eq!(diff.downgraded.len(), 1);
+    assert_eq!(diff.downgraded[0].from, "0.4.20");
+    assert_eq!(diff.net_change, 1);
+}
@@ -0,0 +1,33 @@
+use cargo_sleek::executor;
+use cargo_sleek::logs;
+use chrono::{Local, TimeZone};
+use std::time::Duration;
+
+#[test]
+fn log_names_and_footer_follow_the_documented_format() {
+    let started = Local.with_ymd_and_hms(2024, 6, 1, 10, 22, 33).unwrap();
+    assert_eq!(
+        logs::log_file_name("build", started),
+        "build-2024-06-01T10-22-33.log"
+    );
+    assert_eq!(
+        logs::log_footer(Duration::from_millis(1500), 101),
+        "\n# duration: 1.50s\n# exit code: 101\n"
+    );
+}
+
+#[test]
+fn terminal_env_keeps_colors_and_progress_unless_overridden() {
+    let env = executor::terminal_env(true, true, Some(120), |_| false);
+    assert_eq!(
+        env,
+        vec![
+            ("CARGO_TERM_COLOR".to_string(), "always".to_string()),
+            ("CARGO_TERM_PROGRESS_WHEN".to_string(), "always".to_string()),
+            ("CARGO_TERM_PROGRESS_WIDTH".to_string(), "120".to_string()),
+        ]
+    );
+    assert!(executor::terminal_env(false, true, Some(120), |_| false).is_empty());
+    let user_set = executor::terminal_env(true, true, None, |key| key == "CARGO_TERM_COLOR");
+    assert!(user_set.is_empty());
+}
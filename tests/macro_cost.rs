@@ -0,0 +1,66 @@
+use cargo_sleek::macro_cost::{self, CostKind};
+use cargo_sleek::{metadata, timings};
+
+const METADATA: &str = r#"{
+  "packages": [
+    {"id": "app", "name": "app", "version": "0.1.0", "manifest_path": "/w/app/Cargo.toml",
+     "targets": [{"name": "app", "kind": ["bin"]}]},
+    {"id": "derive", "name": "my-derive", "version": "1.0.0", "manifest_path": "/r/d/Cargo.toml",
+     "targets": [{"name": "my_derive", "kind": ["proc-macro"]}]},
+    {"id": "sys", "name": "zlib-sys", "version": "0.2.0", "manifest_path": "/r/z/Cargo.toml",
+     "targets": [{"name": "zlib_sys", "kind": ["lib"]},
+                 {"name": "build-script-build", "kind": ["custom-build"]}]},
+    {"id": "mid", "name": "mid", "version": "0.3.0", "manifest_path": "/r/m/Cargo.toml",
+     "targets": [{"name": "mid", "kind": ["lib"]}]}
+  ],
+  "workspace_members": ["app"],
+  "target_directory": "/w/app/target",
+  "resolve": {"nodes": [
+    {"id": "app", "dependencies": ["mid", "derive"]},
+    {"id": "mid", "dependencies": ["derive", "sys"]},
+    {"id": "derive", "dependencies": []},
+    {"id": "sys", "dependencies": []}
+  ]}
+}"#;
+
+const REPORT: &str = r#"const UNIT_DATA = [
+  {"i": 0, "name": "my-derive", "version": "1.0.0", "target": "", "start": 0.0, "duration": 3.0,
+   "unblocked_units": [3], "unblocked_rmeta_units": []},
+  {"i": 1, "name": "zlib-sys", "version": "0.2.0", "target": " build-script", "start": 0.0, "duration": 1.0,
+   "unblocked_units": [2], "unblocked_rmeta_units": []},
+  {"i": 2, "name": "zlib-sys", "version": "0.2.0", "target": " build-script (run)", "start": 1.0, "duration": 4.0,
+   "unblocked_units": [4], "unblocked_rmeta_units": []},
+  {"i": 4, "name": "zlib-sys", "version": "0.2.0", "target": "", "start": 5.0, "duration": 0.5,
+   "unblocked_units": [3], "unblocked_rmeta_units": []},
+  {"i": 3, "name": "mid", "version": "0.3.0", "target": "", "start": 5.5, "duration": 1.0,
+   "unblocked_units": [5], "unblocked_rmeta_units": []},
+  {"i": 5, "name": "app", "version": "0.1.0", "target": " app \"bin\"", "start": 6.5, "duration": 1.0,
+   "unblocked_units": [], "unblocked_rmeta_units": []}
+];"#;
+
+#[test]
+fn ranks_macros_and_build_scripts_by_blocking_impact() {
+    let metadata = metadata::parse_metadata(METADATA).unwrap();
+    let units = timings::parse_timing_html(REPORT).unwrap();
+
+    let dependents = macro_cost::dependent_counts(&metadata).unwrap();
+    assert_eq!(dependents["derive"], 2);
+    assert_eq!(dependents["sys"], 2);
+    assert_eq!(dependents["app"], 0);
+
+    let costs = macro_cost::macro_costs(&metadata, &units).unwrap();
+    assert_eq!(costs.len(), 2);
+    assert_eq!(costs[0].name, "zlib-sys");
+    assert_eq!(costs[0].kind, CostKind::BuildScript);
+    // Compiling and running the build script, not the library itself.
+    assert_eq!(costs[0].seconds, 5.0);
+    // The build script also holds up zlib-sys itself.
+    assert_eq!(costs[0].dependents, 3);
+    assert_eq!(costs[0].blocking(), 15.0);
+    assert!(costs[0].critical);
+
+    assert_eq!(costs[1].name, "my-derive");
+    assert_eq!(costs[1].kind, CostKind::ProcMacro);
+    assert_eq!(costs[1].seconds, 3.0);
+    assert!(!costs[1].critical);
+}
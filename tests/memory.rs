@@ -0,0 +1,57 @@
+use cargo_sleek::memory::{self, ProcessMemory};
+
+fn process(pid: u32, ppid: u32, rss_kib: u64) -> ProcessMemory {
+    ProcessMemory {
+        pid,
+        ppid,
+        rss_bytes: rss_kib * 1024,
+    }
+}
+
+#[test]
+fn sums_rss_over_the_process_tree() {
+    let output = "    1     0   4000\n  100     1  20000\n  101   100 500000\n  102   101 300000\n  200     1  90000\n";
+    let processes = memory::parse_ps_output(output);
+    assert_eq!(processes.len(), 5);
+    assert_eq!(processes[1], process(100, 1, 20000));
+
+    // cargo (100) -> rustc (101) -> linker (102); 200 is unrelated.
+    assert_eq!(memory::tree_rss(&processes, 100), 820_000 * 1024);
+    assert_eq!(memory::tree_rss(&processes, 102), 300_000 * 1024);
+    assert_eq!(memory::tree_rss(&processes, 999), 0);
+}
+
+#[test]
+fn parses_proc_status() {
+    let status = "Name:\trustc\nState:\tR (running)\nPid:\t4242\nPPid:\t4200\nVmPeak:\t 2000000 kB\nVmRSS:\t  812344 kB\n";
+    assert_eq!(
+        memory::parse_proc_status(4242, status),
+        Some(process(4242, 4200, 812_344))
+    );
+    // Kernel threads have no VmRSS.
+    assert_eq!(
+        memory::parse_proc_status(2, "Name:\tkthreadd\nPPid:\t0\n"),
+        Some(process(2, 0, 0))
+    );
+}
+
+#[test]
+fn parses_memory_limits() {
+    assert_eq!(memory::parse_size("6G"), Ok(6 << 30));
+    assert_eq!(memory::parse_size("512M"), Ok(512 << 20));
+    assert_eq!(memory::parse_size("1.5GiB"), Ok(3 << 29));
+    assert_eq!(memory::parse_size("4096"), Ok(4096));
+    assert!(memory::parse_size("6Q").is_err());
+    assert!(memory::parse_size("lots").is_err());
+}
+
+#[test]
+fn samples_a_running_child() {
+    let child = std::process::Command::new("sleep")
+        .arg("0.5")
+        .spawn()
+        .unwrap();
+    let sampler = memory::PeakSampler::start(child.id());
+    let _ = child.wait_with_output();
+    assert!(sampler.finish().unwrap_or(0) > 0);
+}
@@ -0,0 +1,68 @@
+use cargo_sleek::metadata;
+use std::path::{Path, PathBuf};
+
+const SAMPLE: &str = r#"{
+  "packages": [
+    {
+      "id": "app 0.1.0 (path+file:///work/app)",
+      "name": "app",
+      "version": "0.1.0",
+      "rust_version": "1.74",
+      "manifest_path": "/work/app/Cargo.toml",
+      "targets": [{"name": "app", "kind": ["bin"], "src_path": "/work/app/src/main.rs"}],
+      "dependencies": [
+        {"name": "serde", "req": "^1", "kind": null},
+        {"name": "tempfile", "req": "^3", "kind": "dev"}
+      ],
+      "features": {}
+    },
+    {
+      "id": "registry+https://github.com/rust-lang/crates.io-index#serde@1.0.210",
+      "name": "serde",
+      "version": "1.0.210",
+      "manifest_path": "/home/me/.cargo/registry/serde/Cargo.toml",
+      "targets": [{"name": "serde", "kind": ["lib"]}],
+      "dependencies": []
+    }
+  ],
+  "workspace_members": ["app 0.1.0 (path+file:///work/app)"],
+  "resolve": {
+    "nodes": [
+      {"id": "app 0.1.0 (path+file:///work/app)",
+       "dependencies": ["registry+https://github.com/rust-lang/crates.io-index#serde@1.0.210"]},
+      {"id": "registry+https://github.com/rust-lang/crates.io-index#serde@1.0.210", "dependencies": []}
+    ],
+    "root": "app 0.1.0 (path+file:///work/app)"
+  },
+  "target_directory": "/work/app/target",
+  "workspace_root": "/work/app",
+  "version": 1
+}"#;
+
+#[test]
+fn parses_sample_metadata_into_typed_structs() {
+    let metadata = metadata::parse_metadata(SAMPLE).unwrap();
+    assert_eq!(metadata.target_directory, PathBuf::from("/work/app/target"));
+    assert_eq!(metadata.workspace_root, PathBuf::from("/work/app"));
+
+    let members: Vec<_> = metadata.members().collect();
+    assert_eq!(members.len(), 1);
+    let app = members[0];
+    assert_eq!(app.name, "app");
+    assert_eq!(app.rust_version.as_deref(), Some("1.74"));
+    assert_eq!(app.targets[0].kind, ["bin"]);
+    assert_eq!(app.dependencies.len(), 2);
+    assert_eq!(app.dependencies[0].kind, None);
+    assert_eq!(app.dependencies[1].kind.as_deref(), Some("dev"));
+
+    assert_eq!(metadata.direct_dependency_names(), ["serde"]);
+}
+
+#[test]
+fn metadata_is_fetched_once_per_process() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let first = metadata::cargo_metadata(dir).unwrap();
+    let second = metadata::cargo_metadata(dir).unwrap();
+    assert_eq!(first.workspace_members, second.workspace_members);
+    assert_eq!(first.workspace_root, dir.canonicalize().unwrap());
+}
@@ -0,0 +1,29 @@
+use cargo_sleek::modernize;
+
+#[test]
+fn findings_compare_against_declared_rust_version() {
+    let manifest = r#"
+[package]
+name = "demo"
+rust-version = "1.75"
+
+[dependencies]
+atty = "0.2"
+lazy_static = "1"
+serde = "1"
+
+[dev-dependencies]
+structopt = "0.3"
+"#;
+
+    let findings = modernize::find_modernizations(manifest).unwrap();
+    let summary: Vec<_> = findings
+        .iter()
+        .map(|f| (f.dependency.as_str(), f.msrv_compatible))
+        .collect();
+    assert_eq!(
+        summary,
+        [("atty", true), ("lazy_static", false), ("structopt", true)]
+    );
+    assert_eq!(findings[1].min_rust.as_deref(), Some("1.80"));
+}
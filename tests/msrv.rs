@@ -0,0 +1,43 @@
+use cargo_sleek::{metadata, msrv};
+
+#[test]
+fn violations_report_the_path_through_the_graph() {
+    let json = r#"{
+        "packages": [
+            { "id": "app", "name": "app", "version": "0.1.0", "rust_version": "1.70",
+              "manifest_path": "/w/app/Cargo.toml" },
+            { "id": "web", "name": "web", "version": "2.0.0", "rust_version": "1.65",
+              "manifest_path": "/r/web/Cargo.toml" },
+            { "id": "rt", "name": "rt", "version": "1.38.0", "rust_version": "1.74",
+              "manifest_path": "/r/rt/Cargo.toml" },
+            { "id": "old", "name": "old", "version": "0.1.0",
+              "manifest_path": "/r/old/Cargo.toml" }
+        ],
+        "workspace_members": ["app"],
+        "target_directory": "/w/target",
+        "resolve": { "nodes": [
+            { "id": "app", "dependencies": ["web", "old"] },
+            { "id": "web", "dependencies": ["rt"] },
+            { "id": "rt", "dependencies": [] },
+            { "id": "old", "dependencies": [] }
+        ] }
+    }"#;
+
+    let metadata = metadata::parse_metadata(json).unwrap();
+    let report = msrv::check_metadata(&metadata).unwrap();
+
+    assert_eq!(report.declared, "1.70");
+    assert_eq!(report.unverifiable, 1);
+    assert_eq!(report.violations.len(), 1);
+    assert_eq!(report.violations[0].name, "rt");
+    assert_eq!(report.violations[0].path, ["app", "web", "rt"]);
+}
+
+#[test]
+fn rust_versions_are_padded() {
+    assert_eq!(
+        msrv::parse_rust_version("1.70"),
+        Some(semver::Version::new(1, 70, 0))
+    );
+    assert!(msrv::parse_rust_version("1.x").is_none());
+}
@@ -0,0 +1,38 @@
+use cargo_sleek::output::{self, OutputMode};
+
+#[test]
+fn plain_output_swaps_emoji_for_prefixes() {
+    assert_eq!(
+        output::plain("❌ Command failed with exit code: 3"),
+        "error: Command failed with exit code: 3"
+    );
+    assert_eq!(
+        output::plain("⚠️  Peak memory 7 GB exceeds the limit"),
+        "warning: Peak memory 7 GB exceeds the limit"
+    );
+    assert_eq!(
+        output::plain("✅ No unused dependencies found!"),
+        "ok: No unused dependencies found!"
+    );
+    assert_eq!(
+        output::plain("\n▶️  --features full"),
+        "\ninfo: --features full"
+    );
+    assert_eq!(output::plain("   • serde"), "   • serde");
+    assert_eq!(output::plain("build    12  40.0%"), "build    12  40.0%");
+}
+
+#[test]
+fn ci_and_pipes_get_plain_output() {
+    assert_eq!(
+        output::detect(false, false, false, true),
+        OutputMode::Decorated
+    );
+    assert_eq!(output::detect(false, false, true, true), OutputMode::Plain);
+    assert_eq!(
+        output::detect(false, false, false, false),
+        OutputMode::Plain
+    );
+    assert_eq!(output::detect(false, true, false, true), OutputMode::Plain);
+    assert_eq!(output::detect(true, false, false, true), OutputMode::Quiet);
+}
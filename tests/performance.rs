@@ -0,0 +1,307 @@
+use cargo_sleek::{metadata, performance};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const METADATA: &str = r#"{
+    "packages": [
+        {
+            "id": "demo 0.1.0 (path+file:///work/demo)",
+            "name": "demo",
+            "manifest_path": "/work/demo/Cargo.toml",
+            "targets": [
+                { "name": "demo", "kind": ["lib"] },
+                { "name": "demo-cli", "kind": ["bin"] }
+            ]
+        },
+        {
+            "id": "serde 1.0.0 (registry+https://github.com/rust-lang/crates.io-index)",
+            "name": "serde",
+            "manifest_path": "/registry/serde/Cargo.toml",
+            "targets": [{ "name": "serde", "kind": ["lib"] }]
+        }
+    ],
+    "workspace_members": ["demo 0.1.0 (path+file:///work/demo)"],
+    "target_directory": "/work/demo/target"
+}"#;
+
+#[test]
+fn binary_paths_resolve_bin_targets_in_profile_dir() {
+    let metadata = metadata::parse_metadata(METADATA).unwrap();
+    let expected = PathBuf::from(format!(
+        "/work/demo/target/release/demo-cli{}",
+        std::env::consts::EXE_SUFFIX
+    ));
+    assert_eq!(
+        performance::binary_paths(&metadata, "release"),
+        vec![expected]
+    );
+}
+
+#[test]
+fn format_size_uses_binary_units() {
+    assert_eq!(performance::format_size(512), "512 B");
+    assert_eq!(performance::format_size(1536), "1.50 KB");
+    assert_eq!(performance::format_size(5 * 1024 * 1024), "5.00 MB");
+}
+
+#[test]
+fn artifacts_locate_outputs_and_profile_dir() {
+    let stream = r#"{"reason":"compiler-artifact","package_id":"demo 0.1.0 (path+file:///work/demo)","target":{"name":"demo","kind":["lib"]},"filenames":["/work/out/x86_64-unknown-linux-gnu/release/deps/libdemo.rlib","/work/out/x86_64-unknown-linux-gnu/release/deps/libdemo.rmeta"],"executable":null}
+not json
+{"reason":"compiler-artifact","package_id":"demo 0.1.0 (path+file:///work/demo)","target":{"name":"demo-cli","kind":["bin"]},"filenames":["/work/out/x86_64-unknown-linux-gnu/release/demo-cli"],"executable":"/work/out/x86_64-unknown-linux-gnu/release/demo-cli"}
+{"reason":"build-finished","success":true}"#;
+    let artifacts = performance::parse_artifacts(stream);
+    assert_eq!(artifacts.len(), 2);
+    assert_eq!(
+        artifacts[0].outputs(),
+        [PathBuf::from(
+            "/work/out/x86_64-unknown-linux-gnu/release/deps/libdemo.rlib"
+        )]
+    );
+    assert_eq!(
+        performance::profile_dir(&artifacts),
+        Some(PathBuf::from("/work/out/x86_64-unknown-linux-gnu/release"))
+    );
+}
+
+#[test]
+fn dir_size_sums_nested_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("deps")).unwrap();
+    std::fs::write(dir.path().join("a"), [0u8; 100]).unwrap();
+    std::fs::write(dir.path().join("deps/b"), [0u8; 50]).unwrap();
+    assert_eq!(performance::dir_size(dir.path()), 150);
+    assert_eq!(performance::dir_size(&dir.path().join("missing")), 0);
+}
+
+#[test]
+fn custom_profile_args_and_target_dir() {
+    let options = performance::BuildOptions {
+        profile: Some("dist".into()),
+        ..Default::default()
+    };
+    let args = options.cargo_args();
+    assert_eq!(args[0], "build");
+    assert!(args.ends_with(&["--profile".to_string(), "dist".to_string()]));
+    assert!(!performance::BuildOptions::default()
+        .cargo_args()
+        .contains(&"--profile".to_string()));
+
+    let target = PathBuf::from("/work/demo/target");
+    assert_eq!(
+        performance::profile_target_dir(&target, "dist"),
+        target.join("dist")
+    );
+    assert_eq!(
+        performance::profile_target_dir(&target, "dev"),
+        target.join("debug")
+    );
+    assert_eq!(
+        performance::profile_target_dir(&target, "bench"),
+        target.join("release")
+    );
+}
+
+#[test]
+fn release_and_feature_flags_are_forwarded() {
+    let options = performance::BuildOptions {
+        release: true,
+        features: vec!["simd".into(), "tls".into()],
+        no_default_features: true,
+        ..Default::default()
+    };
+    let args = options.cargo_args();
+    for expected in [
+        "--release",
+        "--features",
+        "simd,tls",
+        "--no-default-features",
+    ] {
+        assert!(args.iter().any(|a| a == expected), "missing {expected}");
+    }
+    assert_eq!(options.profile_name(), Some("release"));
+    assert_eq!(performance::BuildOptions::default().profile_name(), None);
+}
+
+#[test]
+fn cross_builds_pass_target_and_nest_output_dir() {
+    let options = performance::BuildOptions {
+        release: true,
+        target: Some("aarch64-unknown-linux-gnu".to_string()),
+        ..Default::default()
+    };
+    let args = options.cargo_args();
+    assert!(args
+        .windows(2)
+        .any(|pair| pair == ["--target", "aarch64-unknown-linux-gnu"]));
+
+    let target = PathBuf::from("/work/demo/target");
+    assert_eq!(
+        options.output_dir(&target),
+        target.join("aarch64-unknown-linux-gnu").join("release")
+    );
+    assert_eq!(
+        performance::BuildOptions::default().output_dir(&target),
+        target.join("debug")
+    );
+}
+
+#[test]
+fn validates_target_triples() {
+    for triple in [
+        "aarch64-unknown-linux-gnu",
+        "x86_64-pc-windows-msvc",
+        "wasm32-unknown-unknown",
+        "thumbv7em-none-eabihf",
+        "armv7-unknown-linux-gnueabihf",
+    ] {
+        assert!(
+            performance::validate_target_triple(triple).is_ok(),
+            "{triple}"
+        );
+    }
+    for triple in [
+        "linux",
+        "aarch64--linux",
+        "AArch64-Linux",
+        "x86_64-unknown linux",
+        "",
+    ] {
+        assert!(
+            performance::validate_target_triple(triple).is_err(),
+            "{triple}"
+        );
+    }
+}
+
+#[test]
+fn feature_sets_parse_and_label() {
+    let set = performance::FeatureSet::parse("serde, tls");
+    assert_eq!(set.features, ["serde", "tls"]);
+    assert!(!set.no_default_features);
+    assert_eq!(set.label(), "serde,tls");
+
+    assert_eq!(performance::FeatureSet::parse("default").label(), "default");
+    assert_eq!(performance::FeatureSet::parse("").label(), "default");
+    let bare = performance::FeatureSet::parse("none,rt");
+    assert!(bare.no_default_features);
+    assert_eq!(bare.label(), "none,rt");
+    assert_eq!(performance::FeatureSet::parse(&bare.label()), bare);
+}
+
+#[test]
+fn quick_mode_runs_cargo_check() {
+    let options = performance::BuildOptions {
+        check: true,
+        ..Default::default()
+    };
+    assert_eq!(options.cargo_args()[0], "check");
+}
+
+#[test]
+fn touched_file_restores_its_mtime() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.rs");
+    std::fs::write(&path, "fn main() {}").unwrap();
+    let mtime = || std::fs::metadata(&path).unwrap().modified().unwrap();
+    let old = std::time::SystemTime::now() - std::time::Duration::from_secs(86_400);
+    std::fs::File::options()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+
+    let touched = performance::TouchedFile::touch(&path).unwrap();
+    assert!(mtime() > old);
+    drop(touched);
+    assert_eq!(mtime(), old);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() {}");
+}
+
+#[test]
+fn build_time_budgets_parse_and_apply() {
+    assert_eq!(
+        performance::parse_budget("4m30s"),
+        Ok(Duration::from_secs(270))
+    );
+    assert_eq!(performance::parse_budget("90"), Ok(Duration::from_secs(90)));
+    assert_eq!(
+        performance::parse_budget("1h"),
+        Ok(Duration::from_secs(3600))
+    );
+    assert_eq!(
+        performance::parse_budget("2m5"),
+        Ok(Duration::from_secs(125))
+    );
+    assert!(performance::parse_budget("4 minutes").is_err());
+    assert!(performance::parse_budget("").is_err());
+    assert_eq!(performance::parse_budget("0"), Ok(Duration::ZERO));
+    for invalid in [
+        "inf",
+        "-inf",
+        "NaN",
+        "1e300",
+        "-5",
+        "99999999999999999999999h",
+    ] {
+        assert!(performance::parse_budget(invalid).is_err(), "{invalid}");
+    }
+    assert_eq!(performance::parse_percent("15%"), Ok(15.0));
+    assert_eq!(performance::parse_percent("0"), Ok(0.0));
+    for invalid in ["-3%", "inf%", "-inf", "NaN", "1e309%", "fifteen"] {
+        assert!(performance::parse_percent(invalid).is_err(), "{invalid}");
+    }
+
+    let max = Some(Duration::from_secs(270));
+    assert!(performance::check_budget(200_000, max, None, None).is_empty());
+    let over = performance::check_budget(300_000, max, None, None);
+    assert_eq!(over.len(), 1);
+    assert_eq!((over[0].measured_ms, over[0].limit_ms), (300_000, 270_000));
+
+    let median = Some(("the recent median", 100_000));
+    assert!(performance::check_budget(110_000, None, Some(15.0), median).is_empty());
+    let regressed = performance::check_budget(120_000, None, Some(15.0), median);
+    assert_eq!(regressed[0].limit_ms, 115_000);
+    assert!(regressed[0].source.contains("the recent median"));
+    // Without history there is nothing to regress from.
+    assert!(performance::check_budget(120_000, None, Some(15.0), None).is_empty());
+}
+
+#[test]
+fn doc_mode_runs_cargo_doc_and_sizes_dependency_docs() {
+    let options = performance::BuildOptions {
+        doc: true,
+        no_deps: true,
+        ..Default::default()
+    };
+    let args = options.cargo_args();
+    assert_eq!(args[0], "doc");
+    assert!(args.iter().any(|a| a == "--no-deps"));
+
+    let dir = tempfile::tempdir().unwrap();
+    let doc = dir.path();
+    for (path, bytes) in [
+        ("serde_json/index.html", 300),
+        ("src/serde_json/lib.rs.html", 200),
+        ("windows_sys/all.html", 4_000),
+        ("tiny/index.html", 10),
+    ] {
+        let path = doc.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![b'x'; bytes]).unwrap();
+    }
+    let crates = [
+        ("serde_json", "serde_json"),
+        ("windows-sys", "windows_sys"),
+        ("not-documented", "not_documented"),
+    ]
+    .map(|(pkg, lib)| (pkg.to_string(), lib.to_string()));
+    assert_eq!(
+        performance::doc_crate_sizes(doc, &crates),
+        [
+            ("windows-sys".to_string(), 4_000),
+            ("serde_json".to_string(), 500)
+        ]
+    );
+}
@@ -0,0 +1,79 @@
+use cargo_sleek::pipeline::{self, StepOutcome};
+use std::collections::HashMap;
+
+fn steps() -> Vec<String> {
+    ["fmt", "clippy", "test", "build"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn outcomes(keep_going: bool) -> (Vec<StepOutcome>, Vec<String>) {
+    let mut ran = Vec::new();
+    let results = pipeline::run_steps(&steps(), keep_going, |step| {
+        ran.push(step.to_string());
+        Ok(step != "clippy")
+    })
+    .unwrap();
+    (results.into_iter().map(|r| r.outcome).collect(), ran)
+}
+
+#[test]
+fn pipeline_stops_on_first_failure() {
+    let (outcomes, ran) = outcomes(false);
+    assert_eq!(ran, ["fmt", "clippy"]);
+    assert_eq!(
+        outcomes,
+        [
+            StepOutcome::Passed,
+            StepOutcome::Failed,
+            StepOutcome::Skipped,
+            StepOutcome::Skipped
+        ]
+    );
+}
+
+#[test]
+fn pipeline_keep_going_runs_every_step() {
+    let (outcomes, ran) = outcomes(true);
+    assert_eq!(ran, ["fmt", "clippy", "test", "build"]);
+    assert_eq!(
+        outcomes,
+        [
+            StepOutcome::Passed,
+            StepOutcome::Failed,
+            StepOutcome::Passed,
+            StepOutcome::Passed
+        ]
+    );
+}
+
+#[test]
+fn summary_reports_a_mid_pipeline_failure() {
+    let results = pipeline::run_steps(&steps(), false, |step| Ok(step != "clippy")).unwrap();
+    let lines = pipeline::render_summary(&results, false);
+    assert_eq!(lines.len(), 6);
+    assert!(lines[0].starts_with("#  Step    Status"));
+    let status: Vec<&str> = lines[1..5]
+        .iter()
+        .map(|line| line.split_whitespace().nth(2).unwrap())
+        .collect();
+    assert_eq!(status, ["passed", "failed", "skipped", "skipped"]);
+    assert!(lines[3].trim_end().ends_with('-'));
+    assert!(lines[5].starts_with("Total: "));
+}
+
+#[test]
+fn chain_names_expand_to_their_configured_steps() {
+    let chains: HashMap<String, Vec<String>> = [(
+        "ci".to_string(),
+        vec!["fmt --check".to_string(), "clippy".to_string()],
+    )]
+    .into_iter()
+    .collect();
+    let args = ["ci".to_string(), "test".to_string()];
+    assert_eq!(
+        pipeline::expand_chain(&args, &chains),
+        ["fmt --check", "clippy", "test"]
+    );
+}
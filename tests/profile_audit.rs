@@ -0,0 +1,46 @@
+use cargo_sleek::profile_audit;
+
+const MANIFEST: &str = r#"[package]
+name = "demo"
+version = "0.1.0"
+
+[profile.release]
+debug = true # keep symbols for perf
+panic = "abort"
+"#;
+
+#[test]
+fn flags_release_and_dev_settings() {
+    let findings = profile_audit::audit_manifest(MANIFEST, true, true).unwrap();
+    let keys: Vec<&str> = findings.iter().map(|f| f.key.as_str()).collect();
+    assert_eq!(
+        keys,
+        ["debug", "lto", "codegen-units", "panic", "opt-level"]
+    );
+    assert_eq!(findings[0].current.as_deref(), Some("true"));
+    assert_eq!(findings[1].current, None);
+    // The panic trade-off is advisory only.
+    assert_eq!(findings[3].suggested, None);
+    assert_eq!(findings[4].table, ["profile", "dev", "package", "*"]);
+
+    // Libraries without dependencies only get the debug finding and the panic note.
+    let findings = profile_audit::audit_manifest(MANIFEST, false, false).unwrap();
+    assert_eq!(findings.len(), 2);
+}
+
+#[test]
+fn fixes_preserve_comments_and_create_tables() {
+    let findings = profile_audit::audit_manifest(MANIFEST, true, true).unwrap();
+    let fixed = profile_audit::apply_fixes(MANIFEST, &findings).unwrap();
+    assert!(fixed.contains("debug = \"line-tables-only\" # keep symbols for perf"));
+    assert!(fixed.contains("lto = \"thin\""));
+    assert!(fixed.contains("codegen-units = 1"));
+    assert!(fixed.contains("panic = \"abort\""));
+    assert!(fixed.contains("[profile.dev.package.\"*\"]\nopt-level = 2"));
+    assert!(!fixed.contains("[profile.dev]\n"));
+
+    // Re-auditing the fixed manifest leaves only the advisory finding.
+    let again = profile_audit::audit_manifest(&fixed, true, true).unwrap();
+    assert_eq!(again.len(), 1);
+    assert_eq!(again[0].key, "panic");
+}
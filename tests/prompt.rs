@@ -0,0 +1,41 @@
+use cargo_sleek::config::ConfirmConfig;
+use cargo_sleek::prompt;
+
+fn decide(command: &str, assume_yes: bool, interactive: bool, answer: &str) -> bool {
+    let listed = ConfirmConfig::default().commands;
+    let mut shown = Vec::new();
+    prompt::confirm_command(
+        command,
+        &listed,
+        assume_yes,
+        interactive,
+        answer.as_bytes(),
+        &mut shown,
+    )
+    .unwrap()
+}
+
+#[test]
+fn clean_asks_by_default() {
+    assert!(decide("clean", false, true, "y\n"));
+    assert!(decide("clean", false, true, "YES\n"));
+    assert!(!decide("clean", false, true, "n\n"));
+    assert!(!decide("clean", false, true, "\n"));
+}
+
+#[test]
+fn yes_flag_and_non_interactive_sessions_skip_the_prompt() {
+    assert!(decide("clean", true, true, "n\n"));
+    assert!(decide("clean", false, false, "n\n"));
+}
+
+#[test]
+fn unlisted_commands_run_without_asking() {
+    assert!(decide("build", false, true, "n\n"));
+
+    let listed = vec!["run".to_string()];
+    let mut shown = Vec::new();
+    let ran = prompt::confirm_command("run", &listed, false, true, &b"no\n"[..], &mut shown);
+    assert!(!ran.unwrap());
+    assert!(String::from_utf8(shown).unwrap().contains("cargo run"));
+}
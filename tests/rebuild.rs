@@ -0,0 +1,133 @@
+use cargo_sleek::history::BuildRecord;
+use cargo_sleek::rebuild::{self, RebuildCount, RebuildReason};
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn parses_verbose_dirty_lines() {
+    let stderr = "       Fresh serde v1.0.210
+       Dirty openssl-sys v0.9.103: the env variable PATH changed
+       Dirty app v0.1.0 (/work/app): the file `src/main.rs` has changed (12.5s after last build)
+   Compiling app v0.1.0 (/work/app)
+";
+    let reasons = rebuild::parse_dirty_lines(stderr);
+    assert_eq!(
+        reasons,
+        [
+            RebuildReason {
+                package: "openssl-sys v0.9.103".into(),
+                reason: "the env variable PATH changed".into(),
+            },
+            RebuildReason {
+                package: "app v0.1.0".into(),
+                reason: "the file `src/main.rs` has changed (12.5s after last build)".into(),
+            },
+        ]
+    );
+    assert_eq!(reasons[0].env_var(), Some("PATH"));
+    assert_eq!(reasons[1].env_var(), None);
+}
+
+#[test]
+fn parses_fingerprint_log() {
+    let stderr = r#"   0.01s  INFO prepare_target{force=false package_id=mx v0.1.0 (/tmp/mx) target="build-script-build"}: cargo::core::compiler::fingerprint: fingerprint dirty for mx v0.1.0 (/tmp/mx)/RunCustomBuild/TargetInner { ..: custom_build_target("build-script-build", "/tmp/mx/build.rs", Edition2021) }
+   0.01s  INFO prepare_target{force=false package_id=mx v0.1.0 (/tmp/mx) target="build-script-build"}: cargo::core::compiler::fingerprint:     dirty: EnvVarChanged { name: "SOME_VAR", old_value: Some("1"), new_value: None }
+   0.02s  INFO prepare_target{force=false package_id=log v0.4.22 target="log"}: cargo::core::compiler::fingerprint: fingerprint dirty for log v0.4.22/Build/TargetInner { name: "log" }
+   0.02s  INFO prepare_target{force=false package_id=log v0.4.22 target="log"}: cargo::core::compiler::fingerprint:     dirty: RustcChanged
+"#;
+    let reasons = rebuild::parse_fingerprint_log(stderr);
+    assert_eq!(reasons.len(), 2);
+    assert_eq!(reasons[0].package, "mx v0.1.0");
+    assert_eq!(reasons[0].reason, "the env variable SOME_VAR changed");
+    assert_eq!(reasons[0].env_var(), Some("SOME_VAR"));
+    assert_eq!(reasons[1].package, "log v0.4.22");
+    assert_eq!(reasons[1].reason, "the compiler changed");
+}
+
+#[test]
+fn flags_build_scripts_watching_volatile_vars() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("build/ring-0a1b2c3d");
+    fs::create_dir_all(&script).unwrap();
+    fs::write(
+        script.join("output"),
+        "cargo:rerun-if-env-changed=PATH\ncargo::rerun-if-env-changed=RING_PREGENERATE_ASM\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        rebuild::rerun_env_vars("cargo:rerun-if-env-changed=CC\ncargo:rustc-cfg=x\n"),
+        ["CC"]
+    );
+    assert_eq!(
+        rebuild::volatile_build_script_vars(dir.path()),
+        [("ring".to_string(), "PATH".to_string())]
+    );
+}
+
+#[test]
+fn finds_files_with_future_mtimes() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("src")).unwrap();
+    let future = dir.path().join("src/lib.rs");
+    fs::write(&future, "").unwrap();
+    fs::write(dir.path().join("build.rs"), "").unwrap();
+    fs::File::options()
+        .append(true)
+        .open(&future)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(3600))
+        .unwrap();
+
+    assert_eq!(
+        rebuild::future_mtime_files(dir.path(), SystemTime::now()),
+        [future]
+    );
+}
+
+fn build(kind: Option<&str>, recompiled: Option<&[&str]>) -> BuildRecord {
+    BuildRecord {
+        kind: kind.map(String::from),
+        recompiled: recompiled.map(|names| names.iter().map(|n| n.to_string()).collect()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn counts_rebuilds_and_flags_constant_ones() {
+    let runs = [
+        build(Some("cold"), Some(&["app", "openssl-sys", "serde"])),
+        build(Some("warm"), Some(&["app", "openssl-sys"])),
+        build(Some("warm"), Some(&["app", "openssl-sys"])),
+        build(None, Some(&["app", "openssl-sys"])),
+        build(Some("warm"), Some(&["app"])),
+        // Recorded before recompilation data was tracked.
+        build(Some("warm"), None),
+    ];
+    let (counts, incremental_runs) = rebuild::rebuild_counts(&runs);
+    assert_eq!(incremental_runs, 4);
+    assert_eq!(
+        counts,
+        [
+            RebuildCount {
+                name: "app".into(),
+                rebuilds: 5,
+                incremental_rebuilds: 4,
+            },
+            RebuildCount {
+                name: "openssl-sys".into(),
+                rebuilds: 4,
+                incremental_rebuilds: 3,
+            },
+            RebuildCount {
+                name: "serde".into(),
+                rebuilds: 1,
+                incremental_rebuilds: 0,
+            },
+        ]
+    );
+    assert!(rebuild::rebuilds_constantly(&counts[0], incremental_runs));
+    assert!(!rebuild::rebuilds_constantly(&counts[1], incremental_runs));
+    assert!(!rebuild::rebuilds_constantly(&counts[2], incremental_runs));
+    assert!(!rebuild::rebuilds_constantly(&counts[0], 2));
+}
@@ -0,0 +1,67 @@
+use cargo_sleek::history::SizeRecord;
+use cargo_sleek::size::{self, SizeLimits};
+
+#[test]
+fn crate_is_taken_from_the_symbol_path() {
+    assert_eq!(
+        size::crate_of_symbol("serde_json::de::from_str"),
+        Some("serde_json")
+    );
+    assert_eq!(
+        size::crate_of_symbol("<serde_json::value::Value as core::fmt::Debug>::fmt"),
+        Some("serde_json")
+    );
+    assert_eq!(
+        size::crate_of_symbol("<&mut clap_builder::Command as core::ops::Drop>::drop"),
+        Some("clap_builder")
+    );
+    assert_eq!(size::crate_of_symbol("memcpy"), None);
+}
+
+#[test]
+fn own_binary_breaks_down_into_sections_and_crates() {
+    let path = std::path::Path::new(env!("CARGO_BIN_EXE_cargo-sleek"));
+    let report = size::size_report("cargo-sleek", path).unwrap();
+
+    assert!(report.file_size > 0);
+    assert!(report.stripped_size <= report.file_size);
+    assert!(report.sections.iter().any(|s| s.size > 0));
+    assert!(report.crates.iter().any(|c| c.name == "clap_builder"));
+}
+
+fn size(binary: &str, bytes: u64) -> SizeRecord {
+    SizeRecord {
+        binary: binary.into(),
+        bytes,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn size_limits_judge_each_binary_on_its_own() {
+    let previous = [
+        size("cli", 1_000),
+        size("daemon", 1_000),
+        size("cli", 2_000),
+    ];
+    let current = [
+        size("cli", 2_080),
+        size("daemon", 1_200),
+        size("new", 5_000),
+    ];
+    let limits = SizeLimits {
+        max_size: Some(4_000),
+        max_growth: Some(5.0),
+    };
+    let violations = size::check_size_limits(&previous, &current, limits);
+    let summary: Vec<(&str, u64, Option<u64>)> = violations
+        .iter()
+        .map(|v| (v.binary.as_str(), v.limit_bytes, v.previous_bytes))
+        .collect();
+    // cli grew 4% from its latest size; a binary without history only faces --max-size.
+    assert_eq!(
+        summary,
+        [("daemon", 1_050, Some(1_000)), ("new", 4_000, None)]
+    );
+    assert!(size::check_size_limits(&previous, &current, SizeLimits::default()).is_empty());
+}
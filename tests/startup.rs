@@ -0,0 +1,34 @@
+use cargo_sleek::startup::{self, StartupSummary};
+use std::time::Duration;
+
+#[test]
+fn splits_cargo_and_binary_arguments() {
+    let args: Vec<String> = ["--bin", "cli", "--", "--help"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let (build, binary) = startup::split_run_args(&args);
+    assert_eq!(build, ["--bin", "cli"]);
+    assert_eq!(binary, ["--help"]);
+
+    let (build, binary) = startup::split_run_args(&args[..2]);
+    assert_eq!(build, ["--bin", "cli"]);
+    assert!(binary.is_empty());
+}
+
+#[test]
+fn summarizes_launch_times() {
+    let samples: Vec<Duration> = [12, 3, 9, 5, 7, 4, 6, 8, 10, 30]
+        .iter()
+        .map(|&ms| Duration::from_millis(ms))
+        .collect();
+    assert_eq!(
+        startup::summarize(&samples),
+        Some(StartupSummary {
+            min: Duration::from_millis(3),
+            median: Duration::from_millis(7),
+            p95: Duration::from_millis(30),
+        })
+    );
+    assert_eq!(startup::summarize(&[]), None);
+}
@@ -0,0 +1,569 @@
+use cargo_sleek::stats::{self, CommandStats, StatsColumns};
+use chrono::{TimeZone, Utc};
+use std::collections::HashMap;
+
+#[test]
+fn parse_since_accepts_absolute_dates() {
+    let now = Utc::now();
+    assert_eq!(
+        stats::parse_since("2024-06-01", now).unwrap(),
+        1_717_200_000
+    );
+    assert_eq!(
+        stats::parse_since("2024-06-01T12:00:00Z", now).unwrap(),
+        1_717_243_200
+    );
+}
+
+#[test]
+fn parse_since_accepts_relative_durations() {
+    let now = Utc.with_ymd_and_hms(2024, 6, 8, 0, 0, 0).unwrap();
+    assert_eq!(stats::parse_since("7d", now).unwrap(), 1_717_200_000);
+    assert_eq!(stats::parse_since("1w", now).unwrap(), 1_717_200_000);
+    assert_eq!(stats::parse_since("24h", now).unwrap(), 1_717_718_400);
+}
+
+#[test]
+fn parse_since_rejects_garbage() {
+    let now = Utc::now();
+    assert!(stats::parse_since("yesterday", now).is_err());
+    assert!(stats::parse_since("7y", now).is_err());
+    assert!(stats::parse_since("", now).is_err());
+    assert!(stats::parse_since("7é", now).is_err());
+    assert!(stats::parse_since("é", now).is_err());
+    assert!(stats::parse_since("-5d", now).is_err());
+    assert!(stats::parse_since("0d", now).is_err());
+    assert!(stats::parse_since("9999999999999w", now).is_err());
+}
+
+#[test]
+fn filter_since_keeps_recent_commands() {
+    let mut data = HashMap::new();
+    data.insert(
+        "build".to_string(),
+        CommandStats {
+            usage_count: 3,
+            last_used: 2_000,
+            ..Default::default()
+        },
+    );
+    data.insert(
+        "clean".to_string(),
+        CommandStats {
+            usage_count: 1,
+            last_used: 1_000,
+            ..Default::default()
+        },
+    );
+
+    let recent = stats::filter_since(data, 2_000);
+    assert_eq!(recent.len(), 1);
+    assert!(recent.contains_key("build"));
+}
+
+#[test]
+fn failure_rates_map_to_severity() {
+    use stats::Severity;
+
+    assert_eq!(stats::failure_severity(0.0), Severity::Low);
+    assert_eq!(stats::failure_severity(9.9), Severity::Low);
+    assert_eq!(stats::failure_severity(10.0), Severity::Medium);
+    assert_eq!(stats::failure_severity(50.0), Severity::Medium);
+    assert_eq!(stats::failure_severity(50.1), Severity::High);
+    assert_eq!(stats::failure_severity(100.0), Severity::High);
+}
+
+#[test]
+fn track_command_result_counts_outcomes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.json");
+
+    stats::track_command_result(&path, "test", true).unwrap();
+    stats::track_command_result(&path, "test", false).unwrap();
+    stats::track_command_result(&path, "test", true).unwrap();
+    stats::track_command_result(&path, "test", true).unwrap();
+
+    let loaded = stats::load_stats(&path);
+    assert_eq!(loaded["test"].usage_count, 4);
+    assert_eq!(loaded["test"].failure_rate(), Some(25.0));
+}
+
+#[test]
+fn reset_backs_up_and_restore_brings_data_back() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("command_stats.json");
+    stats::track_command(&path, "build").unwrap();
+    stats::track_command(&path, "build").unwrap();
+
+    stats::reset_stats(&path, true).unwrap();
+    assert!(stats::load_stats(&path).is_empty());
+    assert_eq!(stats::list_backups(&path).len(), 1);
+
+    stats::restore_latest_backup(&path).unwrap();
+    assert_eq!(stats::load_stats(&path)["build"].usage_count, 2);
+}
+
+#[test]
+fn only_the_newest_backups_are_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("command_stats.json");
+    stats::track_command(&path, "build").unwrap();
+
+    for _ in 0..stats::MAX_BACKUPS + 2 {
+        stats::backup_stats(&path).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+    assert_eq!(stats::list_backups(&path).len(), stats::MAX_BACKUPS);
+}
+
+#[test]
+fn args_tracking_separates_argument_variants() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.json");
+    let release = vec!["--release".to_string()];
+    let run_args = vec![
+        "--release".to_string(),
+        "--".to_string(),
+        "--port".to_string(),
+    ];
+
+    for (args, track_args) in [
+        (&vec![], true),
+        (&release, true),
+        (&release, true),
+        (&run_args, true),
+        (&release, false),
+    ] {
+        let key = stats::tracking_key("build", args, track_args);
+        stats::track_command(&path, &key).unwrap();
+    }
+
+    let loaded = stats::load_stats(&path);
+    assert_eq!(loaded["build"].usage_count, 2);
+    assert_eq!(loaded["build --release"].usage_count, 3);
+}
+
+#[test]
+fn stats_path_precedence_is_cli_env_config_default() {
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    let cli = Some(Path::new("cli.json"));
+    let env = Some(OsStr::new("env.json"));
+    let config = Some(Path::new("config.json"));
+
+    assert_eq!(
+        stats::resolve_stats_path(cli, env, config),
+        Path::new("cli.json")
+    );
+    assert_eq!(
+        stats::resolve_stats_path(None, env, config),
+        Path::new("env.json")
+    );
+    assert_eq!(
+        stats::resolve_stats_path(None, None, config),
+        Path::new("config.json")
+    );
+    assert_eq!(
+        stats::resolve_stats_path(None, None, None),
+        Path::new(stats::STATS_FILE)
+    );
+}
+
+#[test]
+fn daily_graph_scales_bars_and_keeps_idle_days() {
+    let daily = |pairs: &[(&str, u32)]| CommandStats {
+        daily: pairs.iter().map(|(d, n)| (d.to_string(), *n)).collect(),
+        ..Default::default()
+    };
+    let stats = HashMap::from([
+        (
+            "build".to_string(),
+            daily(&[("2024-06-01", 4), ("2024-06-03", 6)]),
+        ),
+        ("test".to_string(), daily(&[("2024-06-03", 4)])),
+    ]);
+
+    let today = chrono::NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+    let totals = stats::daily_totals(&stats, today, 4);
+    let counts: Vec<u32> = totals.iter().map(|(_, n)| *n).collect();
+    assert_eq!(counts, [0, 4, 0, 10]);
+    assert_eq!(totals[0].0.to_string(), "2024-05-31");
+
+    assert_eq!(stats::bar_lengths(&counts, 20), [0, 8, 0, 20]);
+    assert_eq!(stats::bar_lengths(&[1, 100], 10), [1, 10]);
+    assert_eq!(stats::bar_lengths(&[0, 0], 10), [0, 0]);
+}
+
+#[test]
+fn table_columns_align_with_long_and_wide_names() {
+    let long = "build --release --features a,b,c --target x86_64-unknown-linux-gnu".to_string();
+    let wide = "run 日本語".to_string();
+    let short = "test".to_string();
+    let data = CommandStats {
+        usage_count: 12_345,
+        last_used: 1_717_200_000,
+        success_count: 1,
+        failure_count: 1,
+        ..Default::default()
+    };
+    let rows = vec![(&long, &data), (&wide, &data), (&short, &data)];
+    let lines = stats::render_stats_table(&rows, false, StatsColumns::default());
+
+    let widths: Vec<usize> = lines.iter().map(|l| stats::display_width(l)).collect();
+    assert!(widths.iter().all(|w| *w == widths[0]), "{lines:#?}");
+
+    // The count column ends at the same display column on every line.
+    let count_end = |line: &str| {
+        let prefix = &line[..line.find("12345").or_else(|| line.find("Count")).unwrap() + 5];
+        stats::display_width(prefix)
+    };
+    assert!(lines.iter().all(|l| count_end(l) == count_end(&lines[0])));
+
+    assert!(lines[1].contains('…'));
+    assert!(!lines[1].contains("x86_64"));
+    assert!(lines[2].contains("run 日本語"));
+    assert_eq!(
+        stats::display_width(&stats::truncate_to_width(&long, stats::MAX_COMMAND_WIDTH)),
+        stats::MAX_COMMAND_WIDTH
+    );
+    assert_eq!(stats::truncate_to_width("日本語", 4), "日…");
+}
+
+#[test]
+fn stats_output_file_has_table_without_ansi() {
+    let dir = tempfile::tempdir().unwrap();
+    let stats_path = dir.path().join("stats.json");
+    stats::track_command_result(&stats_path, "build", true).unwrap();
+    stats::track_command_result(&stats_path, "build", false).unwrap();
+    stats::track_command(&stats_path, "test").unwrap();
+
+    colored::control::set_override(true);
+    let output = dir.path().join("reports/nested/stats.txt");
+    stats::show_stats(
+        &stats_path,
+        None,
+        Some(&output),
+        false,
+        StatsColumns::default(),
+    )
+    .unwrap();
+
+    let content = std::fs::read_to_string(&output).unwrap();
+    assert!(!content.contains('\u{1b}'), "{content:?}");
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines[0], "📊 Most Used Cargo Commands:");
+    assert!(lines[1].starts_with("#  Command"));
+    assert!(lines[2].starts_with("1  build"));
+    assert!(lines[2].contains("50.0%"));
+    assert!(lines[3].starts_with("2  test"));
+}
+
+#[test]
+fn equal_counts_are_listed_alphabetically() {
+    let entry = |usage_count| CommandStats {
+        usage_count,
+        ..Default::default()
+    };
+    let stats: HashMap<String, CommandStats> = [
+        ("test", entry(3)),
+        ("run", entry(3)),
+        ("clippy", entry(1)),
+        ("build", entry(3)),
+        ("check", entry(7)),
+        ("bench", entry(1)),
+    ]
+    .into_iter()
+    .map(|(cmd, data)| (cmd.to_string(), data))
+    .collect();
+
+    let lines = stats::stats_report(&stats, false, StatsColumns::default());
+    let order: Vec<&str> = lines[2..]
+        .iter()
+        .map(|line| line.split_whitespace().nth(1).unwrap())
+        .collect();
+    assert_eq!(order, ["check", "build", "run", "test", "bench", "clippy"]);
+}
+
+#[test]
+fn stats_file_records_the_writing_tool_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.json");
+    let mut commands = HashMap::new();
+    commands.insert(
+        "build".to_string(),
+        CommandStats {
+            usage_count: 4,
+            ..Default::default()
+        },
+    );
+    stats::save_stats_as(&path, &commands, "9.9.9").unwrap();
+
+    let file = stats::read_stats_file(&path).unwrap();
+    assert_eq!(file.schema_version, stats::STATS_SCHEMA_VERSION);
+    assert_eq!(file.tool_version.as_deref(), Some("9.9.9"));
+    assert!(stats::is_newer_version("9.9.9", stats::TOOL_VERSION));
+    assert!(!stats::is_newer_version("0.1.0", stats::TOOL_VERSION));
+    assert_eq!(stats::load_stats(&path)["build"].usage_count, 4);
+
+    // Files from before the wrapper are still read.
+    std::fs::write(&path, r#"{"test": {"usage_count": 2, "last_used": 0}}"#).unwrap();
+    let legacy = stats::read_stats_file(&path).unwrap();
+    assert_eq!(legacy.schema_version, 1);
+    assert_eq!(legacy.tool_version, None);
+    assert_eq!(legacy.commands["test"].usage_count, 2);
+}
+
+#[test]
+fn share_column_gives_each_commands_part_of_all_runs() {
+    let entry = |usage_count| CommandStats {
+        usage_count,
+        ..Default::default()
+    };
+    let stats: HashMap<String, CommandStats> = [("build", 6), ("test", 3), ("clippy", 1)]
+        .into_iter()
+        .map(|(cmd, count)| (cmd.to_string(), entry(count)))
+        .collect();
+
+    let lines = stats::stats_report(&stats, false, StatsColumns::default());
+    assert!(lines[1].contains("Share"));
+    let shares: Vec<(String, f64)> = lines[2..]
+        .iter()
+        .map(|line| {
+            let cells: Vec<&str> = line.split_whitespace().collect();
+            let share = cells[3].strip_suffix('%').unwrap().parse().unwrap();
+            (cells[1].to_string(), share)
+        })
+        .collect();
+    assert_eq!(
+        shares,
+        [
+            ("build".to_string(), 60.0),
+            ("test".to_string(), 30.0),
+            ("clippy".to_string(), 10.0)
+        ]
+    );
+    let sum: f64 = shares.iter().map(|(_, share)| share).sum();
+    assert!((sum - 100.0).abs() < 0.5);
+}
+
+#[test]
+fn merging_sums_counts_and_keeps_the_later_timestamp() {
+    let dir = tempfile::tempdir().unwrap();
+    let laptop = dir.path().join("laptop.json");
+    let desktop = dir.path().join("desktop.json");
+    let mut commands = HashMap::new();
+    commands.insert(
+        "build".to_string(),
+        CommandStats {
+            usage_count: 3,
+            last_used: 1_000,
+            success_count: 2,
+            failure_count: 1,
+            total_duration_ms: 4_000,
+            ..Default::default()
+        },
+    );
+    stats::save_stats(&laptop, &commands).unwrap();
+    // The desktop still has a file from before the schema wrapper.
+    std::fs::write(
+        &desktop,
+        r#"{"build": {"usage_count": 2, "last_used": 5000, "success_count": 2, "total_duration_ms": 1500},
+            "test": {"usage_count": 1, "last_used": 3000}}"#,
+    )
+    .unwrap();
+
+    stats::merge_stats_file(&laptop, &desktop).unwrap();
+    let merged = stats::load_stats(&laptop);
+    let build = &merged["build"];
+    assert_eq!(build.usage_count, 5);
+    assert_eq!(build.success_count, 4);
+    assert_eq!(build.failure_count, 1);
+    assert_eq!(build.total_duration_ms, 5_500);
+    assert_eq!(build.last_used, 5_000);
+    assert_eq!(merged["test"].usage_count, 1);
+    assert_eq!(merged["test"].last_used, 3_000);
+}
+
+#[test]
+fn merging_huge_counts_saturates() {
+    let huge = CommandStats {
+        usage_count: u32::MAX - 1,
+        success_count: u32::MAX,
+        failure_count: 7,
+        total_duration_ms: u64::MAX - 10,
+        daily: [("2024-06-01".to_string(), u32::MAX)].into_iter().collect(),
+        ..Default::default()
+    };
+    let mut merged = huge.clone();
+    merged.merge(&huge);
+    assert_eq!(merged.usage_count, u32::MAX);
+    assert_eq!(merged.success_count, u32::MAX);
+    assert_eq!(merged.failure_count, 14);
+    assert_eq!(merged.total_duration_ms, u64::MAX);
+    assert_eq!(merged.daily["2024-06-01"], u32::MAX);
+}
+
+#[test]
+fn toml_output_round_trips() {
+    let mut commands = HashMap::new();
+    commands.insert(
+        "build --release".to_string(),
+        CommandStats {
+            usage_count: 7,
+            last_used: 1_717_200_000,
+            first_used: 1_717_000_000,
+            success_count: 6,
+            failure_count: 1,
+            total_duration_ms: 42_000,
+            daily: [("2024-06-01".to_string(), 7)].into_iter().collect(),
+            last_exit_code: Some(101),
+            last_exit_signal: false,
+        },
+    );
+    commands.insert(
+        "test \"quoted\".name".to_string(),
+        CommandStats {
+            usage_count: 1,
+            ..Default::default()
+        },
+    );
+
+    let text = stats::stats_to_toml(&commands).unwrap();
+    assert!(text.contains(r#"["build --release"]"#));
+    let parsed = stats::stats_from_toml(&text).unwrap();
+    assert_eq!(parsed.len(), 2);
+    let build = &parsed["build --release"];
+    assert_eq!(build.usage_count, 7);
+    assert_eq!(build.last_used, 1_717_200_000);
+    assert_eq!(build.failure_count, 1);
+    assert_eq!(build.total_duration_ms, 42_000);
+    assert_eq!(build.daily["2024-06-01"], 7);
+    assert_eq!(parsed["test \"quoted\".name"].usage_count, 1);
+}
+
+#[test]
+fn first_used_stays_fixed_across_tracks() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("command_stats.json");
+    std::fs::write(
+        &path,
+        r#"{"build": {"usage_count": 1, "first_used": 1000, "last_used": 2000},
+            "test": {"usage_count": 1, "last_used": 3000}}"#,
+    )
+    .unwrap();
+    // Files written before `first_used` existed fall back to `last_used`.
+    assert_eq!(stats::load_stats(&path)["test"].first_used, 3_000);
+
+    stats::track_command(&path, "build").unwrap();
+    stats::track_command(&path, "build").unwrap();
+    stats::track_command(&path, "new").unwrap();
+    let loaded = stats::load_stats(&path);
+    assert_eq!(loaded["build"].first_used, 1_000);
+    assert!(loaded["build"].last_used > 2_000);
+    assert_eq!(loaded["new"].first_used, loaded["new"].last_used);
+
+    let first_used = StatsColumns {
+        first_used: true,
+        ..Default::default()
+    };
+    let lines = stats::stats_report(&loaded, false, first_used);
+    let header = &lines[1];
+    assert!(header.find("First Used").unwrap() < header.find("Last Used").unwrap());
+    assert!(lines.iter().any(|line| line.contains("1970-01-01 00:16")));
+    assert!(
+        !stats::stats_report(&loaded, false, StatsColumns::default())[1].contains("First Used")
+    );
+}
+
+#[test]
+fn malformed_entries_are_skipped_and_the_rest_loads() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("command_stats.json");
+    std::fs::write(
+        &path,
+        r#"{"schema_version": 2, "tool_version": "0.1.0", "commands": {
+            "build": {"usage_count": 3, "last_used": 1000},
+            "test": {"usage_count": "many", "last_used": 1000},
+            "run": {"usage_count": 1, "last_used": 2000}}}"#,
+    )
+    .unwrap();
+    let loaded = stats::load_stats(&path);
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded["build"].usage_count, 3);
+    assert_eq!(loaded["run"].usage_count, 1);
+    assert!(!loaded.contains_key("test"));
+
+    // The same recovery applies to files from before the schema wrapper.
+    std::fs::write(
+        &path,
+        r#"{"build": {"usage_count": 2, "last_used": 0}, "check": [1, 2]}"#,
+    )
+    .unwrap();
+    let legacy = stats::read_stats_file(&path).unwrap();
+    assert_eq!(legacy.schema_version, 1);
+    assert_eq!(legacy.commands.len(), 1);
+
+    std::fs::write(&path, "not json").unwrap();
+    assert!(stats::load_stats(&path).is_empty());
+}
+
+#[test]
+fn saving_over_malformed_entries_keeps_a_backup_first() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("command_stats.json");
+    let original = r#"{"schema_version": 2, "tool_version": "0.1.0", "commands": {
+        "build": {"usage_count": 3, "last_used": 1000},
+        "test": {"usage_count": "many", "last_used": 1000}}}"#;
+    std::fs::write(&path, original).unwrap();
+
+    stats::track_command(&path, "build").unwrap();
+    let backups = stats::list_backups(&path);
+    assert_eq!(backups.len(), 1);
+    assert_eq!(std::fs::read_to_string(&backups[0]).unwrap(), original);
+    assert_eq!(stats::load_stats(&path)["build"].usage_count, 4);
+
+    // Once the dropped entries are in a backup, later saves don't make more.
+    stats::track_command(&path, "build").unwrap();
+    assert_eq!(stats::list_backups(&path).len(), 1);
+}
+
+#[test]
+fn last_exit_code_follows_the_latest_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("command_stats.json");
+    let second = std::time::Duration::from_secs(1);
+
+    stats::track_command_exit(&path, "run", Some(101), second).unwrap();
+    let loaded = stats::load_stats(&path);
+    assert_eq!(loaded["run"].last_exit_code, Some(101));
+    assert_eq!(loaded["run"].last_exit().as_deref(), Some("101"));
+    assert_eq!(loaded["run"].failure_count, 1);
+
+    stats::track_command_exit(&path, "run", Some(0), second).unwrap();
+    let loaded = stats::load_stats(&path);
+    assert_eq!(loaded["run"].last_exit_code, Some(0));
+    assert_eq!(loaded["run"].success_count, 1);
+
+    // Killed by a signal: no exit code.
+    stats::track_command_exit(&path, "run", None, second).unwrap();
+    let loaded = stats::load_stats(&path);
+    assert_eq!(loaded["run"].last_exit_code, None);
+    assert_eq!(loaded["run"].last_exit().as_deref(), Some("signal"));
+    assert_eq!(loaded["run"].failure_count, 2);
+    assert_eq!(loaded["run"].total_duration_ms, 3_000);
+
+    // Tracking without an exit code keeps the last one known.
+    stats::track_command_exit(&path, "run", Some(2), second).unwrap();
+    stats::track_command_result(&path, "run", true).unwrap();
+    let loaded = stats::load_stats(&path);
+    assert_eq!(loaded["run"].last_exit().as_deref(), Some("2"));
+
+    let verbose = StatsColumns {
+        first_used: true,
+        last_exit: true,
+    };
+    let lines = stats::stats_report(&loaded, false, verbose);
+    assert!(lines[1].trim_end().ends_with("Last Exit"), "{}", lines[1]);
+    assert!(lines[2].trim_end().ends_with(" 2"), "{}", lines[2]);
+}
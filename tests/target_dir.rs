@@ -0,0 +1,64 @@
+use cargo_sleek::target_dir;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn write(path: &Path, bytes: usize, age_days: u64) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, vec![0u8; bytes]).unwrap();
+    let mtime = SystemTime::now() - Duration::from_secs(age_days * 86_400);
+    fs::File::options()
+        .append(true)
+        .open(path)
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+}
+
+#[test]
+fn target_is_broken_down_and_old_artifacts_found() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path();
+    write(&target.join("debug/deps/libold.rlib"), 400, 90);
+    write(&target.join("debug/deps/libnew.rlib"), 100, 1);
+    write(&target.join("debug/incremental/app-1/s-old/query"), 300, 60);
+    write(&target.join("debug/app"), 50, 1);
+    write(
+        &target.join("x86_64-unknown-linux-gnu/release/deps/libx.rlib"),
+        70,
+        1,
+    );
+    write(&target.join("doc/index.html"), 20, 1);
+
+    let report = target_dir::scan_target(target);
+    assert_eq!(report.total, 940);
+    let names: Vec<_> = report.profiles.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["debug", "x86_64-unknown-linux-gnu/release"]);
+    let debug = &report.profiles[0];
+    assert_eq!(debug.bytes, 850);
+    assert_eq!(debug.areas[0].name, "deps");
+    assert_eq!(debug.areas[0].bytes, 500);
+    assert_eq!(report.other[0].name, "doc");
+
+    let cutoff = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        - 30 * 86_400;
+    let stale: Vec<_> = target_dir::find_stale(target, cutoff)
+        .into_iter()
+        .map(|entry| {
+            (
+                entry.path.strip_prefix(target).unwrap().to_path_buf(),
+                entry.bytes,
+            )
+        })
+        .collect();
+    assert_eq!(
+        stale,
+        [
+            (Path::new("debug/deps/libold.rlib").to_path_buf(), 400),
+            (Path::new("debug/incremental/app-1").to_path_buf(), 300),
+        ]
+    );
+}
@@ -0,0 +1,72 @@
+use cargo_sleek::history::{TestRunRecord, TestTime};
+use cargo_sleek::test_times;
+use std::time::Duration;
+
+const STABLE_OUTPUT: &str = "\
+     Running unittests src/lib.rs (target/debug/deps/demo-1234)
+
+running 2 tests
+test tests::quick ... ok
+test tests::slow ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 1.50s
+
+     Running tests/cli.rs (target/debug/deps/cli-5678)
+test result: ok. 4 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.25s
+
+   Doc-tests demo
+test result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+
+#[test]
+fn stable_output_yields_suite_times() {
+    let record = test_times::test_run_record(STABLE_OUTPUT, Duration::from_secs(9), 1);
+    assert_eq!(record.suite_ms, 1_750);
+    assert_eq!(record.duration_ms, 9_000);
+    let names: Vec<_> = record.slowest.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["unittests src/lib.rs", "tests/cli.rs", "doc-tests demo"]
+    );
+}
+
+#[test]
+fn per_test_times_come_from_report_time_and_json() {
+    let output = r#"
+test parser::handles_unicode ... ok <0.120s>
+test parser::empty ... ok <0.001s>
+{ "type": "test", "event": "ok", "name": "net::timeout", "exec_time": 2.5 }
+{ "type": "suite", "event": "ok", "passed": 3, "exec_time": 2.7 }
+"#;
+    let parsed = test_times::parse_test_output(output);
+    let tests: Vec<_> = parsed
+        .tests
+        .iter()
+        .map(|t| (t.name.as_str(), t.seconds))
+        .collect();
+    assert_eq!(
+        tests,
+        [
+            ("parser::handles_unicode", 0.12),
+            ("parser::empty", 0.001),
+            ("net::timeout", 2.5)
+        ]
+    );
+}
+
+#[test]
+fn tests_entering_the_top_list_are_flagged() {
+    let run = |names: &[&str]| TestRunRecord {
+        slowest: names
+            .iter()
+            .map(|name| TestTime {
+                name: name.to_string(),
+                seconds: 1.0,
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let previous = run(&["a", "b", "c"]);
+    let current = run(&["b", "d", "a"]);
+    assert_eq!(test_times::new_in_top(&current, &previous), ["d"]);
+}
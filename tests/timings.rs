@@ -0,0 +1,109 @@
+use cargo_sleek::timings;
+
+const REPORT: &str = r#"<script>
+DURATION = 9;
+const UNIT_DATA = [
+  {"i": 0, "name": "proc-macro2", "version": "1.0.86", "target": "", "start": 0.0, "duration": 2.0,
+   "rmeta_time": 1.5, "unblocked_units": [], "unblocked_rmeta_units": [1]},
+  {"i": 1, "name": "syn", "version": "2.0.70", "target": "", "start": 1.5, "duration": 5.0,
+   "rmeta_time": 4.0, "unblocked_units": [2], "unblocked_rmeta_units": []},
+  {"i": 2, "name": "app", "version": "0.1.0", "target": " app \"bin\"", "start": 6.5, "duration": 2.5,
+   "unblocked_units": [], "unblocked_rmeta_units": []},
+  {"i": 3, "name": "log", "version": "0.4.22", "target": "", "start": 0.0, "duration": 0.5,
+   "rmeta_time": 0.25, "unlocked_units": [2], "unlocked_rmeta_units": []}
+];
+const CONCURRENCY_DATA = [];
+</script>"#;
+
+#[test]
+fn html_report_yields_sorted_crates_with_critical_path() {
+    let units = timings::parse_timing_html(REPORT).unwrap();
+    assert_eq!(units.len(), 4);
+    assert_eq!(units[3].unblocked_units, [2]);
+
+    let crates = timings::crate_timings(&units);
+    let order: Vec<_> = crates.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(order, ["syn", "app", "proc-macro2", "log"]);
+
+    assert_eq!(crates[0].frontend, Some(4.0));
+    assert_eq!(crates[0].codegen, Some(1.0));
+    assert_eq!(crates[1].frontend, None);
+
+    let critical: Vec<_> = crates
+        .iter()
+        .filter(|c| c.critical)
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(critical, ["syn", "app", "proc-macro2"]);
+}
+
+#[test]
+fn timing_messages_are_read_from_json_stream() {
+    let stream = r#"{"reason":"compiler-artifact","package_id":"x"}
+{"reason":"timing-info","package_id":"serde 1.0.0","target":{"name":"serde","kind":["lib"]},"mode":"build","duration":3.5,"rmeta_time":2.0}"#;
+    let units = timings::parse_timing_messages(stream);
+    assert_eq!(units.len(), 1);
+    assert_eq!(units[0].name, "serde");
+    assert_eq!(units[0].rmeta_time, Some(2.0));
+}
+
+#[test]
+fn heaviest_direct_deps_keep_ranking_and_drop_transitive() {
+    let units = timings::parse_timing_html(REPORT).unwrap();
+    let crates = timings::crate_timings(&units);
+    // `syn` is only pulled in transitively; `app` is the workspace member itself.
+    let direct = vec!["log".to_string(), "proc-macro2".to_string()];
+
+    let ranked: Vec<_> = timings::heaviest_direct_deps(&crates, &direct)
+        .into_iter()
+        .map(|c| (c.name.as_str(), c.seconds))
+        .collect();
+    assert_eq!(ranked, [("proc-macro2", 2.0), ("log", 0.5)]);
+}
+
+#[test]
+fn sums_link_sections_when_present() {
+    let html = r#"const UNIT_DATA = [
+      {"i": 0, "name": "app", "duration": 4.0, "sections": [
+        ["frontend", {"start": 0.0, "end": 1.0}],
+        ["codegen", {"start": 1.0, "end": 2.5}],
+        ["link", {"start": 2.5, "end": 4.0}]]},
+      {"i": 1, "name": "log", "duration": 0.5, "sections": null}
+    ];"#;
+    let units = timings::parse_timing_html(html).unwrap();
+    assert_eq!(timings::link_seconds(&units), Some(1.5));
+
+    let units = timings::parse_timing_html(REPORT).unwrap();
+    assert_eq!(timings::link_seconds(&units), None);
+}
+
+#[test]
+fn newest_timing_report_is_picked_by_modification_time() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |name: &str, secs_ago: u64| {
+        let path = dir.path().join(name);
+        std::fs::write(&path, "<html></html>").unwrap();
+        let modified = std::time::SystemTime::now() - std::time::Duration::from_secs(secs_ago);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    };
+    // Names sort the other way round, so only the timestamps can pick the right one.
+    write("cargo-timing-20240101T000000Z.html", 10);
+    write("cargo-timing-20230101T000000Z.html", 5);
+    write("cargo-timing.html", 0);
+    write("notes.txt", 0);
+
+    let newest = timings::newest_timing_report(dir.path()).unwrap();
+    assert_eq!(
+        newest.file_name().unwrap(),
+        "cargo-timing-20230101T000000Z.html"
+    );
+    assert_eq!(
+        timings::newest_timing_report(&dir.path().join("missing")),
+        None
+    );
+}
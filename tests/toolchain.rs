@@ -0,0 +1,38 @@
+use cargo_sleek::performance::BuildOptions;
+use cargo_sleek::toolchain;
+
+const LIST: &str = "stable-x86_64-unknown-linux-gnu (active, default)
+nightly-2024-05-01-x86_64-unknown-linux-gnu
+1.74.0-x86_64-unknown-linux-gnu
+";
+
+#[test]
+fn matches_requested_toolchains_against_installed_ones() {
+    let installed = toolchain::parse_toolchain_list(LIST);
+    assert_eq!(installed.len(), 3);
+    assert_eq!(installed[0], "stable-x86_64-unknown-linux-gnu");
+
+    assert!(toolchain::is_installed("stable", &installed));
+    assert!(toolchain::is_installed("1.74.0", &installed));
+    assert!(toolchain::is_installed("nightly-2024-05-01", &installed));
+    assert!(toolchain::is_installed(
+        "stable-x86_64-unknown-linux-gnu",
+        &installed
+    ));
+    assert!(!toolchain::is_installed("beta", &installed));
+    assert!(!toolchain::is_installed("1.74", &installed));
+}
+
+#[test]
+fn toolchain_builds_run_through_rustup() {
+    let options = BuildOptions {
+        toolchain: Some("beta".into()),
+        release: true,
+        ..Default::default()
+    };
+    let cmd = options.command();
+    assert_eq!(cmd.get_program(), "rustup");
+    let args: Vec<_> = cmd.get_args().map(|a| a.to_string_lossy()).collect();
+    assert_eq!(args[..4], ["run", "beta", "cargo", "build"]);
+    assert!(args.iter().any(|a| a == "--release"));
+}
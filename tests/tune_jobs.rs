@@ -0,0 +1,36 @@
+use cargo_sleek::tune_jobs::{self, JobsResult};
+use std::time::Duration;
+
+fn result(jobs: usize, secs: &[u64]) -> JobsResult {
+    JobsResult {
+        jobs,
+        samples: secs.iter().map(|s| Duration::from_secs(*s)).collect(),
+    }
+}
+
+#[test]
+fn fastest_median_is_recommended() {
+    let results = [
+        result(4, &[30, 31, 29]),
+        result(6, &[25, 40, 24]),
+        result(8, &[25, 26, 24]),
+    ];
+    assert_eq!(results[1].median(), Duration::from_secs(25));
+    // 6 and 8 tie on the median; fewer jobs wins.
+    assert_eq!(tune_jobs::recommend(&results), Some(6));
+    assert_eq!(tune_jobs::recommend(&[result(2, &[])]), None);
+    assert_eq!(tune_jobs::default_job_counts(8), [4, 6, 7, 8, 10]);
+    assert_eq!(tune_jobs::default_job_counts(1), [1, 3]);
+}
+
+#[test]
+fn applying_jobs_keeps_existing_config() {
+    let config = "# shared settings\n[build]\ntarget-dir = \"/tmp/t\" # fast disk\n\n[alias]\nb = \"build\"\n";
+    let updated = tune_jobs::apply_jobs(config, 6).unwrap();
+    assert!(updated.contains("# shared settings"));
+    assert!(updated.contains("target-dir = \"/tmp/t\" # fast disk"));
+    assert!(updated.contains("jobs = 6"));
+    assert!(updated.contains("[alias]"));
+
+    assert_eq!(tune_jobs::apply_jobs("", 4).unwrap(), "[build]\njobs = 4\n");
+}
@@ -0,0 +1,73 @@
+use cargo_sleek::usage::{self, RefKind};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn scan_usage_reports_file_and_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/main.rs"),
+        "// serde_json is great\nuse std::fs;\n\nfn main() {\n    let v = serde_json::json!({});\n}\n",
+    )
+    .unwrap();
+
+    let deps = vec!["serde-json".to_string(), "rand".to_string()];
+    let found = usage::scan_usage(root, &deps).unwrap();
+
+    assert_eq!(found["serde-json"].len(), 1);
+    let reference = &found["serde-json"][0];
+    assert_eq!(reference.file, PathBuf::from("src/main.rs"));
+    assert_eq!(reference.line, 5);
+    assert_eq!(reference.kind, RefKind::Main);
+    assert!(found["rand"].is_empty());
+}
+
+#[test]
+fn build_script_and_macro_usage_are_detected() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("build.rs"),
+        "fn main() {\n    cc::Build::new();\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "#[derive(Debug, Serialize)]\n#[serde(rename_all = \"camelCase\")]\npub struct A;\n\n#[async_trait]\nimpl B for A {}\n",
+    )
+    .unwrap();
+
+    let deps = vec![
+        "cc".to_string(),
+        "serde".to_string(),
+        "async-trait".to_string(),
+        "bindgen".to_string(),
+    ];
+    let found = usage::scan_usage(root, &deps).unwrap();
+
+    assert_eq!(found["cc"].len(), 1);
+    assert_eq!(found["cc"][0].kind, RefKind::Build);
+    let serde_lines: Vec<_> = found["serde"].iter().map(|r| r.line).collect();
+    assert_eq!(serde_lines, [1, 2]);
+    assert_eq!(found["async-trait"][0].line, 5);
+    assert!(found["bindgen"].is_empty());
+}
+
+#[test]
+fn attribute_crates_maps_derives_and_attribute_paths() {
+    assert_eq!(
+        usage::attribute_crates("#[derive(Clone, serde::Deserialize, Error)]"),
+        ["serde", "thiserror"]
+    );
+    assert_eq!(
+        usage::attribute_crates("#[tokio::main]"),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        usage::attribute_crates("#[instrument(skip(self))]"),
+        ["tracing"]
+    );
+}
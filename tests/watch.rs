@@ -0,0 +1,112 @@
+use cargo_sleek::watch::{self, WatchEvent, WatchFilter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[test]
+fn rebuild_event_is_emitted_as_one_json_line() {
+    let mut out = Vec::new();
+    let event = WatchEvent::rebuild("src/main.rs", true, Duration::from_millis(1234));
+    watch::emit_json_line(&mut out, &event).unwrap();
+
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.ends_with('\n'));
+    assert_eq!(text.lines().count(), 1);
+
+    let value: serde_json::Value = serde_json::from_str(text.trim_end()).unwrap();
+    assert_eq!(value["event"], "rebuild");
+    assert_eq!(value["trigger"], "src/main.rs");
+    assert_eq!(value["success"], true);
+    assert_eq!(value["duration_ms"], 1234);
+    assert!(value.get("restarted").is_none());
+}
+
+#[test]
+fn start_and_restarted_events_carry_only_their_fields() {
+    let start = serde_json::to_value(WatchEvent::start("src/lib.rs")).unwrap();
+    assert_eq!(
+        start,
+        serde_json::json!({ "event": "start", "trigger": "src/lib.rs" })
+    );
+
+    let stopped =
+        serde_json::to_value(WatchEvent::restarted("start", Duration::from_millis(40))).unwrap();
+    assert_eq!(
+        stopped,
+        serde_json::json!({
+            "event": "rebuild",
+            "trigger": "start",
+            "success": false,
+            "duration_ms": 40,
+            "restarted": true,
+        })
+    );
+}
+
+#[test]
+fn watch_globs_decide_which_paths_trigger_rebuilds() {
+    let defaults = WatchFilter::new(&[]).unwrap();
+    for path in [
+        "src/main.rs",
+        "crates/core/src/lib.rs",
+        "Cargo.toml",
+        "build.rs",
+    ] {
+        assert!(defaults.triggers_rebuild(Path::new(path)), "{path}");
+    }
+    for path in [
+        "target/debug/build/out.rs",
+        "crates/core/target/x.rs",
+        ".git/config.rs",
+        "src/.cache/gen.rs",
+        "README.md",
+    ] {
+        assert!(!defaults.triggers_rebuild(Path::new(path)), "{path}");
+    }
+
+    let custom = WatchFilter::new(&["src/**/*.rs".to_string(), "Cargo.toml".to_string()]).unwrap();
+    assert!(custom.triggers_rebuild(Path::new("src/bin/tool.rs")));
+    assert!(custom.triggers_rebuild(Path::new("Cargo.toml")));
+    assert!(!custom.triggers_rebuild(Path::new("crates/core/Cargo.toml")));
+    assert!(!custom.triggers_rebuild(Path::new("tests/cli.rs")));
+
+    assert!(WatchFilter::new(&["src/[".to_string()]).is_err());
+}
+
+#[test]
+fn extra_watch_paths_and_ignores_shape_the_filter() {
+    let globs = watch::watch_globs(
+        &[],
+        &[PathBuf::from("./assets/"), PathBuf::from("build.rs")],
+    );
+    let filter = WatchFilter::new(&globs)
+        .unwrap()
+        .ignoring(&["src/generated/**".to_string()])
+        .unwrap();
+    assert!(filter.triggers_rebuild(Path::new("src/main.rs")));
+    assert!(filter.triggers_rebuild(Path::new("assets/logo.svg")));
+    assert!(filter.triggers_rebuild(Path::new("build.rs")));
+    assert!(!filter.triggers_rebuild(Path::new("src/generated/schema.rs")));
+    assert!(!filter.triggers_rebuild(Path::new("target/debug/app.rs")));
+    assert!(!filter.triggers_rebuild(Path::new("docs/guide.md")));
+
+    // Explicit globs replace the defaults and extra paths.
+    let explicit = watch::watch_globs(&["src/**".to_string()], &[PathBuf::from("assets")]);
+    assert_eq!(explicit, vec!["src/**".to_string()]);
+}
+
+#[test]
+fn changed_paths_are_made_relative_to_the_project() {
+    let root = Path::new("/work/app");
+    assert_eq!(
+        watch::relative_to(root, Path::new("/work/app/src/lib.rs")),
+        PathBuf::from("src/lib.rs")
+    );
+    assert_eq!(
+        watch::relative_to(root, Path::new("./Cargo.toml")),
+        PathBuf::from("Cargo.toml")
+    );
+    assert_eq!(
+        watch::relative_to(root, Path::new("src/main.rs")),
+        PathBuf::from("src/main.rs")
+    );
+}
@@ -0,0 +1,50 @@
+use cargo_sleek::workspace;
+use std::fs;
+use std::path::Path;
+
+fn write(root: &Path, relative: &str, content: &str) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn member(root: &Path, name: &str, deps: &str) {
+    write(
+        root,
+        &format!("{name}/Cargo.toml"),
+        &format!(
+            "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n{deps}"
+        ),
+    );
+    write(root, &format!("{name}/src/lib.rs"), "");
+}
+
+#[test]
+fn local_pins_are_reported_and_fixed() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"a\", \"b\", \"c\"]\nresolver = \"2\"\n\n[workspace.dependencies]\nserde = \"1.0.200\"\n",
+    );
+    member(root, "a", "serde = \"1.0.150\"\n");
+    member(
+        root,
+        "b",
+        "serde = { version = \"1.0.200\", features = [\"derive\"] }\n",
+    );
+    member(root, "c", "serde = { workspace = true }\n");
+
+    let findings = workspace::find_inheritance_issues(root).unwrap();
+    let summary: Vec<_> = findings
+        .iter()
+        .map(|f| (f.member.as_str(), f.differs(), f.eligible))
+        .collect();
+    assert_eq!(summary, [("a", true, true), ("b", false, true)]);
+
+    assert_eq!(workspace::apply_inheritance(&findings).unwrap(), 2);
+    let b = fs::read_to_string(root.join("b/Cargo.toml")).unwrap();
+    assert!(b.contains(r#"serde = { workspace = true, features = ["derive"] }"#));
+    assert!(workspace::find_inheritance_issues(root).unwrap().is_empty());
+}
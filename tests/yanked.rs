@@ -0,0 +1,58 @@
+use cargo_sleek::index::{self, IndexEntry};
+use cargo_sleek::yanked;
+
+fn entry(vers: &str, yanked: bool) -> IndexEntry {
+    IndexEntry {
+        name: "demo".to_string(),
+        vers: vers.to_string(),
+        yanked,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn index_paths_follow_sparse_layout() {
+    assert_eq!(index::index_path("a"), "1/a");
+    assert_eq!(index::index_path("cc"), "2/cc");
+    assert_eq!(index::index_path("syn"), "3/s/syn");
+    assert_eq!(index::index_path("Serde"), "se/rd/serde");
+}
+
+#[test]
+fn nearest_compatible_prefers_smallest_upgrade() {
+    let entries = vec![
+        entry("1.0.0", false),
+        entry("1.0.1", true),
+        entry("1.0.2", false),
+        entry("1.1.0", false),
+        entry("2.0.0", false),
+    ];
+    assert_eq!(
+        yanked::nearest_compatible("1.0.1", &entries),
+        Some("1.0.2".to_string())
+    );
+
+    let only_older = vec![
+        entry("0.3.0", false),
+        entry("0.3.1", true),
+        entry("0.4.0", false),
+    ];
+    assert_eq!(
+        yanked::nearest_compatible("0.3.1", &only_older),
+        Some("0.3.0".to_string())
+    );
+}
+
+#[test]
+fn index_features_include_implicit_optional_deps() {
+    let entries = index::parse_index(
+        r#"{"name":"demo","vers":"1.0.0","features":{"default":["std"],"std":[]},"features2":{"tls":["dep:rustls"]},"deps":[{"name":"rustls","optional":true},{"name":"log","optional":true},{"name":"libc","optional":false}]}"#,
+    );
+    let entry = &entries[0];
+    assert!(entry.has_feature("std"));
+    assert!(entry.has_feature("tls"));
+    assert!(entry.has_feature("log"));
+    assert!(!entry.has_feature("rustls"));
+    assert!(!entry.has_feature("libc"));
+    assert!(!entry.has_feature("simd"));
+}